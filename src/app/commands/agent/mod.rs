@@ -0,0 +1,56 @@
+// Solwalrs, A simple and easy to use CLI Solana wallet
+// Copyright (C) 2022  Solwalrs contributors <https://github.com/TheAwiteb/solwalrs/graphs/contributors>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/gpl-3.0.html>.
+
+mod serve;
+mod start;
+mod status;
+mod stop;
+
+pub use serve::ServeCommand;
+pub use start::StartCommand;
+pub use status::StatusCommand;
+pub use stop::StopCommand;
+
+use clap::Subcommand;
+
+use crate::{app::AppArgs, errors::Result as SolwalrsResult};
+
+/// Commands for managing the session agent, it holds the decrypted wallet
+/// so the password is entered once per session
+#[derive(Subcommand, Debug)]
+pub enum AgentCommand {
+    Start(StartCommand),
+    #[clap(visible_alias = "lock")]
+    Stop(StopCommand),
+    Status(StatusCommand),
+    #[clap(hide = true)]
+    Serve(ServeCommand),
+}
+
+impl AgentCommand {
+    /// Run the command
+    pub fn run(&self, args: &AppArgs) -> SolwalrsResult<()> {
+        use AgentCommand::*;
+
+        crate::info!(args, "The agent command is: {self:?}");
+        match self {
+            Start(command) => command.run(args),
+            Stop(command) => command.run(args),
+            Status(command) => command.run(args),
+            Serve(command) => command.run(args),
+        }
+    }
+}
@@ -0,0 +1,63 @@
+// Solwalrs, A simple and easy to use CLI Solana wallet
+// Copyright (C) 2022  Solwalrs contributors <https://github.com/TheAwiteb/solwalrs/graphs/contributors>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/gpl-3.0.html>.
+
+use std::io::{self, BufRead, Write};
+use std::time::Duration;
+
+use clap::Parser;
+use zeroize::Zeroizing;
+
+use crate::app::AppArgs;
+use crate::errors::{Error as SolwalrsError, Result as SolwalrsResult};
+use crate::wallet::{Agent, DEFAULT_IDLE_TIMEOUT};
+
+/// Serve the agent in the foreground, the password is read from stdin.
+/// This is used by `agent start`, `ok` is printed when the agent is ready
+#[derive(Parser, Debug)]
+pub struct ServeCommand {
+    /// Lock the wallet and stop the agent after this many seconds without a request
+    #[clap(short, long, default_value_t = DEFAULT_IDLE_TIMEOUT)]
+    pub idle_timeout: u64,
+}
+
+impl ServeCommand {
+    /// Serve the agent until it's stopped or idle
+    pub fn run(&self, args: &AppArgs) -> SolwalrsResult<()> {
+        let mut password = Zeroizing::new(String::new());
+        io::stdin()
+            .lock()
+            .read_line(&mut password)
+            .map_err(|err| SolwalrsError::IO(format!("Failed to read the password: {err}")))?;
+        // Trim the newline in place, so the password isn't copied
+        let len = password.trim_end_matches(['\r', '\n']).len();
+        password.truncate(len);
+
+        let result =
+            Agent::new(password, Duration::from_secs(self.idle_timeout), args).and_then(|agent| {
+                agent.serve(|socket| {
+                    crate::info!(args, "The agent is listening on `{}`", socket.display());
+                    let mut stdout = io::stdout();
+                    let _ = writeln!(stdout, "ok");
+                    let _ = stdout.flush();
+                })
+            });
+        if let Err(err) = &result {
+            // The `agent start` command is waiting for the first line
+            let _ = writeln!(io::stdout(), "error: {err}");
+        }
+        result
+    }
+}
@@ -0,0 +1,95 @@
+// Solwalrs, A simple and easy to use CLI Solana wallet
+// Copyright (C) 2022  Solwalrs contributors <https://github.com/TheAwiteb/solwalrs/graphs/contributors>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/gpl-3.0.html>.
+
+use std::io::{BufRead, BufReader, Write};
+use std::process::{Command, Stdio};
+
+use clap::Parser;
+
+use crate::app::AppArgs;
+use crate::errors::{Error as SolwalrsError, Result as SolwalrsResult};
+use crate::utils;
+use crate::wallet::{app_file_path, AgentClient, Wallet, DEFAULT_IDLE_TIMEOUT};
+
+/// Start the agent of the selected wallet, the wallet will be locked after the idle timeout
+#[derive(Parser, Debug)]
+pub struct StartCommand {
+    /// Lock the wallet and stop the agent after this many seconds without a request
+    #[clap(short, long, default_value_t = DEFAULT_IDLE_TIMEOUT)]
+    pub idle_timeout: u64,
+}
+
+impl StartCommand {
+    /// Start the agent in the background, the password is sent to it over its stdin
+    pub fn run(&self, args: &AppArgs) -> SolwalrsResult<()> {
+        if AgentClient::connect(args)?.is_some() {
+            return Err(SolwalrsError::Other(
+                "The agent is already running for this wallet".to_owned(),
+            ));
+        }
+        let password = utils::get_password(args)?;
+        // Check the password before starting the agent
+        Wallet::load(&password, args)?;
+
+        let app_file = app_file_path(args)?;
+        let exe = std::env::current_exe()
+            .map_err(|err| SolwalrsError::IO(format!("Failed to get the executable: {err}")))?;
+        let mut command = Command::new(exe);
+        command
+            .arg("--app-file")
+            .arg(&app_file)
+            .args(["agent", "serve", "--idle-timeout"])
+            .arg(self.idle_timeout.to_string())
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null());
+        // Run the agent in its own process group, so it's not killed with the terminal job
+        #[cfg(unix)]
+        std::os::unix::process::CommandExt::process_group(&mut command, 0);
+        crate::info!(args, "Starting the agent: {command:?}");
+        let mut child = command
+            .spawn()
+            .map_err(|err| SolwalrsError::IO(format!("Failed to start the agent: {err}")))?;
+
+        // SAFETY: The stdin and stdout are piped
+        let mut stdin = child.stdin.take().unwrap();
+        stdin
+            .write_all(password.as_bytes())
+            .and_then(|_| stdin.write_all(b"\n"))
+            .map_err(|err| {
+                SolwalrsError::IO(format!("Failed to send the password to the agent: {err}"))
+            })?;
+        drop(stdin);
+        let mut ready = String::new();
+        BufReader::new(child.stdout.take().unwrap())
+            .read_line(&mut ready)
+            .map_err(|err| SolwalrsError::IO(format!("Failed to read from the agent: {err}")))?;
+        if ready.trim() != "ok" {
+            let _ = child.wait();
+            return Err(SolwalrsError::Other(format!(
+                "Failed to start the agent: {}",
+                ready.trim().trim_start_matches("error: ")
+            )));
+        }
+        println!(
+            "The agent is started (pid {}), the wallet `{}` will be locked after {} seconds of inactivity",
+            child.id(),
+            app_file.display(),
+            self.idle_timeout
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,48 @@
+// Solwalrs, A simple and easy to use CLI Solana wallet
+// Copyright (C) 2022  Solwalrs contributors <https://github.com/TheAwiteb/solwalrs/graphs/contributors>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/gpl-3.0.html>.
+
+use clap::Parser;
+
+use crate::app::AppArgs;
+use crate::errors::Result as SolwalrsResult;
+use crate::wallet::{print_table, AgentClient};
+
+/// Show the status of the agent of the selected wallet
+#[derive(Parser, Debug)]
+pub struct StatusCommand;
+
+impl StatusCommand {
+    /// Print the agent status, checking the status doesn't reset the idle timeout
+    pub fn run(&self, args: &AppArgs) -> SolwalrsResult<()> {
+        let Some(agent) = AgentClient::connect(args)? else {
+            println!("The agent isn't running, start it with `solwalrs agent start`");
+            return Ok(());
+        };
+        let status = agent.status()?;
+        crate::info!(args, "The agent status: {status:?}");
+        print_table(
+            vec!["Pid", "Wallet file", "Keypairs", "Idle timeout", "Locks in"],
+            vec![vec![
+                &status.pid.to_string(),
+                &status.wallet_file,
+                &status.keypairs.to_string(),
+                &format!("{} seconds", status.idle_timeout),
+                &format!("{} seconds", status.locks_in),
+            ]],
+        );
+        Ok(())
+    }
+}
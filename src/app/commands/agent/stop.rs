@@ -0,0 +1,39 @@
+// Solwalrs, A simple and easy to use CLI Solana wallet
+// Copyright (C) 2022  Solwalrs contributors <https://github.com/TheAwiteb/solwalrs/graphs/contributors>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/gpl-3.0.html>.
+
+use clap::Parser;
+
+use crate::app::AppArgs;
+use crate::errors::Result as SolwalrsResult;
+use crate::wallet::AgentClient;
+
+/// Lock the wallet and stop the agent of the selected wallet
+#[derive(Parser, Debug)]
+pub struct StopCommand;
+
+impl StopCommand {
+    /// Stop the agent, the decrypted wallet is zeroized
+    pub fn run(&self, args: &AppArgs) -> SolwalrsResult<()> {
+        match AgentClient::connect(args)? {
+            Some(agent) => {
+                agent.stop()?;
+                println!("The agent is stopped, the wallet is locked");
+            }
+            None => println!("The agent isn't running"),
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,114 @@
+// Solwalrs, A simple and easy to use CLI Solana wallet
+// Copyright (C) 2022  Solwalrs contributors <https://github.com/TheAwiteb/solwalrs/graphs/contributors>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/gpl-3.0.html>.
+
+use std::path::PathBuf;
+
+use clap::Parser;
+
+use crate::app::AppArgs;
+use crate::errors::{Error as SolwalrsError, Result as SolwalrsResult};
+use crate::utils;
+use crate::wallet::{app_file_path, short_public_key, split_secret, SecretKind, Wallet};
+
+/// Split a keypair or the wallet password into Shamir shares, any `--threshold` of them recover it.
+///
+/// Without `--keypair` the wallet password is split, the shares and the wallet file (or a copy of it,
+/// see `solwalrs restore wallet`) restore the whole wallet. Use `solwalrs restore shamir` to recover the secret
+#[derive(Parser, Debug)]
+pub struct ShamirCommand {
+    /// The number of the shares needed to recover the secret
+    #[clap(short, long, value_parser = clap::value_parser!(u8).range(2..))]
+    pub threshold: u8,
+    /// The number of the shares to create
+    #[clap(short, long, value_parser = clap::value_parser!(u8).range(2..))]
+    pub shares: u8,
+    /// The keypair to split, the wallet password will be split if not provided
+    #[clap(short, long)]
+    pub keypair: Option<String>,
+    /// Print the QR code of every share
+    #[clap(long)]
+    pub qr: bool,
+    /// The directory to save the QR code images of the shares to, as `share-<index>.png`
+    #[clap(short, long)]
+    pub output: Option<PathBuf>,
+}
+
+impl ShamirCommand {
+    /// Run the command
+    pub fn run(&self, wallet: &mut Wallet, args: &AppArgs) -> SolwalrsResult<()> {
+        let (kind, secret, description) = if let Some(name) = &self.keypair {
+            let keypair = wallet.get_keypair(name, args)?;
+            (
+                SecretKind::KeyPair,
+                keypair.keypair_bytes(),
+                format!(
+                    "the keypair `{name}` ({})",
+                    short_public_key(&keypair.public_key)
+                ),
+            )
+        } else {
+            let app_file = app_file_path(args)?;
+            if !app_file.exists() {
+                return Err(SolwalrsError::Wallet(format!(
+                    "There is no wallet file at `{}`",
+                    app_file.display()
+                )));
+            }
+            let password = utils::get_password(args)?;
+            // Make sure it's the password of the wallet, before splitting it
+            Wallet::load(&password, args)?;
+            (
+                SecretKind::Password,
+                zeroize::Zeroizing::new(password.as_bytes().to_vec()),
+                "the wallet password".to_owned(),
+            )
+        };
+        crate::info!(
+            args,
+            "Splitting {description} into {} shares, the threshold is {}",
+            self.shares,
+            self.threshold
+        );
+        let shares = split_secret(kind, &secret, self.threshold, self.shares)?;
+        if let Some(output) = &self.output {
+            std::fs::create_dir_all(output).map_err(|err| {
+                SolwalrsError::IO(format!("Failed to create the output directory: {err}"))
+            })?;
+        }
+
+        println!(
+            "Split {description} into {} shares, any {} of them recover it.",
+            self.shares, self.threshold
+        );
+        println!("Store every share in a different place, they are as secret as the {kind}.\n");
+        for share in &shares {
+            let encoded = share.encode();
+            println!("Share {}/{}: {}", share.index(), self.shares, *encoded);
+            if self.qr || self.output.is_some() {
+                let qr_code = utils::qr_code(&encoded)?;
+                if self.qr {
+                    println!("\n{}\n", utils::render_qr_code(&qr_code));
+                }
+                if let Some(output) = &self.output {
+                    let path = output.join(format!("share-{}.png", share.index()));
+                    utils::save_qr_code(&qr_code, &path, args)?;
+                    println!("Saved the QR code to `{}`", path.display());
+                }
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,48 @@
+// Solwalrs, A simple and easy to use CLI Solana wallet
+// Copyright (C) 2022  Solwalrs contributors <https://github.com/TheAwiteb/solwalrs/graphs/contributors>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/gpl-3.0.html>.
+
+use clap::Parser;
+
+use crate::app::AppArgs;
+use crate::errors::Result as SolwalrsResult;
+use crate::wallet::{Contact, Wallet};
+
+/// Add a contact to the address book
+#[derive(Parser, Debug)]
+pub struct AddCommand {
+    /// The alias of the contact, use it as `@alias` in place of the address
+    pub alias: String,
+    /// The address of the contact
+    pub address: String,
+    /// A note about the contact
+    #[clap(short, long)]
+    pub note: Option<String>,
+}
+
+impl AddCommand {
+    /// Add a contact to the address book
+    /// Note: You need to export the wallet after running this command, using `Wallet::export`
+    pub fn run(&self, wallet: &mut Wallet, args: &AppArgs) -> SolwalrsResult<()> {
+        let contact = Contact::new(&self.alias, &self.address, self.note.clone())?;
+        let message = format!(
+            "Contact `@{}` added successfully, its address is `{}`",
+            contact.alias, contact.address
+        );
+        wallet.add_contact(contact, args)?;
+        println!("{message}");
+        Ok(())
+    }
+}
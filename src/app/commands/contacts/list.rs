@@ -0,0 +1,54 @@
+// Solwalrs, A simple and easy to use CLI Solana wallet
+// Copyright (C) 2022  Solwalrs contributors <https://github.com/TheAwiteb/solwalrs/graphs/contributors>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/gpl-3.0.html>.
+
+use clap::Parser;
+
+use crate::app::AppArgs;
+use crate::errors::Result as SolwalrsResult;
+use crate::wallet::{print_table, Wallet};
+
+/// List the contacts of the address book
+#[derive(Parser, Debug)]
+pub struct ListCommand;
+
+impl ListCommand {
+    /// List the contacts of the address book
+    pub fn run(&self, wallet: &mut Wallet, args: &AppArgs) -> SolwalrsResult<()> {
+        crate::info!(args, "Listing {} contacts", wallet.contacts.len());
+        if wallet.contacts.is_empty() {
+            println!("No contacts found");
+            return Ok(());
+        }
+        let rows: Vec<_> = wallet
+            .contacts
+            .iter()
+            .map(|contact| {
+                vec![
+                    format!("@{}", contact.alias),
+                    contact.address.to_string(),
+                    contact.note.clone().unwrap_or_default(),
+                ]
+            })
+            .collect();
+        print_table(
+            vec!["Alias", "Address", "Note"],
+            rows.iter()
+                .map(|r| r.iter().map(|s| s.as_str()).collect())
+                .collect(),
+        );
+        Ok(())
+    }
+}
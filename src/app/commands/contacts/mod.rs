@@ -0,0 +1,59 @@
+// Solwalrs, A simple and easy to use CLI Solana wallet
+// Copyright (C) 2022  Solwalrs contributors <https://github.com/TheAwiteb/solwalrs/graphs/contributors>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/gpl-3.0.html>.
+
+mod add;
+mod list;
+mod remove;
+mod rename;
+
+pub use add::AddCommand;
+pub use list::ListCommand;
+pub use remove::RemoveCommand;
+pub use rename::RenameCommand;
+
+use clap::Subcommand;
+
+use crate::{app::AppArgs, errors::Result as SolwalrsResult, wallet::Wallet};
+
+/// Commands for managing the address book, named external addresses.
+///
+/// Use `@alias` in place of an address to use a contact
+#[derive(Subcommand, Debug)]
+pub enum ContactsCommand {
+    #[clap(visible_alias = "a")]
+    Add(AddCommand),
+    #[clap(visible_alias = "ls")]
+    List(ListCommand),
+    #[clap(visible_alias = "rm")]
+    Remove(RemoveCommand),
+    Rename(RenameCommand),
+}
+
+impl ContactsCommand {
+    /// Run the command
+    pub fn run(&self, wallet: &mut Wallet, args: &AppArgs) -> SolwalrsResult<()> {
+        use ContactsCommand::*;
+
+        crate::info!(args, "The contacts command is: {self:?}");
+        match self {
+            Add(command) => command.run(wallet, args)?,
+            List(command) => command.run(wallet, args)?,
+            Remove(command) => command.run(wallet, args)?,
+            Rename(command) => command.run(wallet, args)?,
+        };
+        Ok(())
+    }
+}
@@ -18,27 +18,23 @@ use clap::Parser;
 
 use crate::app::AppArgs;
 use crate::errors::Result as SolwalrsResult;
-use crate::wallet::cache::Cache;
-use crate::wallet::{Price, Tokens};
+use crate::wallet::Wallet;
 
-/// Get the price of a token/SOL in USDT
-#[derive(Debug, Parser)]
-pub struct PriceCommand {
-    /// The name of the keypair to get the balance of (defaults to the default keypair)
-    pub name: Option<String>,
-    /// SPL token to get the price of
-    #[clap(long)]
-    pub spl: Option<Tokens>,
+/// Remove a contact from the address book
+#[derive(Parser, Debug)]
+pub struct RemoveCommand {
+    /// The alias of the contact
+    pub alias: String,
 }
 
-impl PriceCommand {
-    pub fn run(&self, args: &AppArgs, cache: &mut Cache) -> SolwalrsResult<()> {
-        let price = Price::get_price(self.spl.as_ref(), args, cache)?;
+impl RemoveCommand {
+    /// Remove a contact from the address book
+    /// Note: You need to export the wallet after running this command, using `Wallet::export`
+    pub fn run(&self, wallet: &mut Wallet, args: &AppArgs) -> SolwalrsResult<()> {
+        let contact = wallet.remove_contact(&self.alias, args)?;
         println!(
-            "{}: ${}, Price change in the last 24h: {}",
-            self.spl.as_ref().map(|t| t.name()).unwrap_or("SOL"),
-            price.data.price,
-            price.data.price_change_24h
+            "Contact `@{}` ({}) removed successfully",
+            contact.alias, contact.address
         );
         Ok(())
     }
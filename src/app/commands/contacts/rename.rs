@@ -0,0 +1,44 @@
+// Solwalrs, A simple and easy to use CLI Solana wallet
+// Copyright (C) 2022  Solwalrs contributors <https://github.com/TheAwiteb/solwalrs/graphs/contributors>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/gpl-3.0.html>.
+
+use clap::Parser;
+
+use crate::app::AppArgs;
+use crate::errors::Result as SolwalrsResult;
+use crate::wallet::Wallet;
+
+/// Rename a contact of the address book
+#[derive(Parser, Debug)]
+pub struct RenameCommand {
+    /// The current alias of the contact
+    pub old_alias: String,
+    /// The new alias of the contact
+    pub new_alias: String,
+}
+
+impl RenameCommand {
+    /// Rename a contact of the address book
+    /// Note: You need to export the wallet after running this command, using `Wallet::export`
+    pub fn run(&self, wallet: &mut Wallet, args: &AppArgs) -> SolwalrsResult<()> {
+        wallet.rename_contact(&self.old_alias, &self.new_alias, args)?;
+        println!(
+            "Contact `@{}` renamed to `@{}` successfully",
+            self.old_alias.trim_start_matches('@'),
+            self.new_alias.trim_start_matches('@')
+        );
+        Ok(())
+    }
+}
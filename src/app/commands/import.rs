@@ -18,11 +18,14 @@ use clap::Parser;
 
 use crate::app::AppArgs;
 use crate::errors::Result as SolwalrsResult;
-use crate::wallet::{short_public_key, ImportType, KeyPair, Wallet};
+use crate::wallet::{
+    decrypt_paper_secret, short_public_key, ImportType, KeyPair, Wallet, PAPER_SECRET_PREFIX,
+};
+use zeroize::Zeroizing;
 
 /// Import new keypair by private key or secret key (input prompt).
 ///
-/// base58 encoded or bytes array.
+/// base58 encoded or bytes array, or the encrypted secret of a paper wallet.
 #[derive(Parser, Debug)]
 pub struct ImportCommand {
     /// The name of the keypair
@@ -37,11 +40,22 @@ impl ImportCommand {
     /// This function will prompt the user to enter the private key or secret key.
     pub fn run(&self, wallet: &mut Wallet, args: &AppArgs) -> SolwalrsResult<()> {
         crate::info!(args, "Importing keypair `{}`", self.name);
-        let import_type = ImportType::parse(
+        let input =
             rpassword::prompt_password("Enter the private key or secret key: ").map_err(|err| {
                 crate::errors::Error::Other(format!("Faild to read from stdin: {err}"))
-            })?,
-        )?;
+            })?;
+        let import_type = if input.trim().starts_with(PAPER_SECRET_PREFIX) {
+            // The encrypted secret of a paper wallet
+            let input = Zeroizing::new(input);
+            let passphrase = Zeroizing::new(
+                rpassword::prompt_password("Enter the passphrase of the paper wallet: ").map_err(
+                    |err| crate::errors::Error::Other(format!("Faild to read from stdin: {err}")),
+                )?,
+            );
+            ImportType::from_bytes(decrypt_paper_secret(&input, &passphrase)?)?
+        } else {
+            ImportType::parse(input)?
+        };
 
         let keypair = KeyPair::import(&self.name, import_type, self.default, args)?;
 
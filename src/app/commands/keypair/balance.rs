@@ -19,7 +19,7 @@ use clap::Parser;
 use crate::app::GetKeypairName;
 use crate::errors::Result as SolwalrsResult;
 use crate::wallet::cache::Cache;
-use crate::wallet::{short_public_key, Price, Tokens};
+use crate::wallet::{short_public_key, Currency, Price, Tokens};
 use crate::{app::AppArgs, wallet::Wallet};
 
 /// Get the balance of a keypair, SOL/SPL
@@ -50,18 +50,27 @@ impl BalanceCommand {
             .as_ref()
             .map(Tokens::lamports_per_token)
             .unwrap_or(1e9);
-        let price = Price::get_price(self.spl.as_ref(), args, cache)?.data.price
-            * (balance as f64 / per_one);
+        let currency = Currency::selected(args);
+        let value = currency.format(
+            currency.convert(
+                Price::get_price(&self.spl.as_ref().into(), args, cache)?
+                    .data
+                    .price
+                    * (balance as f64 / per_one),
+                args,
+                cache,
+            )?,
+        );
         let token_name = self.spl.as_ref().map(Tokens::name).unwrap_or("SOL");
         let message = format!(
             "The `{}` address has",
             short_public_key(&keypair.public_key)
         );
         if self.lamports {
-            println!("{message} `{balance}` {token_name} lamports ~${price:.2}");
+            println!("{message} `{balance}` {token_name} lamports ~{value}");
         } else {
             println!(
-                "{message} `{}` {token_name} ~${price:.2}",
+                "{message} `{}` {token_name} ~{value}",
                 balance as f64 / per_one
             );
         }
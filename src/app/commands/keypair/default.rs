@@ -33,15 +33,7 @@ impl DefaultCommand {
     /// Note: You need to export the wallet after running this command, using `Wallet::export`
     pub fn run(&self, wallet: &mut Wallet, args: &AppArgs) -> SolwalrsResult<()> {
         crate::info!(args, "Setting `{}` as a default in {:?}", self.name, wallet);
-        let mut keypair = wallet.get_keypair(&self.name, args)?.clone();
-        crate::info!(
-            args,
-            "The public key of the keypair is {:?}",
-            keypair.public_key
-        );
-        keypair.is_default = true;
-        wallet.delete_keypair(&self.name, args)?;
-        wallet.add_keypair(keypair, args)?;
+        wallet.set_default_keypair(&self.name, args)?;
         println!("Done setting `{}` as a default!", self.name);
         Ok(())
     }
@@ -18,15 +18,27 @@ mod airdrop;
 mod balance;
 mod default;
 mod delete;
+mod note;
+mod paper;
 mod qrcode;
+mod rename;
+mod request;
+mod tag;
 mod transactions;
+mod transfer;
 
 pub use self::qrcode::QrCodeCommand;
 pub use airdrop::AirdropCommand;
 pub use balance::BalanceCommand;
 pub use default::DefaultCommand;
 pub use delete::DeleteCommand;
+pub use note::NoteCommand;
+pub use paper::PaperCommand;
+pub use rename::RenameCommand;
+pub use request::RequestCommand;
+pub use tag::TagCommand;
 pub use transactions::TransactionsCommand;
+pub use transfer::TransferCommand;
 
 use crate::{
     errors::Result as SolwalrsResult,
@@ -45,12 +57,22 @@ pub enum KeypairCommand {
     SetDefault(DefaultCommand),
     #[clap(visible_alias = "qr")]
     QrCode(QrCodeCommand),
+    Paper(PaperCommand),
+    #[clap(visible_alias = "r")]
+    Request(RequestCommand),
     #[clap(visible_alias = "b")]
     Balance(BalanceCommand),
     #[clap(visible_alias = "a")]
     Airdrop(AirdropCommand),
     #[clap(visible_alias = "t")]
     Transactions(TransactionsCommand),
+    #[clap(visible_alias = "tr")]
+    Transfer(TransferCommand),
+    #[clap(visible_alias = "mv")]
+    Rename(RenameCommand),
+    #[clap(subcommand)]
+    Tag(TagCommand),
+    Note(NoteCommand),
 }
 
 impl KeypairCommand {
@@ -68,9 +90,15 @@ impl KeypairCommand {
             Delete(command) => command.run(wallet, args)?,
             SetDefault(command) => command.run(wallet, args)?,
             QrCode(command) => command.run(wallet, args)?,
+            Paper(command) => command.run(wallet, args)?,
+            Request(command) => command.run(wallet, args)?,
             Balance(command) => command.run(wallet, args, cache)?,
             Airdrop(command) => command.run(wallet, args)?,
             Transactions(command) => command.run(wallet, args)?,
+            Transfer(command) => command.run(wallet, args)?,
+            Rename(command) => command.run(wallet, args)?,
+            Tag(command) => command.run(wallet, args)?,
+            Note(command) => command.run(wallet, args)?,
         };
         Ok(())
     }
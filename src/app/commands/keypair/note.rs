@@ -0,0 +1,54 @@
+// Solwalrs, A simple and easy to use CLI Solana wallet
+// Copyright (C) 2022  Solwalrs contributors <https://github.com/TheAwiteb/solwalrs/graphs/contributors>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/gpl-3.0.html>.
+
+use clap::Parser;
+
+use crate::app::AppArgs;
+use crate::errors::Result as SolwalrsResult;
+use crate::wallet::Wallet;
+
+/// Set, clear or print the note of a keypair
+#[derive(Debug, Parser)]
+pub struct NoteCommand {
+    /// The name of the keypair
+    pub name: String,
+    /// The new note, if not provided, the current note will be printed
+    pub note: Option<String>,
+    /// Clear the note of the keypair
+    #[clap(long, conflicts_with = "note")]
+    pub clear: bool,
+}
+
+impl NoteCommand {
+    /// Set, clear or print the note of a keypair
+    /// Note: You need to export the wallet after running this command, using `Wallet::export`
+    pub fn run(&self, wallet: &mut Wallet, args: &AppArgs) -> SolwalrsResult<()> {
+        let keypair = wallet.get_keypair_mut(&self.name, args)?;
+        if self.clear {
+            keypair.note = None;
+            println!("Done clearing the note of `{}`!", keypair.name);
+        } else if let Some(note) = &self.note {
+            keypair.note = Some(note.clone());
+            println!("Done setting the note of `{}`!", keypair.name);
+        } else {
+            match &keypair.note {
+                Some(note) => println!("{note}"),
+                None => println!("`{}` doesn't have a note", keypair.name),
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,83 @@
+// Solwalrs, A simple and easy to use CLI Solana wallet
+// Copyright (C) 2022  Solwalrs contributors <https://github.com/TheAwiteb/solwalrs/graphs/contributors>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/gpl-3.0.html>.
+
+use std::path::PathBuf;
+
+use clap::Parser;
+use zeroize::Zeroizing;
+
+use crate::app::{AppArgs, GetKeypairName};
+use crate::errors::{Error as SolwalrsError, Result as SolwalrsResult};
+use crate::wallet::{PaperWallet, Wallet};
+
+/// Export a printable paper wallet of a keypair, as PNG or SVG.
+///
+/// The page has the address QR code, the keypair name, the creation date and a checksum.
+/// With `--secret` it has the secret too, encrypted with a passphrase (input prompt)
+#[derive(Parser, Debug)]
+pub struct PaperCommand {
+    /// The name of the keypair, will use the default keypair if not provided
+    pub name: Option<String>,
+    /// The path to save the paper wallet to, the format is chosen by the extension (`.png` or `.svg`)
+    #[clap(short, long)]
+    pub output: PathBuf,
+    /// Include the secret QR code, encrypted with a passphrase
+    #[clap(short, long)]
+    pub secret: bool,
+}
+
+impl PaperCommand {
+    /// Prompt for the passphrase of the secret twice
+    fn read_passphrase() -> SolwalrsResult<Zeroizing<String>> {
+        let read = |prompt: &str| {
+            rpassword::prompt_password(prompt)
+                .map(Zeroizing::new)
+                .map_err(|err| SolwalrsError::Other(format!("Faild to read from stdin: {err}")))
+        };
+        let passphrase = read("Enter the passphrase of the secret: ")?;
+        if passphrase.is_empty() {
+            return Err(SolwalrsError::Other(
+                "The passphrase can't be empty".to_owned(),
+            ));
+        }
+        if read("Enter the passphrase again: ")? != passphrase {
+            return Err(SolwalrsError::Other(
+                "The passphrases don't match".to_owned(),
+            ));
+        }
+        Ok(passphrase)
+    }
+
+    /// Run the command
+    pub fn run(&self, wallet: &mut Wallet, args: &AppArgs) -> SolwalrsResult<()> {
+        let name = self.name.get_keypair_name(wallet, args)?;
+        let keypair = wallet.get_keypair(&name, args)?;
+        let passphrase = self.secret.then(Self::read_passphrase).transpose()?;
+        let paper = PaperWallet::new(keypair, passphrase.as_deref().map(String::as_str), args)?;
+        paper.save(&self.output, args)?;
+        println!(
+            "Saved the paper wallet of `{name}` to `{}`, its checksum is {}",
+            self.output.display(),
+            paper.checksum()
+        );
+        if self.secret {
+            println!(
+                "Keep the passphrase apart from the paper, the secret can't be imported without it"
+            );
+        }
+        Ok(())
+    }
+}
@@ -17,20 +17,39 @@
 use std::path::PathBuf;
 
 use clap::Parser;
-use qrcode::render::unicode;
 
 use crate::app::{AppArgs, GetKeypairName};
 use crate::errors::{Error as SolwalrsError, Result as SolwalrsResult};
+use crate::utils::{self, QrEcLevel, QrFormat, QrStyle};
 use crate::wallet::{short_public_key, Wallet};
 
-/// Print the QR code of a keypair, or save it as an image
+/// Print the QR code of a keypair, or save it as an image or text file
 #[derive(Parser, Debug)]
 pub struct QrCodeCommand {
     /// The name of the keypair, will use the default keypair if not provided
     pub name: Option<String>,
-    /// The path to save the QR code image to, if not provided, the QR code will be printed to the terminal
+    /// The path to save the QR code to, if not provided, the QR code will be printed to the terminal
     #[clap(short, long)]
     pub output: Option<PathBuf>,
+    /// The format of the QR code, guessed from the output extension if not provided (`.svg` for SVG, `.txt` for ASCII, otherwise PNG).
+    /// The terminal default is unicode
+    #[clap(short, long, value_enum)]
+    pub format: Option<QrFormat>,
+    /// The minimum width of the image, in pixels
+    #[clap(short, long, default_value_t = 256)]
+    pub size: u32,
+    /// The width of the margin around the QR code, in modules
+    #[clap(short, long, default_value_t = 4)]
+    pub quiet_zone: u32,
+    /// The error correction level, higher levels are more robust but denser
+    #[clap(short, long, value_enum, ignore_case = true, default_value = "m")]
+    pub ec_level: QrEcLevel,
+    /// Swap the dark and light colours, e.g. for terminals with a light background
+    #[clap(short, long)]
+    pub invert: bool,
+    /// Add the keypair name and the full address under the QR code
+    #[clap(short, long)]
+    pub caption: bool,
 }
 
 impl QrCodeCommand {
@@ -38,32 +57,38 @@ impl QrCodeCommand {
     pub fn run(&self, wallet: &mut Wallet, args: &AppArgs) -> SolwalrsResult<()> {
         let name = self.name.get_keypair_name(wallet, args)?;
         let keypair = wallet.get_keypair(&name, args)?;
-        let qr_code = keypair.qr_code();
+        let qr_code = keypair.qr_code(self.ec_level.into());
+        let style = QrStyle {
+            size: self.size,
+            quiet_zone: self.quiet_zone,
+            invert: self.invert,
+            caption: if self.caption {
+                vec![keypair.name.clone(), keypair.pubkey().to_string()]
+            } else {
+                Vec::new()
+            },
+        };
         if let Some(path) = &self.output {
-            crate::info!(args, "Saving QR code to {path:?}");
-            qr_code
-                .render::<image::Luma<u8>>()
-                .build()
-                .save(path)
-                .map_err(|err| {
-                    SolwalrsError::IO(format!(
-                        "Failed to save QR code to {}: {err}",
-                        path.display()
-                    ))
-                })?;
-            crate::info!(args, "Saved QR code to `{path:?}`");
+            let format = self.format.unwrap_or_else(|| QrFormat::from_path(path));
+            utils::save_styled_qr_code(&qr_code, path, format, &style, args)?;
             println!("Saved QR code to `{}`", path.display())
         } else {
-            crate::info!(args, "Printing QR code to terminal");
-            let str_qr_code = qr_code
-                .render::<unicode::Dense1x2>()
-                .dark_color(unicode::Dense1x2::Light)
-                .light_color(unicode::Dense1x2::Dark)
-                .build();
-            println!(
-                "\n{str_qr_code}\n{:>24}\n",
-                short_public_key(&keypair.public_key)
-            )
+            let format = self.format.unwrap_or(QrFormat::Unicode);
+            if format.is_image() {
+                return Err(SolwalrsError::Other(format!(
+                    "The {format:?} format can't be printed to the terminal, use `--output` to save it"
+                )));
+            }
+            crate::info!(args, "Printing QR code to terminal as {format:?}");
+            let str_qr_code = utils::render_qr_text(&qr_code, format, &style);
+            if self.caption {
+                println!("\n{str_qr_code}\n")
+            } else {
+                println!(
+                    "\n{str_qr_code}\n{:>24}\n",
+                    short_public_key(&keypair.public_key)
+                )
+            }
         }
         Ok(())
     }
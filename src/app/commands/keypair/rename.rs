@@ -0,0 +1,40 @@
+// Solwalrs, A simple and easy to use CLI Solana wallet
+// Copyright (C) 2022  Solwalrs contributors <https://github.com/TheAwiteb/solwalrs/graphs/contributors>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/gpl-3.0.html>.
+
+use clap::Parser;
+
+use crate::app::AppArgs;
+use crate::errors::Result as SolwalrsResult;
+use crate::wallet::Wallet;
+
+/// Rename a keypair
+#[derive(Debug, Parser)]
+pub struct RenameCommand {
+    /// The current name of the keypair
+    pub old_name: String,
+    /// The new name of the keypair
+    pub new_name: String,
+}
+
+impl RenameCommand {
+    /// Rename a keypair
+    /// Note: You need to export the wallet after running this command, using `Wallet::export`
+    pub fn run(&self, wallet: &mut Wallet, args: &AppArgs) -> SolwalrsResult<()> {
+        wallet.rename_keypair(&self.old_name, &self.new_name, args)?;
+        println!("Done renaming `{}` to `{}`!", self.old_name, self.new_name);
+        Ok(())
+    }
+}
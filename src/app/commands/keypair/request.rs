@@ -0,0 +1,93 @@
+// Solwalrs, A simple and easy to use CLI Solana wallet
+// Copyright (C) 2022  Solwalrs contributors <https://github.com/TheAwiteb/solwalrs/graphs/contributors>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/gpl-3.0.html>.
+
+use std::path::PathBuf;
+
+use clap::Parser;
+use solana_sdk::{pubkey::Pubkey, signature::Keypair, signer::Signer};
+
+use crate::app::{AppArgs, GetKeypairName};
+use crate::errors::Result as SolwalrsResult;
+use crate::utils;
+use crate::wallet::{format_amount, Tokens, TransferRequest, Wallet, SOL_DECIMALS};
+
+/// Create a Solana Pay payment request to a keypair, as a `solana:` URI and its QR code
+#[derive(Parser, Debug)]
+pub struct RequestCommand {
+    /// The name of the keypair, will use the default keypair if not provided
+    pub name: Option<String>,
+    /// The requested amount, in SOL or in tokens. The payer enters it if not provided
+    #[clap(short, long)]
+    pub amount: Option<f64>,
+    /// The requested spl token, if not specified, SOL will be requested
+    #[clap(short, long, value_enum)]
+    pub spl: Option<Tokens>,
+    /// The source of the request, e.g. the store name
+    #[clap(long)]
+    pub label: Option<String>,
+    /// What the request is for, e.g. the item name
+    #[clap(long)]
+    pub message: Option<String>,
+    /// The memo to add to the payment transaction
+    #[clap(long)]
+    pub memo: Option<String>,
+    /// The reference key to find the payment transaction with, a new one will be generated if not provided
+    #[clap(long)]
+    pub reference: Option<Pubkey>,
+    /// The path to save the QR code to (`.png` or `.svg`), if not provided, the QR code will be printed to the terminal
+    #[clap(short, long)]
+    pub output: Option<PathBuf>,
+}
+
+impl RequestCommand {
+    /// Run the command
+    pub fn run(&self, wallet: &mut Wallet, args: &AppArgs) -> SolwalrsResult<()> {
+        let name = self.name.get_keypair_name(wallet, args)?;
+        let keypair = wallet.get_keypair(&name, args)?;
+        let decimals = self
+            .spl
+            .as_ref()
+            .map(Tokens::decimals)
+            .unwrap_or(SOL_DECIMALS);
+        // The reference is a random public key, nobody needs its secret key
+        let reference = self.reference.unwrap_or_else(|| Keypair::new().pubkey());
+        let request = TransferRequest {
+            recipient: keypair.pubkey(),
+            amount: self
+                .amount
+                .map(|amount| format_amount(amount, decimals))
+                .transpose()?,
+            spl_token: self.spl.as_ref().map(Tokens::mint),
+            references: vec![reference],
+            label: self.label.clone(),
+            message: self.message.clone(),
+            memo: self.memo.clone(),
+        };
+        crate::info!(args, "The payment request is {request:?}");
+        let uri = request.to_uri();
+        let qr_code = utils::qr_code(uri.as_str())?;
+
+        println!("{uri}");
+        if let Some(path) = &self.output {
+            utils::save_qr_code(&qr_code, path, args)?;
+            println!("Saved the QR code to `{}`", path.display());
+        } else {
+            println!("\n{}\n", utils::render_qr_code(&qr_code));
+        }
+        println!("Reference: {reference}");
+        Ok(())
+    }
+}
@@ -0,0 +1,73 @@
+// Solwalrs, A simple and easy to use CLI Solana wallet
+// Copyright (C) 2022  Solwalrs contributors <https://github.com/TheAwiteb/solwalrs/graphs/contributors>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/gpl-3.0.html>.
+
+use clap::{Parser, Subcommand};
+
+use crate::app::AppArgs;
+use crate::errors::Result as SolwalrsResult;
+use crate::wallet::Wallet;
+
+/// The keypair name and the tags
+#[derive(Debug, Parser)]
+pub struct TagArgs {
+    /// The name of the keypair
+    pub name: String,
+    /// The tags
+    #[clap(required = true)]
+    pub tags: Vec<String>,
+}
+
+/// Add or remove tags of a keypair
+#[derive(Debug, Subcommand)]
+pub enum TagCommand {
+    /// Add tags to a keypair
+    Add(TagArgs),
+    /// Remove tags from a keypair
+    #[clap(visible_alias = "rm")]
+    Remove(TagArgs),
+}
+
+impl TagCommand {
+    /// Add or remove tags of a keypair
+    /// Note: You need to export the wallet after running this command, using `Wallet::export`
+    pub fn run(&self, wallet: &mut Wallet, args: &AppArgs) -> SolwalrsResult<()> {
+        match self {
+            TagCommand::Add(tag_args) => {
+                let keypair = wallet.get_keypair_mut(&tag_args.name, args)?;
+                keypair.add_tags(&tag_args.tags);
+                crate::info!(
+                    args,
+                    "The tags of `{}` are {:?}",
+                    keypair.name,
+                    keypair.tags
+                );
+                println!("Done adding the tags to `{}`!", keypair.name);
+            }
+            TagCommand::Remove(tag_args) => {
+                let keypair = wallet.get_keypair_mut(&tag_args.name, args)?;
+                keypair.remove_tags(&tag_args.tags);
+                crate::info!(
+                    args,
+                    "The tags of `{}` are {:?}",
+                    keypair.name,
+                    keypair.tags
+                );
+                println!("Done removing the tags from `{}`!", keypair.name);
+            }
+        }
+        Ok(())
+    }
+}
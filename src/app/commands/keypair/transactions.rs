@@ -20,21 +20,20 @@ use crate::app::{AppArgs, GetKeypairName};
 use crate::errors::Result as SolwalrsResult;
 use crate::wallet::{transactions_url, Wallet};
 
-/// The transactions of a keypair
+/// The transactions of a keypair or an address
 #[derive(Debug, Parser)]
 pub struct TransactionsCommand {
-    /// The name of the keypair, defaults to the default keypair
+    /// The name of the keypair, a contact `@alias` or an address, defaults to the default keypair
     pub name: Option<String>,
 }
 
 impl TransactionsCommand {
     pub fn run(&self, wallet: &Wallet, args: &AppArgs) -> SolwalrsResult<()> {
         let name = self.name.get_keypair_name(wallet, args)?;
-        let keypair = wallet.get_keypair(&name, args)?;
+        let address = wallet.resolve_address(&name, args)?;
         println!(
-            "Checking the transaction of `{}`\n    Here: {}",
-            name,
-            transactions_url(&keypair.public_key, args)?
+            "Checking the transaction of `{address}`\n    Here: {}",
+            transactions_url(&address.address, args)?
         );
         Ok(())
     }
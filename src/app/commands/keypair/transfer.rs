@@ -0,0 +1,69 @@
+// Solwalrs, A simple and easy to use CLI Solana wallet
+// Copyright (C) 2022  Solwalrs contributors <https://github.com/TheAwiteb/solwalrs/graphs/contributors>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/gpl-3.0.html>.
+
+use clap::Parser;
+use solana_sdk::system_instruction;
+
+use crate::app::{AppArgs, GetKeypairName};
+use crate::errors::{Error as SolwalrsError, Result as SolwalrsResult};
+use crate::wallet::{amount_to_units, send_instructions, transaction_url, Wallet, SOL_DECIMALS};
+
+/// Transfer SOL from a keypair to an address
+#[derive(Debug, Parser)]
+pub struct TransferCommand {
+    /// The name of the keypair to transfer from (defaults to the default keypair)
+    pub name: Option<String>,
+    /// The address to transfer to, a contact `@alias` or a keypair name
+    #[clap(short, long)]
+    pub to: String,
+    /// The amount to transfer in SOL, e.g. `0.5`
+    #[clap(
+        short,
+        long,
+        required_unless_present = "lamports",
+        conflicts_with = "lamports"
+    )]
+    pub amount: Option<String>,
+    /// The amount to transfer in lamports
+    #[clap(short, long)]
+    pub lamports: Option<u64>,
+}
+
+impl TransferCommand {
+    pub fn run(&self, wallet: &mut Wallet, args: &AppArgs) -> SolwalrsResult<()> {
+        let amount = match (&self.amount, self.lamports) {
+            (Some(amount), _) => amount_to_units(amount, SOL_DECIMALS)?,
+            (None, lamports) => lamports.unwrap_or_default(),
+        };
+        if amount == 0 {
+            return Err(SolwalrsError::Other(
+                "The amount to transfer must be greater than zero".to_owned(),
+            ));
+        }
+        let name = self.name.get_keypair_name(wallet, args)?;
+        let keypair = wallet.get_keypair(&name, args)?;
+        let recipient = wallet.resolve_address(&self.to, args)?;
+        println!("Recipient: {recipient}");
+        let instruction =
+            system_instruction::transfer(&keypair.pubkey(), &recipient.address, amount);
+        let signature = send_instructions(&[instruction], &[keypair], wallet, args)?;
+        println!(
+            "Transaction confirmed!\n{}",
+            transaction_url(&signature.to_string(), args)?
+        );
+        Ok(())
+    }
+}
@@ -16,6 +16,7 @@
 
 use base58::ToBase58;
 use clap::Parser;
+use zeroize::Zeroizing;
 
 use crate::app::AppArgs;
 use crate::errors::Result as SolwalrsResult;
@@ -41,10 +42,21 @@ pub struct ListCommand {
     /// The name of the keypair, (default: list all keypairs)
     #[clap(short, long)]
     pub name: Option<String>,
+    /// List only the keypairs with the given tag
+    #[clap(short, long)]
+    pub tag: Option<String>,
+    /// List only the keypairs that their name, tags or note matches the given glob pattern, e.g. `team-*`
+    #[clap(long)]
+    pub search: Option<String>,
 }
 
-/// Create a row for the table
-fn create_row(keypair: &KeyPair, list_command: &ListCommand, args: &AppArgs) -> Vec<String> {
+/// Create a row for the table, the row is zeroized on drop because it may contain the secret and private keys
+fn create_row(
+    keypair: &KeyPair,
+    list_command: &ListCommand,
+    metadata: bool,
+    args: &AppArgs,
+) -> Zeroizing<Vec<String>> {
     crate::info!(args, "Creating a row for `{keypair:?}`");
     let mut row = vec![
         format!(
@@ -62,9 +74,13 @@ fn create_row(keypair: &KeyPair, list_command: &ListCommand, args: &AppArgs) ->
         row.push(keypair.secret_key.as_bytes().to_base58());
     }
     if list_command.private {
-        row.push(keypair.private_key.clone());
+        row.push(keypair.private_key.to_string());
     }
-    row
+    if metadata {
+        row.push(keypair.tags.join(", "));
+        row.push(keypair.note.clone().unwrap_or_default());
+    }
+    Zeroizing::new(row)
 }
 
 /// List all keypairs
@@ -72,16 +88,20 @@ fn list_all_keypairs(
     list_command: &ListCommand,
     wallet: &Wallet,
     header: Vec<&str>,
+    metadata: bool,
     args: &AppArgs,
 ) {
     let keypairs_len = wallet.keypairs.len();
     let limit = list_command.limit.unwrap_or(keypairs_len);
     let rows: Vec<_> = wallet
-        .keypairs
-        .iter()
+        .filter_keypairs(list_command.tag.as_deref(), list_command.search.as_deref())
         .take(limit)
-        .map(|kp| create_row(kp, list_command, args))
+        .map(|kp| create_row(kp, list_command, metadata, args))
         .collect();
+    if rows.is_empty() {
+        println!("No keypairs match the filters");
+        return;
+    }
     print_table(
         header,
         rows.iter()
@@ -96,12 +116,13 @@ fn list_keypair_by_name(
     wallet: &Wallet,
     name: &str,
     header: Vec<&str>,
+    metadata: bool,
     args: &AppArgs,
 ) -> SolwalrsResult<()> {
     let keypair = wallet.get_keypair(name, args)?;
     print_table(
         header,
-        vec![create_row(keypair, list_command, args)
+        vec![create_row(keypair, list_command, metadata, args)
             .iter()
             .map(|s| s.as_str())
             .collect()],
@@ -122,12 +143,21 @@ impl ListCommand {
             if self.private {
                 header.push("Private Key");
             }
+            // Show the tags and notes only if there is a keypair with them
+            let metadata = wallet
+                .keypairs
+                .iter()
+                .any(|kp| !kp.tags.is_empty() || kp.note.is_some());
+            if metadata {
+                header.push("Tags");
+                header.push("Note");
+            }
             if let Some(name) = &self.name {
                 // If the name is set, we will only list the keypair with the name
-                list_keypair_by_name(self, wallet, name, header, args)?;
+                list_keypair_by_name(self, wallet, name, header, metadata, args)?;
             } else {
                 // If the name is not set, we will list all keypairs
-                list_all_keypairs(self, wallet, header, args)
+                list_all_keypairs(self, wallet, header, metadata, args)
             };
         } else {
             println!("No keypairs found")
@@ -14,13 +14,20 @@
 // You should have received a copy of the GNU General Public License
 // along with this program.  If not, see <https://www.gnu.org/licenses/gpl-3.0.html>.
 
+pub mod agent;
+pub mod backup;
 mod clean;
 mod completions;
+pub mod contacts;
 mod import;
 pub mod keypair;
 mod list;
 mod new;
+pub mod pay;
 mod price;
+pub mod restore;
+pub mod tx;
+pub mod wallet;
 pub use clean::CleanCommand;
 pub use completions::CompletionsCommand;
 pub use import::ImportCommand;
@@ -0,0 +1,64 @@
+// Solwalrs, A simple and easy to use CLI Solana wallet
+// Copyright (C) 2022  Solwalrs contributors <https://github.com/TheAwiteb/solwalrs/graphs/contributors>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/gpl-3.0.html>.
+
+mod request;
+mod watch;
+
+pub use request::RequestArgs;
+pub use watch::WatchCommand;
+
+use clap::{Parser, Subcommand};
+
+use crate::{app::AppArgs, errors::Result as SolwalrsResult, wallet::Wallet};
+
+/// Pay a Solana Pay request by its `solana:` URI or QR code image, or watch for the payment of a request
+#[derive(Parser, Debug)]
+#[clap(args_conflicts_with_subcommands = true, subcommand_negates_reqs = true)]
+pub struct PayCommand {
+    #[clap(subcommand)]
+    pub command: Option<PaySubcommand>,
+    #[clap(flatten)]
+    pub request: RequestArgs,
+}
+
+/// Commands for Solana Pay payments
+#[derive(Subcommand, Debug)]
+pub enum PaySubcommand {
+    #[clap(visible_alias = "w")]
+    Watch(WatchCommand),
+}
+
+impl PayCommand {
+    /// Whether the command needs the wallet
+    pub fn needs_wallet(&self) -> bool {
+        match &self.command {
+            Some(PaySubcommand::Watch(command)) => command.needs_wallet(),
+            None => true,
+        }
+    }
+
+    /// Run the command
+    pub fn run(&self, wallet: &mut Wallet, args: &AppArgs) -> SolwalrsResult<()> {
+        use PaySubcommand::*;
+
+        crate::info!(args, "The pay command is: {self:?}");
+        match &self.command {
+            Some(Watch(command)) => command.run(wallet, args)?,
+            None => self.request.run(wallet, args)?,
+        };
+        Ok(())
+    }
+}
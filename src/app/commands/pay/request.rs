@@ -0,0 +1,168 @@
+// Solwalrs, A simple and easy to use CLI Solana wallet
+// Copyright (C) 2022  Solwalrs contributors <https://github.com/TheAwiteb/solwalrs/graphs/contributors>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/gpl-3.0.html>.
+
+use std::path::PathBuf;
+
+use clap::Parser;
+use url::Url;
+
+use crate::app::{AppArgs, GetKeypairName};
+use crate::errors::{Error as SolwalrsError, Result as SolwalrsResult};
+use crate::utils;
+use crate::wallet::{
+    amount_to_units, format_amount, send_instructions, sign_and_send, transaction_url, KeyPair,
+    PaymentRequest, Tokens, TransactionRequest, TransferRequest, Wallet,
+};
+
+/// The request to pay, as a `solana:` URI or a QR code image
+#[derive(Parser, Debug)]
+pub struct RequestArgs {
+    /// The `solana:` URI of the request, a transfer request or a transaction request
+    #[clap(required_unless_present = "qr_image", conflicts_with = "qr_image")]
+    pub uri: Option<String>,
+    /// Read the request from the QR code in the image, e.g. a screenshot
+    #[clap(long)]
+    pub qr_image: Option<PathBuf>,
+    /// The name of the keypair to pay from, will use the default keypair if not provided
+    #[clap(short, long)]
+    pub keypair: Option<String>,
+    /// The amount to pay, in SOL or in tokens. Only for the transfer requests without an amount
+    #[clap(short, long)]
+    pub amount: Option<f64>,
+    /// Send the transaction request to this endpoint instead of the origin of its link, e.g. a staging server
+    #[clap(long)]
+    pub endpoint: Option<Url>,
+}
+
+impl RequestArgs {
+    /// Run the command
+    pub fn run(&self, wallet: &mut Wallet, args: &AppArgs) -> SolwalrsResult<()> {
+        let uri = match (&self.uri, &self.qr_image) {
+            (Some(uri), _) => uri.clone(),
+            (None, Some(path)) => utils::read_qr_code(path, args)?,
+            // SAFETY: clap requires one of them
+            (None, None) => unreachable!(),
+        };
+        let request = PaymentRequest::parse(&uri)?;
+        crate::info!(args, "The payment request is {request:?}");
+        let name = self.keypair.get_keypair_name(wallet, args)?;
+        let keypair = wallet.get_keypair(&name, args)?;
+        println!("Payer: {} ({})", keypair.pubkey(), keypair.name);
+
+        let signature = match request {
+            PaymentRequest::Transfer(request) => {
+                self.pay_transfer(&request, keypair, wallet, args)?
+            }
+            PaymentRequest::Transaction(mut request) => {
+                if let Some(endpoint) = &self.endpoint {
+                    request.set_endpoint(endpoint)?;
+                }
+                self.pay_transaction(&request, keypair, wallet, args)?
+            }
+        };
+        println!(
+            "Transaction confirmed!\n{}",
+            transaction_url(&signature.to_string(), args)?
+        );
+        Ok(())
+    }
+
+    /// Show the transfer request, then send the transfer after the user confirms it
+    fn pay_transfer(
+        &self,
+        request: &TransferRequest,
+        keypair: &KeyPair,
+        wallet: &Wallet,
+        args: &AppArgs,
+    ) -> SolwalrsResult<solana_sdk::signature::Signature> {
+        if self.endpoint.is_some() {
+            return Err(SolwalrsError::Other(
+                "`--endpoint` is only for the transaction requests".to_owned(),
+            ));
+        }
+        let decimals = request.decimals(args)?;
+        let amount = match (&request.amount, self.amount) {
+            (Some(amount), None) => amount.clone(),
+            (None, Some(amount)) => format_amount(amount, decimals)?,
+            (Some(amount), Some(_)) => {
+                return Err(SolwalrsError::Other(format!(
+                    "The request amount is {amount}, `--amount` is only for the requests without an amount"
+                )))
+            }
+            (None, None) => {
+                return Err(SolwalrsError::Other(
+                    "The request doesn't have an amount, enter it with `--amount`".to_owned(),
+                ))
+            }
+        };
+        let token = match &request.spl_token {
+            Some(mint) => match Tokens::from_mint(mint) {
+                Some(token) => format!("{} ({mint})", token.name().to_uppercase()),
+                None => format!("tokens of the mint {mint}"),
+            },
+            None => "SOL".to_owned(),
+        };
+
+        println!(
+            "Recipient: {}",
+            wallet.annotate_address(&request.recipient.to_string())
+        );
+        println!("Amount: {amount} {token}");
+        for (key, value) in [
+            ("Label", &request.label),
+            ("Message", &request.message),
+            ("Memo", &request.memo),
+        ] {
+            if let Some(value) = value {
+                println!("{key}: {value}");
+            }
+        }
+        for reference in &request.references {
+            println!("Reference: {reference}");
+        }
+
+        let instructions = request.instructions(
+            &keypair.pubkey(),
+            amount_to_units(&amount, decimals)?,
+            decimals,
+        )?;
+        send_instructions(&instructions, &[keypair], wallet, args)
+    }
+
+    /// Request the transaction from the merchant, then sign and send it after the user confirms it
+    fn pay_transaction(
+        &self,
+        request: &TransactionRequest,
+        keypair: &KeyPair,
+        wallet: &Wallet,
+        args: &AppArgs,
+    ) -> SolwalrsResult<solana_sdk::signature::Signature> {
+        if self.amount.is_some() {
+            return Err(SolwalrsError::Other(
+                "`--amount` is only for the transfer requests, the merchant sets the amount of the transaction requests".to_owned(),
+            ));
+        }
+        let merchant = request.merchant(args)?;
+        println!("Merchant: {}", merchant.label);
+        println!("Icon: {}", merchant.icon);
+        println!("Link: {}", request.link);
+        let (transaction, message) = request.transaction(&keypair.pubkey(), args)?;
+        if let Some(message) = message {
+            println!("Message: {message}");
+        }
+        sign_and_send(transaction, keypair, wallet, args)
+    }
+}
@@ -0,0 +1,114 @@
+// Solwalrs, A simple and easy to use CLI Solana wallet
+// Copyright (C) 2022  Solwalrs contributors <https://github.com/TheAwiteb/solwalrs/graphs/contributors>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/gpl-3.0.html>.
+
+use std::time::{Duration, Instant};
+
+use clap::Parser;
+use solana_sdk::pubkey::Pubkey;
+
+use crate::app::AppArgs;
+use crate::errors::{Error as SolwalrsError, Result as SolwalrsResult};
+use crate::wallet::{
+    find_reference, format_amount, transaction_url, Tokens, TransactionDetails, TransferRequest,
+    Wallet, SOL_DECIMALS,
+};
+
+/// The interval between searching for the payment transaction
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// The exit codes of the command, for the scripts
+const EXIT_CODES: &str = "Exit codes:
+  0   The payment is found and valid
+  2   Invalid command line arguments
+  3   The payment is found but doesn't match the request (wrong recipient, amount or token)
+  4   No payment is found before the timeout
+  65  The wallet password is wrong, or the wallet file failed the integrity check
+  70  Any other error, e.g. an RPC request failed or the recipient isn't a keypair, a contact or an address
+  74  Failed to read or write a file
+  75  The wallet is in use by another Solwalrs process";
+
+/// Wait for a Solana Pay payment by its reference key, and verify its recipient, amount and token
+#[derive(Parser, Debug)]
+#[clap(after_help = EXIT_CODES)]
+pub struct WatchCommand {
+    /// The reference key of the payment request
+    #[clap(short, long)]
+    pub reference: Pubkey,
+    /// The recipient of the payment, a keypair name, a contact `@alias` or an address
+    #[clap(short = 'R', long)]
+    pub recipient: String,
+    /// The requested amount, in SOL or in tokens
+    #[clap(short, long)]
+    pub amount: f64,
+    /// The requested spl token, if not specified, SOL is expected
+    #[clap(short, long, value_enum)]
+    pub spl: Option<Tokens>,
+    /// The maximum time to wait for the payment, in seconds
+    #[clap(short, long, default_value = "300")]
+    pub timeout: u64,
+}
+
+impl WatchCommand {
+    /// Whether the wallet is needed to resolve the recipient, it's not needed for a plain address
+    pub fn needs_wallet(&self) -> bool {
+        self.recipient.parse::<Pubkey>().is_err()
+    }
+
+    /// Run the command
+    pub fn run(&self, wallet: &mut Wallet, args: &AppArgs) -> SolwalrsResult<()> {
+        let recipient = wallet.resolve_address(&self.recipient, args)?;
+        let decimals = self
+            .spl
+            .as_ref()
+            .map(Tokens::decimals)
+            .unwrap_or(SOL_DECIMALS);
+        let request = TransferRequest {
+            recipient: recipient.address,
+            amount: Some(format_amount(self.amount, decimals)?),
+            spl_token: self.spl.as_ref().map(Tokens::mint),
+            references: vec![self.reference],
+            ..Default::default()
+        };
+        crate::info!(args, "Watching for the payment {request:?}");
+        println!("Recipient: {recipient}");
+
+        let started = Instant::now();
+        let timeout = Duration::from_secs(self.timeout);
+        let signature = loop {
+            // A failed request shouldn't stop the watching, the next one may succeed
+            match find_reference(&self.reference, args) {
+                Ok(Some(signature)) => break signature,
+                Ok(None) => {}
+                Err(err) => {
+                    crate::warn!(args, "Failed to search for the payment: {err}");
+                }
+            }
+            if started.elapsed() >= timeout {
+                return Err(SolwalrsError::PaymentTimeout(self.reference.to_string()));
+            }
+            crate::info!(args, "Waiting for the payment...");
+            std::thread::sleep(POLL_INTERVAL);
+        };
+        println!("Found the payment transaction `{signature}`");
+        let details = TransactionDetails::fetch(&signature, args)?;
+        request.validate_transfer(&details, decimals)?;
+        println!(
+            "The payment is valid!\n{}",
+            transaction_url(&signature.to_string(), args)?
+        );
+        Ok(())
+    }
+}
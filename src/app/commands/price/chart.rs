@@ -0,0 +1,112 @@
+// Solwalrs, A simple and easy to use CLI Solana wallet
+// Copyright (C) 2022  Solwalrs contributors <https://github.com/TheAwiteb/solwalrs/graphs/contributors>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/gpl-3.0.html>.
+
+/// Resample the values to the given count, each value is the average of its bucket.
+/// The values are returned as they are if they're less than the count
+fn resample(values: &[f64], count: usize) -> Vec<f64> {
+    if values.len() <= count || count == 0 {
+        return values.to_vec();
+    }
+    (0..count)
+        .map(|idx| {
+            let bucket = &values[idx * values.len() / count..(idx + 1) * values.len() / count];
+            bucket.iter().sum::<f64>() / bucket.len() as f64
+        })
+        .collect()
+}
+
+/// Returns the position of the value between the lowest and the highest values, scaled to `0..=max`
+fn scale(value: f64, (low, high): (f64, f64), max: usize) -> usize {
+    if high > low {
+        (((value - low) / (high - low)) * max as f64).round() as usize
+    } else {
+        // All the values are equal, draw them in the middle
+        max / 2
+    }
+}
+
+/// Returns the lowest and the highest values
+fn bounds(values: &[f64]) -> (f64, f64) {
+    values
+        .iter()
+        .fold((f64::INFINITY, f64::NEG_INFINITY), |(low, high), value| {
+            (low.min(*value), high.max(*value))
+        })
+}
+
+/// Render the values as a sparkline of block characters, at most `width` characters wide
+pub fn sparkline(values: &[f64], width: usize) -> String {
+    const BLOCKS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+    let values = resample(values, width);
+    let bounds = bounds(&values);
+    values
+        .iter()
+        .map(|value| BLOCKS[scale(*value, bounds, BLOCKS.len() - 1)])
+        .collect()
+}
+
+/// Render the values as a line chart of braille characters, at most `width` characters wide and `height` lines high.
+/// Each character has 2x4 dots, the consecutive values are connected with vertical lines
+pub fn braille_chart(values: &[f64], width: usize, height: usize) -> Vec<String> {
+    // The dot bits of the braille characters, by the dot row and column
+    const DOTS: [[u32; 2]; 4] = [[0x01, 0x08], [0x02, 0x10], [0x04, 0x20], [0x40, 0x80]];
+    let values = resample(values, width * 2);
+    let bounds = bounds(&values);
+    let rows = height.max(1) * 4;
+    let mut cells = vec![vec![0u32; values.len().div_ceil(2)]; height.max(1)];
+    let mut previous = None;
+    for (x, value) in values.iter().enumerate() {
+        // The dot rows are counted from the top
+        let y = rows - 1 - scale(*value, bounds, rows - 1);
+        let (from, to) = match previous {
+            Some(previous) if previous < y => (previous + 1, y),
+            Some(previous) if previous > y => (y, previous - 1),
+            _ => (y, y),
+        };
+        for y in from..=to {
+            cells[y / 4][x / 2] |= DOTS[y % 4][x % 2];
+        }
+        previous = Some(y);
+    }
+    cells
+        .iter()
+        .map(|row| {
+            row.iter()
+                .map(|dots| char::from_u32(0x2800 + dots).unwrap_or(' '))
+                .collect()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn charts() {
+        let values = [1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0];
+        assert_eq!(sparkline(&values, 8), "▁▂▃▄▅▆▇█");
+        assert_eq!(sparkline(&values, 4), "▁▃▆█");
+        assert_eq!(sparkline(&[3.0, 3.0], 10), "▄▄");
+
+        let chart = braille_chart(&[0.0, 1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0], 4, 2);
+        // A straight line from the bottom left to the top right
+        assert_eq!(chart, vec!["⠀⠀⡠⠊".to_owned(), "⡠⠊⠀⠀".to_owned()]);
+        let chart = braille_chart(&[0.0, 7.0], 1, 2);
+        // The values are connected with a vertical line
+        assert_eq!(chart, vec!["⢸".to_owned(), "⡸".to_owned()]);
+    }
+}
@@ -0,0 +1,255 @@
+// Solwalrs, A simple and easy to use CLI Solana wallet
+// Copyright (C) 2022  Solwalrs contributors <https://github.com/TheAwiteb/solwalrs/graphs/contributors>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/gpl-3.0.html>.
+
+use std::{
+    env,
+    io::{self, IsTerminal},
+    thread,
+    time::Duration,
+};
+
+use clap::Args;
+
+use crate::app::AppArgs;
+use crate::errors::{Error as SolwalrsError, Result as SolwalrsResult};
+use crate::wallet::cache::Cache;
+use crate::wallet::{Asset, Currency, Price, PriceProvider, Tokens, PRICE_TTL};
+
+/// The arguments of the current prices
+#[derive(Debug, Args)]
+pub struct PriceArgs {
+    /// The assets to get the price of, `sol`, a supported token (`usdc`, `usdt`, `srm`) or a token mint.
+    /// Default is `sol`
+    pub assets: Vec<Asset>,
+    /// SPL token to get the price of, same as passing it as an asset
+    #[clap(long)]
+    pub spl: Option<Tokens>,
+    /// Refresh the prices every given seconds until Ctrl-C, at least 10 seconds to not overload the price APIs
+    #[clap(short, long, value_name = "INTERVAL", value_parser = clap::value_parser!(u64).range(10..))]
+    pub watch: Option<u64>,
+}
+
+/// A row of the prices table
+struct PriceRow {
+    /// The symbol of the asset
+    asset: String,
+    /// The formatted price
+    price: String,
+    /// The price change in the last 24 hours, in percent
+    change: Option<f64>,
+    /// The source of the price
+    source: String,
+    /// How long ago the price was fetched
+    updated: String,
+}
+
+/// Returns how long ago the price was fetched, e.g. `12s ago`
+pub fn format_age(age: u64) -> String {
+    match age {
+        0..=4 => "just now".to_owned(),
+        5..=59 => format!("{age}s ago"),
+        60..=3599 => format!("{}m ago", age / 60),
+        _ => format!("{}h ago", age / 3600),
+    }
+}
+
+/// Colour the text green if the change is positive and red if it's negative, when `color` is set
+pub fn color_change(text: String, change: f64, color: bool) -> String {
+    match change {
+        change if color && change > 0.0 => format!("\x1b[32m{text}\x1b[0m"),
+        change if color && change < 0.0 => format!("\x1b[31m{text}\x1b[0m"),
+        _ => text,
+    }
+}
+
+/// Render the prices table, the 24h change is green if it's positive and red if it's negative when `color` is set
+fn render_table(rows: &[PriceRow], color: bool) -> String {
+    let header = ["Asset", "Price", "24h", "Source", "Updated"];
+    let cells: Vec<[String; 5]> = rows
+        .iter()
+        .map(|row| {
+            [
+                row.asset.clone(),
+                row.price.clone(),
+                row.change
+                    .map(|change| format!("{change:+.2}%"))
+                    .unwrap_or_else(|| "n/a".to_owned()),
+                row.source.clone(),
+                row.updated.clone(),
+            ]
+        })
+        .collect();
+    let widths: Vec<usize> = (0..header.len())
+        .map(|idx| {
+            cells
+                .iter()
+                .map(|row| row[idx].chars().count())
+                .chain([header[idx].len()])
+                .max()
+                .unwrap_or_default()
+        })
+        .collect();
+
+    let mut lines = vec![header
+        .iter()
+        .zip(&widths)
+        .map(|(name, width)| format!("{name:<width$}"))
+        .collect::<Vec<_>>()
+        .join("  ")
+        .trim_end()
+        .to_owned()];
+    for (row, cells) in rows.iter().zip(cells) {
+        let line = cells
+            .iter()
+            .zip(&widths)
+            .enumerate()
+            .map(|(idx, (cell, width))| {
+                // The price and the change are right aligned, the colour codes are added after the padding
+                let cell = match idx {
+                    1 | 2 => format!("{cell:>width$}"),
+                    _ => format!("{cell:<width$}"),
+                };
+                match (idx, row.change) {
+                    (2, Some(change)) => color_change(cell, change, color),
+                    _ => cell,
+                }
+            })
+            .collect::<Vec<_>>()
+            .join("  ");
+        lines.push(line.trim_end().to_owned());
+    }
+    lines.join("\n")
+}
+
+impl PriceArgs {
+    /// Returns the assets to get the price of, SOL if there are no assets
+    fn assets(&self) -> Vec<Asset> {
+        let mut assets = self.assets.clone();
+        if let Some(token) = &self.spl {
+            assets.push(Asset::Token(token.clone()));
+        }
+        if assets.is_empty() {
+            assets.push(Asset::Sol);
+        }
+        // Remove the duplicates, keeping the order
+        let mut unique = Vec::with_capacity(assets.len());
+        for asset in assets {
+            if !unique.contains(&asset) {
+                unique.push(asset);
+            }
+        }
+        unique
+    }
+
+    /// Update the prices of the assets that are older than `max_age` seconds,
+    /// the last price is kept if the update fails. Returns the update errors with their assets
+    fn update_prices<'a>(
+        assets: &'a [Asset],
+        prices: &mut [Option<Price>],
+        max_age: u64,
+        args: &AppArgs,
+        cache: &mut Cache,
+    ) -> Vec<(&'a Asset, SolwalrsError)> {
+        let mut errors = Vec::new();
+        for (asset, price) in assets.iter().zip(prices.iter_mut()) {
+            match Price::get_fresh_price(asset, max_age, args, cache) {
+                Ok(new_price) => *price = Some(new_price),
+                Err(err) => errors.push((asset, err)),
+            }
+        }
+        errors
+    }
+
+    /// Returns the table rows of the fetched prices, in the selected currency
+    fn rows(
+        assets: &[Asset],
+        prices: &[Option<Price>],
+        args: &AppArgs,
+        cache: &mut Cache,
+    ) -> SolwalrsResult<Vec<PriceRow>> {
+        let currency = Currency::selected(args);
+        assets
+            .iter()
+            .zip(prices)
+            .filter_map(|(asset, price)| price.as_ref().map(|price| (asset, price)))
+            .map(|(asset, price)| {
+                Ok(PriceRow {
+                    asset: asset.symbol(),
+                    price: currency.format_price(currency.convert(
+                        price.data.price,
+                        args,
+                        cache,
+                    )?),
+                    change: price.data.price_change_24h,
+                    source: price
+                        .source
+                        .map(PriceProvider::name)
+                        .unwrap_or("unknown")
+                        .to_owned(),
+                    updated: format_age(price.age()),
+                })
+            })
+            .collect()
+    }
+
+    /// Print the update errors to stderr, like the command errors
+    fn report_errors(errors: Vec<(&Asset, SolwalrsError)>, args: &AppArgs) {
+        for (asset, err) in errors {
+            crate::error!(args, "Failed to get the price of {asset}: {err:?}");
+            eprintln!("Solwalrs: {err}");
+        }
+    }
+
+    /// Show the current prices, or refresh them with `--watch`
+    pub fn run(&self, args: &AppArgs, cache: &mut Cache) -> SolwalrsResult<()> {
+        let assets = self.assets();
+        let mut prices = vec![None; assets.len()];
+        let is_terminal = io::stdout().is_terminal();
+        let color = is_terminal && env::var_os("NO_COLOR").is_none();
+
+        let Some(interval) = self.watch else {
+            let mut errors = Self::update_prices(&assets, &mut prices, PRICE_TTL, args, cache);
+            if prices.iter().all(Option::is_none) {
+                // All the assets failed, return the error of the first one
+                return Err(errors.remove(0).1);
+            }
+            println!(
+                "{}",
+                render_table(&Self::rows(&assets, &prices, args, cache)?, color)
+            );
+            Self::report_errors(errors, args);
+            return Ok(());
+        };
+
+        crate::info!(args, "Watching the prices every {interval} seconds");
+        loop {
+            // The prices fetched in the last refresh are reused, so each price is requested once per interval
+            let errors = Self::update_prices(&assets, &mut prices, interval, args, cache);
+            let table = render_table(&Self::rows(&assets, &prices, args, cache)?, color);
+            if is_terminal {
+                // Clear the screen and move the cursor to the top
+                print!("\x1b[2J\x1b[H");
+            } else {
+                println!();
+            }
+            println!("{table}");
+            Self::report_errors(errors, args);
+            println!("Refreshing every {interval}s, press Ctrl-C to stop");
+            cache.save(args)?;
+            thread::sleep(Duration::from_secs(interval));
+        }
+    }
+}
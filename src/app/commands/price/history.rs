@@ -0,0 +1,173 @@
+// Solwalrs, A simple and easy to use CLI Solana wallet
+// Copyright (C) 2022  Solwalrs contributors <https://github.com/TheAwiteb/solwalrs/graphs/contributors>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/gpl-3.0.html>.
+
+use std::{
+    env,
+    io::{self, IsTerminal},
+};
+
+use clap::{Parser, ValueEnum};
+
+use super::chart;
+use super::current::{color_change, format_age};
+use crate::app::AppArgs;
+use crate::errors::{Error as SolwalrsError, Result as SolwalrsResult};
+use crate::utils;
+use crate::wallet::{Asset, Currency, HistoryRange, PriceHistory};
+
+/// The export formats of the price history
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum HistoryOutput {
+    /// Comma separated values, with a header line
+    Csv,
+    /// A JSON object with the prices
+    Json,
+}
+
+/// The styles of the price history chart
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ChartStyle {
+    /// A line chart of braille characters
+    Braille,
+    /// A single line of block characters
+    Sparkline,
+}
+
+/// Show the price history of an asset as a chart in the selected currency, or export it
+#[derive(Parser, Debug)]
+pub struct HistoryCommand {
+    /// The asset, `sol`, a supported token (`usdc`, `usdt`, `srm`) or a token mint
+    pub asset: Asset,
+    /// The time range of the history
+    #[clap(short, long, value_enum, default_value = "7d")]
+    pub range: HistoryRange,
+    /// Print the history as CSV or JSON instead of the chart, e.g. for spreadsheets
+    #[clap(long, value_enum)]
+    pub output: Option<HistoryOutput>,
+    /// The style of the chart
+    #[clap(short, long, value_enum, default_value = "braille")]
+    pub chart: ChartStyle,
+    /// The width of the chart, in characters
+    #[clap(long, default_value_t = 60, value_parser = clap::value_parser!(u16).range(10..))]
+    pub width: u16,
+    /// The height of the braille chart, in lines
+    #[clap(long, default_value_t = 10, value_parser = clap::value_parser!(u16).range(2..))]
+    pub height: u16,
+}
+
+/// Returns the UTC date of the timestamp, in the ISO 8601 format
+fn iso_date(timestamp: i64) -> String {
+    chrono::NaiveDateTime::from_timestamp_opt(timestamp, 0)
+        .map(|date| date.format("%Y-%m-%dT%H:%M:%SZ").to_string())
+        .unwrap_or_default()
+}
+
+impl HistoryCommand {
+    /// Print the history as CSV
+    fn print_csv(history: &PriceHistory) {
+        println!(
+            "timestamp,date,price_{}",
+            history.currency.code().to_lowercase()
+        );
+        for point in &history.prices {
+            println!(
+                "{},{},{}",
+                point.timestamp,
+                iso_date(point.timestamp),
+                point.price
+            );
+        }
+    }
+
+    /// Print the history as JSON
+    fn print_json(history: &PriceHistory) -> SolwalrsResult<()> {
+        let json = serde_json::json!({
+            "asset": history.symbol,
+            "currency": history.currency.code(),
+            "range": history.range.name(),
+            "prices": history.prices.iter().map(|point| serde_json::json!({
+                "timestamp": point.timestamp,
+                "date": iso_date(point.timestamp),
+                "price": point.price,
+            })).collect::<Vec<_>>(),
+        });
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&json).map_err(|err| SolwalrsError::Other(format!(
+                "Failed to serialize the price history: {err}"
+            )))?
+        );
+        Ok(())
+    }
+
+    /// Print the history chart, with the lowest and the highest prices on the axis
+    fn print_chart(&self, history: &PriceHistory) {
+        let color = io::stdout().is_terminal() && env::var_os("NO_COLOR").is_none();
+        let currency = &history.currency;
+        let prices: Vec<f64> = history.prices.iter().map(|point| point.price).collect();
+        let (low, high) = history.bounds();
+        let (low, high) = (currency.format_price(low), currency.format_price(high));
+        println!(
+            "{} price in the last {}, {} (updated {})",
+            history.symbol,
+            history.range.description(),
+            currency,
+            format_age(utils::get_timestamp().saturating_sub(history.timestamp))
+        );
+        match self.chart {
+            ChartStyle::Sparkline => println!(
+                "{low} {} {high}",
+                chart::sparkline(&prices, self.width.into())
+            ),
+            ChartStyle::Braille => {
+                let chart = chart::braille_chart(&prices, self.width.into(), self.height.into());
+                let label_width = low.chars().count().max(high.chars().count());
+                let last = chart.len() - 1;
+                for (idx, line) in chart.iter().enumerate() {
+                    let (label, axis) = match idx {
+                        0 => (high.as_str(), '┤'),
+                        idx if idx == last => (low.as_str(), '┤'),
+                        _ => ("", '│'),
+                    };
+                    println!("{label:>label_width$} {axis}{line}");
+                }
+            }
+        }
+        let (Some(open), Some(close)) = (history.prices.first(), history.prices.last()) else {
+            return;
+        };
+        println!(
+            "Open {}  Close {}  Change {}",
+            currency.format_price(open.price),
+            currency.format_price(close.price),
+            history
+                .change()
+                .map(|change| color_change(format!("{change:+.2}%"), change, color))
+                .unwrap_or_else(|| "n/a".to_owned())
+        );
+    }
+
+    /// Run the command
+    pub fn run(&self, args: &AppArgs) -> SolwalrsResult<()> {
+        let history = PriceHistory::get(&self.asset, self.range, &Currency::selected(args), args)?;
+        match self.output {
+            Some(HistoryOutput::Csv) => Self::print_csv(&history),
+            Some(HistoryOutput::Json) => Self::print_json(&history)?,
+            None => self.print_chart(&history),
+        }
+        Ok(())
+    }
+}
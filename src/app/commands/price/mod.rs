@@ -0,0 +1,69 @@
+// Solwalrs, A simple and easy to use CLI Solana wallet
+// Copyright (C) 2022  Solwalrs contributors <https://github.com/TheAwiteb/solwalrs/graphs/contributors>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/gpl-3.0.html>.
+
+mod chart;
+mod current;
+mod history;
+
+pub use current::PriceArgs;
+pub use history::HistoryCommand;
+
+use clap::{Parser, Subcommand};
+
+use crate::{app::AppArgs, errors::Result as SolwalrsResult, wallet::cache::Cache};
+
+/// Get the prices of SOL and tokens in the selected currency, from the first available price source,
+/// or show their price history
+#[derive(Parser, Debug)]
+#[clap(args_conflicts_with_subcommands = true)]
+pub struct PriceCommand {
+    #[clap(subcommand)]
+    pub command: Option<PriceSubcommand>,
+    #[clap(flatten)]
+    pub prices: PriceArgs,
+}
+
+/// Commands for the prices
+#[derive(Subcommand, Debug)]
+pub enum PriceSubcommand {
+    #[clap(visible_alias = "h")]
+    History(HistoryCommand),
+}
+
+impl PriceCommand {
+    /// Whether the wallet header should be printed, not with the exported history so it can be parsed
+    pub fn shows_header(&self) -> bool {
+        !matches!(
+            &self.command,
+            Some(PriceSubcommand::History(HistoryCommand {
+                output: Some(_),
+                ..
+            }))
+        )
+    }
+
+    /// Run the command
+    pub fn run(&self, args: &AppArgs, cache: &mut Cache) -> SolwalrsResult<()> {
+        use PriceSubcommand::*;
+
+        crate::info!(args, "The price command is: {self:?}");
+        match &self.command {
+            Some(History(command)) => command.run(args)?,
+            None => self.prices.run(args, cache)?,
+        };
+        Ok(())
+    }
+}
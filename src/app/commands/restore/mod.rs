@@ -0,0 +1,55 @@
+// Solwalrs, A simple and easy to use CLI Solana wallet
+// Copyright (C) 2022  Solwalrs contributors <https://github.com/TheAwiteb/solwalrs/graphs/contributors>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/gpl-3.0.html>.
+
+mod shamir;
+mod wallet;
+
+pub use shamir::ShamirCommand;
+pub use wallet::WalletFileCommand;
+
+use clap::Subcommand;
+
+use crate::{app::AppArgs, errors::Result as SolwalrsResult, wallet::Wallet};
+
+/// Commands for restoring the keypairs and the wallet from a backup
+#[derive(Subcommand, Debug)]
+pub enum RestoreCommand {
+    Shamir(ShamirCommand),
+    Wallet(WalletFileCommand),
+}
+
+impl RestoreCommand {
+    /// Whether the command needs the selected wallet, restoring the wallet password doesn't need it
+    /// and restoring the wallet file loads the copy instead
+    pub fn needs_wallet(&self) -> bool {
+        match self {
+            RestoreCommand::Shamir(command) => !command.password,
+            RestoreCommand::Wallet(_) => false,
+        }
+    }
+
+    /// Run the command
+    pub fn run(&self, wallet: &mut Wallet, args: &AppArgs) -> SolwalrsResult<()> {
+        use RestoreCommand::*;
+
+        crate::info!(args, "The restore command is: {self:?}");
+        match self {
+            Shamir(command) => command.run(wallet, args)?,
+            Wallet(command) => command.run(args)?,
+        };
+        Ok(())
+    }
+}
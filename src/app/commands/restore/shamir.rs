@@ -0,0 +1,114 @@
+// Solwalrs, A simple and easy to use CLI Solana wallet
+// Copyright (C) 2022  Solwalrs contributors <https://github.com/TheAwiteb/solwalrs/graphs/contributors>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/gpl-3.0.html>.
+
+use clap::Parser;
+use zeroize::Zeroizing;
+
+use crate::app::AppArgs;
+use crate::errors::{Error as SolwalrsError, Result as SolwalrsResult};
+use crate::wallet::{
+    recover_secret, short_public_key, ImportType, KeyPair, SecretKind, SecretShare, Wallet,
+};
+
+/// Recover a keypair or the wallet password from its Shamir shares (input prompt).
+///
+/// The shares are entered one by one, until the threshold is reached
+#[derive(Parser, Debug)]
+pub struct ShamirCommand {
+    /// The name of the restored keypair
+    #[clap(required_unless_present = "password")]
+    pub name: Option<String>,
+    /// Whether to make the restored keypair the default keypair
+    #[clap(short, long)]
+    pub default: bool,
+    /// Recover the wallet password instead of a keypair, it will be printed
+    #[clap(long, conflicts_with_all = ["name", "default"])]
+    pub password: bool,
+}
+
+impl ShamirCommand {
+    /// Prompt for the shares until the threshold of the first share is reached, an empty share cancels
+    fn read_shares(&self, args: &AppArgs) -> SolwalrsResult<Vec<SecretShare>> {
+        let mut shares: Vec<SecretShare> = Vec::new();
+        while shares
+            .first()
+            .is_none_or(|first| shares.len() < first.threshold as usize)
+        {
+            let input = Zeroizing::new(
+                rpassword::prompt_password(format!("Enter the share #{}: ", shares.len() + 1))
+                    .map_err(|err| {
+                        SolwalrsError::Other(format!("Faild to read from stdin: {err}"))
+                    })?,
+            );
+            if input.trim().is_empty() {
+                return Err(SolwalrsError::Cancelled);
+            }
+            match SecretShare::decode(&input) {
+                Ok(share)
+                    if shares
+                        .iter()
+                        .any(|entered| entered.index() == share.index()) =>
+                {
+                    println!("The share {} is already entered", share.index())
+                }
+                Ok(share) => {
+                    crate::info!(args, "The share {} is entered", share.index());
+                    shares.push(share)
+                }
+                Err(err) => println!("{err}, try again"),
+            }
+        }
+        Ok(shares)
+    }
+
+    /// Run the command
+    pub fn run(&self, wallet: &mut Wallet, args: &AppArgs) -> SolwalrsResult<()> {
+        let shares = self.read_shares(args)?;
+        let (kind, secret) = recover_secret(&shares)?;
+        crate::info!(args, "The {kind} is recovered from {} shares", shares.len());
+        match (kind, &self.name) {
+            (SecretKind::Password, None) => {
+                let password =
+                    Zeroizing::new(String::from_utf8(secret.to_vec()).map_err(|_| {
+                        SolwalrsError::Other("The recovered password is not valid UTF-8".to_owned())
+                    })?);
+                println!("The wallet password is: {}", *password);
+            }
+            (SecretKind::KeyPair, Some(name)) => {
+                let keypair =
+                    KeyPair::import(name, ImportType::from_bytes(secret)?, self.default, args)?;
+                println!(
+                    "Keypair `{name}` restored successfully. His public key is `{}`",
+                    short_public_key(&keypair.public_key)
+                );
+                wallet.add_keypair(keypair, args)?;
+            }
+            (SecretKind::Password, Some(_)) => {
+                return Err(SolwalrsError::Other(
+                    "The shares are of the wallet password, use `--password` to recover it"
+                        .to_owned(),
+                ))
+            }
+            (SecretKind::KeyPair, None) => {
+                return Err(SolwalrsError::Other(
+                    "The shares are of a keypair, enter its name instead of `--password`"
+                        .to_owned(),
+                ))
+            }
+        }
+        Ok(())
+    }
+}
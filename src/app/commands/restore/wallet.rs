@@ -0,0 +1,82 @@
+// Solwalrs, A simple and easy to use CLI Solana wallet
+// Copyright (C) 2022  Solwalrs contributors <https://github.com/TheAwiteb/solwalrs/graphs/contributors>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/gpl-3.0.html>.
+
+use std::path::PathBuf;
+
+use clap::Parser;
+
+use crate::app::AppArgs;
+use crate::errors::{Error as SolwalrsError, Result as SolwalrsResult};
+use crate::utils;
+use crate::wallet::{app_file_path, sibling_path, AgentClient, Wallet, WalletLock};
+
+/// Restore the wallet file from a copy of it, by default the previous version `solwalrs.json.bak`.
+///
+/// The copy is verified with the wallet password, then it's saved as a new revision of the wallet file,
+/// so it isn't rejected as a rolled back wallet. The replaced wallet file is kept as the new `.bak` file
+#[derive(Parser, Debug)]
+pub struct WalletFileCommand {
+    /// The copy of the wallet file, default is the `.bak` file next to the wallet file
+    pub file: Option<PathBuf>,
+}
+
+impl WalletFileCommand {
+    /// Run the command
+    pub fn run(&self, args: &AppArgs) -> SolwalrsResult<()> {
+        let _lock = WalletLock::acquire(args)?;
+        // The agent would overwrite the restored wallet with the one it holds
+        if AgentClient::connect(args)?.is_some() {
+            return Err(SolwalrsError::Other(
+                "The session agent is running, stop it using `solwalrs agent stop` before restoring the wallet"
+                    .to_owned(),
+            ));
+        }
+        let app_file = app_file_path(args)?;
+        let backup = self
+            .file
+            .clone()
+            .unwrap_or_else(|| sibling_path(&app_file, "bak"));
+        if !backup.exists() {
+            return Err(SolwalrsError::Wallet(format!(
+                "There is no wallet file copy at `{}`",
+                backup.display()
+            )));
+        }
+
+        let password = utils::get_password(args)?;
+        let mut wallet = Wallet::load_backup(&backup, &password, args)?;
+        println!(
+            "The copy `{}` is verified, it's the revision {} with {} keypairs and {} contacts",
+            backup.display(),
+            wallet.revision,
+            wallet.keypairs.len(),
+            wallet.contacts.len()
+        );
+        if !utils::confirm("Replace the wallet file with it?", args)? {
+            return Err(SolwalrsError::Cancelled);
+        }
+        let replaced = app_file.exists();
+        wallet.export(&password, args)?;
+        println!("The wallet file is restored successfully");
+        if replaced {
+            println!(
+                "The replaced wallet file is kept in `{}`",
+                sibling_path(&app_file, "bak").display()
+            );
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,43 @@
+// Solwalrs, A simple and easy to use CLI Solana wallet
+// Copyright (C) 2022  Solwalrs contributors <https://github.com/TheAwiteb/solwalrs/graphs/contributors>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/gpl-3.0.html>.
+
+mod show;
+
+pub use show::ShowCommand;
+
+use clap::Subcommand;
+
+use crate::{app::AppArgs, errors::Result as SolwalrsResult, wallet::Wallet};
+
+/// Commands for inspecting transactions
+#[derive(Subcommand, Debug)]
+pub enum TxCommand {
+    #[clap(visible_alias = "s")]
+    Show(ShowCommand),
+}
+
+impl TxCommand {
+    /// Run the command
+    pub fn run(&self, wallet: &mut Wallet, args: &AppArgs) -> SolwalrsResult<()> {
+        use TxCommand::*;
+
+        crate::info!(args, "The tx command is: {self:?}");
+        match self {
+            Show(command) => command.run(wallet, args)?,
+        };
+        Ok(())
+    }
+}
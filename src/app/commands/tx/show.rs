@@ -0,0 +1,213 @@
+// Solwalrs, A simple and easy to use CLI Solana wallet
+// Copyright (C) 2022  Solwalrs contributors <https://github.com/TheAwiteb/solwalrs/graphs/contributors>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/gpl-3.0.html>.
+
+use clap::Parser;
+use solana_sdk::native_token::lamports_to_sol;
+use solana_transaction_status::option_serializer::OptionSerializer;
+
+use crate::app::AppArgs;
+use crate::errors::Result as SolwalrsResult;
+use crate::wallet::{
+    Asset, Currency, DecodedInstruction, PriceHistory, TokenBalanceChange, TransactionDetails,
+    Wallet,
+};
+
+/// Show the details of a transaction, by its signature or the base64 encoded transaction
+#[derive(Parser, Debug)]
+pub struct ShowCommand {
+    /// The signature of the transaction, or the base64 encoded transaction to decode locally
+    pub transaction: String,
+    /// Show the value of the fee and the SOL balance changes at the block time, in the selected currency.
+    /// The SOL price is from the price history, the transactions older than a year can't be valued
+    #[clap(long)]
+    pub value: bool,
+}
+
+/// Print the given instruction, `index` is the position of the instruction in the transaction
+fn print_instruction(index: &str, instruction: &DecodedInstruction, wallet: &Wallet) {
+    println!(
+        "  #{index} {}: {}",
+        instruction.program, instruction.instruction_type
+    );
+    for (key, value) in &instruction.info {
+        println!("      {key}: {}", wallet.annotate_address(value));
+    }
+}
+
+/// Print the token balance changes of the transaction
+fn print_token_balances(changes: &[TokenBalanceChange], account_keys: &[String], wallet: &Wallet) {
+    println!("Token balances:");
+    for change in changes {
+        let owner = change
+            .owner
+            .as_ref()
+            .map(|owner| format!(", owner {}", wallet.annotate_address(owner)))
+            .unwrap_or_default();
+        let closed = if change.closed { ", closed" } else { "" };
+        println!(
+            "  #{} {} (mint {}{owner}{closed}): {} -> {} ({:+})",
+            change.account_index,
+            account_keys
+                .get(change.account_index as usize)
+                .map(String::as_str)
+                .unwrap_or("<unknown>"),
+            change.mint,
+            change.pre,
+            change.post,
+            change.post - change.pre
+        );
+    }
+}
+
+impl ShowCommand {
+    /// Returns the SOL price at the block time in the selected currency, `None` if it's unknown
+    fn sol_price(&self, details: &TransactionDetails, args: &AppArgs) -> Option<f64> {
+        let block_time = details.block_time?;
+        let currency = Currency::selected(args);
+        match PriceHistory::price_at(&Asset::Sol, block_time, &currency, args) {
+            Ok(Some(price)) => {
+                println!(
+                    "SOL price at the block time: {}",
+                    currency.format_price(price)
+                );
+                Some(price)
+            }
+            Ok(None) => {
+                println!(
+                    "SOL price at the block time: unknown, the transaction is older than a year"
+                );
+                None
+            }
+            Err(err) => {
+                println!("SOL price at the block time: unknown, {err}");
+                None
+            }
+        }
+    }
+
+    /// Run the command
+    pub fn run(&self, wallet: &mut Wallet, args: &AppArgs) -> SolwalrsResult<()> {
+        let details = TransactionDetails::from_input(&self.transaction, args)?;
+        let account_keys: Vec<_> = details
+            .account_keys()
+            .iter()
+            .map(|key| key.to_string())
+            .collect();
+
+        if let Some(signature) = details.signature() {
+            println!("Signature: {signature}");
+        }
+        if let Some(slot) = details.slot {
+            println!("Slot: {slot}");
+        }
+        if let Some(block_time) = details.block_time {
+            if let Some(date) = chrono::NaiveDateTime::from_timestamp_opt(block_time, 0) {
+                println!("Block time: {} UTC", date.format("%Y-%m-%d %H:%M:%S"));
+            }
+        }
+        let sol_price = self.value.then(|| self.sol_price(&details, args)).flatten();
+        let currency = Currency::selected(args);
+        // The value of the SOL amount at the block time, if `--value` is set
+        let value = |sol: f64| {
+            sol_price
+                .map(|price| format!(" ~{}", currency.format(sol * price)))
+                .unwrap_or_default()
+        };
+        if let Some(meta) = &details.meta {
+            match &meta.err {
+                Some(err) => println!("Status: Failed, {err}"),
+                None => println!("Status: Success"),
+            }
+            println!(
+                "Fee: {} SOL ({} lamports){}",
+                lamports_to_sol(meta.fee),
+                meta.fee,
+                value(lamports_to_sol(meta.fee))
+            );
+            if let OptionSerializer::Some(units) = meta.compute_units_consumed {
+                println!("Compute units consumed: {units}");
+            }
+        } else {
+            println!("Status: Decoded locally, not fetched from the cluster");
+        }
+
+        println!("Accounts:");
+        for (idx, address) in account_keys.iter().enumerate() {
+            let mut flags = Vec::new();
+            if details.is_signer(idx) {
+                flags.push("signer");
+            }
+            if details.is_writable(idx) {
+                flags.push("writable");
+            }
+            let balances = details
+                .meta
+                .as_ref()
+                .and_then(|meta| Some((meta.pre_balances.get(idx)?, meta.post_balances.get(idx)?)))
+                .map(|(pre, post)| {
+                    let change = lamports_to_sol(*post) - lamports_to_sol(*pre);
+                    format!(
+                        ": {} SOL -> {} SOL ({change:+} SOL{})",
+                        lamports_to_sol(*pre),
+                        lamports_to_sol(*post),
+                        if change != 0.0 {
+                            value(change)
+                        } else {
+                            String::new()
+                        }
+                    )
+                })
+                .unwrap_or_default();
+            println!(
+                "  #{idx} {} [{}]{balances}",
+                wallet.annotate_address(address),
+                flags.join(", ")
+            );
+        }
+
+        // The accounts closed by the transaction are shown too, even if there are no post balances
+        let token_balances = details.token_balance_changes();
+        if !token_balances.is_empty() {
+            print_token_balances(&token_balances, &account_keys, wallet);
+        }
+
+        println!("Instructions:");
+        let inner_instructions = details.inner_instructions();
+        for (idx, instruction) in details.instructions().iter().enumerate() {
+            print_instruction(&(idx + 1).to_string(), instruction, wallet);
+            if let Some((_, inner)) = inner_instructions
+                .iter()
+                .find(|(parent, _)| *parent as usize == idx)
+            {
+                for (inner_idx, instruction) in inner.iter().enumerate() {
+                    print_instruction(
+                        &format!("{}.{}", idx + 1, inner_idx + 1),
+                        instruction,
+                        wallet,
+                    );
+                }
+            }
+        }
+
+        if let Some(OptionSerializer::Some(logs)) = details.meta.as_ref().map(|m| &m.log_messages) {
+            println!("Logs:");
+            for log in logs {
+                println!("  {log}");
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,106 @@
+// Solwalrs, A simple and easy to use CLI Solana wallet
+// Copyright (C) 2022  Solwalrs contributors <https://github.com/TheAwiteb/solwalrs/graphs/contributors>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/gpl-3.0.html>.
+
+use clap::{Parser, ValueEnum};
+
+use crate::app::AppArgs;
+use crate::errors::Result as SolwalrsResult;
+use crate::wallet::{Currency, PriceProvider, Profiles};
+
+/// Show or change the settings of a wallet profile
+#[derive(Parser, Debug)]
+pub struct ConfigCommand {
+    /// The name of the profile, default is the selected profile
+    pub name: Option<String>,
+    /// Set the cluster of the profile, can be repeated (or comma separated) to set multiple endpoints
+    #[clap(short, long, value_delimiter = ',')]
+    pub cluster: Vec<url::Url>,
+    /// Set the price sources of the profile in priority order
+    #[clap(short, long, value_enum, value_delimiter = ',')]
+    pub price_sources: Vec<PriceProvider>,
+    /// Reset the price sources of the profile to the default order
+    #[clap(long, conflicts_with = "price_sources")]
+    pub reset_price_sources: bool,
+    /// Set the fiat currency of the profile, an ISO 4217 code e.g. `EUR`
+    #[clap(long)]
+    pub currency: Option<Currency>,
+}
+
+impl ConfigCommand {
+    /// Whether the command changes the profile settings
+    fn changes(&self) -> bool {
+        !self.cluster.is_empty()
+            || !self.price_sources.is_empty()
+            || self.reset_price_sources
+            || self.currency.is_some()
+    }
+
+    /// Show the profile settings, after changing them if any option is set
+    pub fn run(&self, profiles: &mut Profiles, args: &AppArgs) -> SolwalrsResult<()> {
+        let name = self
+            .name
+            .as_deref()
+            .or(args.profile.as_deref())
+            .unwrap_or(profiles.active_name())
+            .to_owned();
+        let mut profile = profiles.get(&name)?;
+        if self.changes() {
+            if !self.cluster.is_empty() {
+                profile.cluster = self.cluster.clone();
+            }
+            if !self.price_sources.is_empty() || self.reset_price_sources {
+                profile.price_sources = self.price_sources.clone();
+            }
+            if let Some(currency) = &self.currency {
+                // USD is the default currency, so it's not stored
+                profile.currency = (currency != &Currency::default()).then(|| currency.clone());
+            }
+            profiles.update(profile.clone())?;
+            crate::info!(args, "The profile after the changes: {profile:?}");
+            println!("Wallet profile `{name}` updated successfully");
+        }
+
+        println!(
+            "Cluster: {}",
+            profile
+                .cluster
+                .iter()
+                .map(|url| url.as_str())
+                .collect::<Vec<_>>()
+                .join(", ")
+        );
+        let price_sources = if profile.price_sources.is_empty() {
+            PriceProvider::value_variants().to_vec()
+        } else {
+            profile.price_sources.clone()
+        };
+        println!(
+            "Price sources: {}{}",
+            price_sources
+                .iter()
+                .map(|provider| provider.name())
+                .collect::<Vec<_>>()
+                .join(", "),
+            if profile.price_sources.is_empty() {
+                " (default)"
+            } else {
+                ""
+            }
+        );
+        println!("Currency: {}", profile.currency.clone().unwrap_or_default());
+        Ok(())
+    }
+}
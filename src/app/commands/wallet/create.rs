@@ -0,0 +1,72 @@
+// Solwalrs, A simple and easy to use CLI Solana wallet
+// Copyright (C) 2022  Solwalrs contributors <https://github.com/TheAwiteb/solwalrs/graphs/contributors>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/gpl-3.0.html>.
+
+use clap::Parser;
+
+use crate::app::AppArgs;
+use crate::errors::Result as SolwalrsResult;
+use crate::wallet::{default_cluster, Currency, PriceProvider, Profile, Profiles};
+
+/// Create a new wallet profile, with its own wallet file and password
+#[derive(Parser, Debug)]
+pub struct CreateCommand {
+    /// The name of the profile
+    pub name: String,
+    /// The cluster of the profile, default is `https://api.mainnet-beta.solana.com`.
+    ///
+    /// Can be repeated (or comma separated) to set multiple endpoints
+    #[clap(short, long, value_delimiter = ',')]
+    pub cluster: Vec<url::Url>,
+    /// The price sources of the profile in priority order, default is `solscan,coingecko,jupiter,pyth`
+    #[clap(short, long, value_enum, value_delimiter = ',')]
+    pub price_sources: Vec<PriceProvider>,
+    /// The fiat currency of the profile, an ISO 4217 code e.g. `EUR`, default is `USD`
+    #[clap(long)]
+    pub currency: Option<Currency>,
+    /// Use the profile after creating it
+    #[clap(short, long = "use")]
+    pub use_it: bool,
+}
+
+impl CreateCommand {
+    /// Create a new wallet profile
+    pub fn run(&self, profiles: &mut Profiles, args: &AppArgs) -> SolwalrsResult<()> {
+        let cluster = if self.cluster.is_empty() {
+            default_cluster()
+        } else {
+            self.cluster.clone()
+        };
+        let mut profile = Profile::new(&self.name, cluster)?;
+        profile.price_sources = self.price_sources.clone();
+        profile.currency = self.currency.clone();
+        profiles.add(profile)?;
+        println!(
+            "Wallet profile `{}` created successfully, its password will be the password of its first keypair",
+            self.name
+        );
+        if self.use_it {
+            profiles.set_active(&self.name)?;
+            println!("Switched to the wallet profile `{}`", self.name);
+        } else {
+            println!(
+                "Use it with `solwalrs --wallet {0} <command>`, or switch to it with `solwalrs wallet use {0}`",
+                self.name
+            );
+        }
+        crate::info!(args, "The profiles after creating: {profiles:?}");
+        Ok(())
+    }
+}
@@ -0,0 +1,68 @@
+// Solwalrs, A simple and easy to use CLI Solana wallet
+// Copyright (C) 2022  Solwalrs contributors <https://github.com/TheAwiteb/solwalrs/graphs/contributors>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/gpl-3.0.html>.
+
+use clap::Parser;
+
+use crate::app::AppArgs;
+use crate::errors::Result as SolwalrsResult;
+use crate::wallet::{print_table, profile_file_path, Profiles, DEFAULT_PROFILE};
+
+/// List the wallet profiles
+#[derive(Parser, Debug)]
+pub struct ListCommand;
+
+impl ListCommand {
+    /// List the wallet profiles, the default profile is always listed
+    pub fn run(&self, profiles: &Profiles, args: &AppArgs) -> SolwalrsResult<()> {
+        crate::info!(args, "Listing {} profiles", profiles.profiles.len());
+        let mut names = vec![DEFAULT_PROFILE.to_owned()];
+        names.extend(
+            profiles
+                .profiles
+                .iter()
+                .map(|profile| profile.name.clone())
+                .filter(|name| name != DEFAULT_PROFILE),
+        );
+        let rows = names
+            .iter()
+            .map(|name| {
+                let profile = profiles.get(name)?;
+                Ok(vec![
+                    profile.name.clone(),
+                    profile
+                        .cluster
+                        .iter()
+                        .map(|url| url.as_str())
+                        .collect::<Vec<_>>()
+                        .join(", "),
+                    if profile.name == profiles.active_name() {
+                        "Yes".to_owned()
+                    } else {
+                        "No".to_owned()
+                    },
+                    profile_file_path(name)?.display().to_string(),
+                ])
+            })
+            .collect::<SolwalrsResult<Vec<_>>>()?;
+        print_table(
+            vec!["Name", "Cluster", "Active", "Wallet file"],
+            rows.iter()
+                .map(|r| r.iter().map(|s| s.as_str()).collect())
+                .collect(),
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,80 @@
+// Solwalrs, A simple and easy to use CLI Solana wallet
+// Copyright (C) 2022  Solwalrs contributors <https://github.com/TheAwiteb/solwalrs/graphs/contributors>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/gpl-3.0.html>.
+
+use std::{fs::File, io::BufReader};
+
+use clap::Parser;
+use serde_json::Value;
+
+use crate::app::AppArgs;
+use crate::errors::{Error as SolwalrsError, Result as SolwalrsResult};
+use crate::wallet::{app_file_path, migrate, wallet_version, WALLET_VERSION};
+
+/// Migrate the wallet file to the current format version.
+///
+/// The wallet file is migrated when it's loaded anyway, this command shows what is changed
+#[derive(Parser, Debug)]
+pub struct MigrateCommand {
+    /// Report what would change without writing the wallet file, the password is not needed
+    #[clap(long)]
+    pub dry_run: bool,
+}
+
+impl MigrateCommand {
+    /// Print the migration steps of the wallet file
+    /// Note: You need to export the wallet after running this command, using `Wallet::export`
+    pub fn run(&self, args: &AppArgs) -> SolwalrsResult<()> {
+        let app_file = app_file_path(args)?;
+        if !app_file.exists() {
+            return Err(SolwalrsError::Wallet(format!(
+                "There is no wallet file at `{}`",
+                app_file.display()
+            )));
+        }
+        let file = File::open(&app_file)
+            .map_err(|err| SolwalrsError::Wallet(format!("Failed to open wallet file: {err}")))?;
+        let mut wallet: Value = serde_json::from_reader(BufReader::new(file)).map_err(|err| {
+            SolwalrsError::TamperedWallet(format!(
+                "it can't be parsed, it may be truncated ({err})"
+            ))
+        })?;
+        let version = wallet_version(&wallet)?;
+        let applied = migrate(&mut wallet, args)?;
+        if applied.is_empty() {
+            println!("The wallet file is already version {WALLET_VERSION}, nothing to migrate");
+            return Ok(());
+        }
+
+        println!("The wallet file is version {version}, the current version is {WALLET_VERSION}");
+        for step in applied {
+            println!(
+                "- Version {} to {}: {}",
+                step.migration.from,
+                step.migration.from + 1,
+                step.migration.description
+            );
+            for change in step.changes {
+                println!("  - {change}");
+            }
+        }
+        if self.dry_run {
+            println!("Dry run, the wallet file is not changed");
+        } else {
+            println!("The wallet file is migrated to version {WALLET_VERSION}");
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,87 @@
+// Solwalrs, A simple and easy to use CLI Solana wallet
+// Copyright (C) 2022  Solwalrs contributors <https://github.com/TheAwiteb/solwalrs/graphs/contributors>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/gpl-3.0.html>.
+
+mod config;
+mod create;
+mod list;
+mod migrate;
+mod remove;
+mod seal;
+mod switch;
+
+pub use config::ConfigCommand;
+pub use create::CreateCommand;
+pub use list::ListCommand;
+pub use migrate::MigrateCommand;
+pub use remove::RemoveCommand;
+pub use seal::SealCommand;
+pub use switch::UseCommand;
+
+use clap::Subcommand;
+
+use crate::{
+    app::AppArgs,
+    errors::Result as SolwalrsResult,
+    wallet::{Profiles, Wallet},
+};
+
+/// Commands for managing the wallet profiles.
+///
+/// Each profile has its own wallet file, password, cluster, price sources and default keypair
+#[derive(Subcommand, Debug)]
+pub enum WalletCommand {
+    #[clap(visible_alias = "new")]
+    Create(CreateCommand),
+    #[clap(visible_alias = "ls")]
+    List(ListCommand),
+    #[clap(visible_alias = "switch")]
+    Use(UseCommand),
+    #[clap(visible_alias = "rm")]
+    Remove(RemoveCommand),
+    Seal(SealCommand),
+    Migrate(MigrateCommand),
+    Config(ConfigCommand),
+}
+
+impl WalletCommand {
+    /// Whether the command needs the selected wallet, the profile commands and the dry run migration don't need it
+    pub fn needs_wallet(&self) -> bool {
+        match self {
+            WalletCommand::Seal(_) => true,
+            WalletCommand::Migrate(command) => !command.dry_run,
+            _ => false,
+        }
+    }
+
+    /// Run the command
+    pub fn run(&self, wallet: &mut Wallet, args: &AppArgs) -> SolwalrsResult<()> {
+        use WalletCommand::*;
+
+        crate::info!(args, "The wallet command is: {self:?}");
+        let mut profiles = Profiles::load(args)?;
+        match self {
+            Create(command) => command.run(&mut profiles, args)?,
+            List(command) => command.run(&profiles, args)?,
+            Use(command) => command.run(&mut profiles, args)?,
+            Remove(command) => command.run(&mut profiles, args)?,
+            Config(command) => command.run(&mut profiles, args)?,
+            // The seal and migrate commands don't change the profiles
+            Seal(command) => return command.run(wallet, args),
+            Migrate(command) => return command.run(args),
+        };
+        profiles.save(args)
+    }
+}
@@ -0,0 +1,70 @@
+// Solwalrs, A simple and easy to use CLI Solana wallet
+// Copyright (C) 2022  Solwalrs contributors <https://github.com/TheAwiteb/solwalrs/graphs/contributors>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/gpl-3.0.html>.
+
+use clap::Parser;
+
+use crate::app::AppArgs;
+use crate::errors::{Error as SolwalrsError, Result as SolwalrsResult};
+use crate::utils;
+use crate::wallet::{
+    profile_file_path, remove_wallet_backup, Profiles, Revisions, WalletLock, DEFAULT_PROFILE,
+};
+
+/// Remove a wallet profile and its wallet file
+#[derive(Parser, Debug)]
+pub struct RemoveCommand {
+    /// The name of the profile
+    pub name: String,
+}
+
+impl RemoveCommand {
+    /// Remove the profile and its wallet file, the default profile will be active if the removed profile is active
+    pub fn run(&self, profiles: &mut Profiles, args: &AppArgs) -> SolwalrsResult<()> {
+        if self.name == DEFAULT_PROFILE {
+            return Err(SolwalrsError::Other(
+                "The default profile can't be removed, use `solwalrs clean` to remove its wallet file"
+                    .to_owned(),
+            ));
+        }
+        let wallet_file = profile_file_path(&self.name)?;
+        profiles.get(&self.name)?;
+        if !utils::confirm(
+            &format!(
+                "The wallet profile `{}` and all of its keypairs will be removed, continue?",
+                self.name
+            ),
+            args,
+        )? {
+            return Err(SolwalrsError::Cancelled);
+        }
+        let wallet_args = AppArgs {
+            app_file: Some(wallet_file.display().to_string()),
+            ..args.clone()
+        };
+        let _lock = WalletLock::acquire(&wallet_args)?;
+        profiles.remove(&self.name)?;
+        if wallet_file.exists() {
+            crate::info!(args, "Removing the wallet file `{}`", wallet_file.display());
+            std::fs::remove_file(&wallet_file).map_err(|err| {
+                SolwalrsError::Wallet(format!("Failed to remove wallet file: {err}"))
+            })?;
+        }
+        remove_wallet_backup(&wallet_file, args)?;
+        Revisions::forget(&wallet_args)?;
+        println!("Wallet profile `{}` removed successfully", self.name);
+        Ok(())
+    }
+}
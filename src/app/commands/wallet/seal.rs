@@ -0,0 +1,50 @@
+// Solwalrs, A simple and easy to use CLI Solana wallet
+// Copyright (C) 2022  Solwalrs contributors <https://github.com/TheAwiteb/solwalrs/graphs/contributors>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/gpl-3.0.html>.
+
+use clap::Parser;
+
+use crate::app::AppArgs;
+use crate::errors::Result as SolwalrsResult;
+use crate::wallet::Wallet;
+
+/// Migrate the wallet file to the sealed format, it hides the number of keypairs and the default keypair.
+///
+/// All the keypairs and contacts are stored in one encrypted blob, padded to a size bucket
+#[derive(Parser, Debug)]
+pub struct SealCommand {
+    /// Migrate the wallet file back to the per-keypair format
+    #[clap(long)]
+    pub undo: bool,
+}
+
+impl SealCommand {
+    /// Set the format of the wallet file
+    /// Note: You need to export the wallet after running this command, using `Wallet::export`
+    pub fn run(&self, wallet: &mut Wallet, args: &AppArgs) -> SolwalrsResult<()> {
+        let sealed = !self.undo;
+        crate::info!(args, "Setting the sealed format to {sealed}");
+        match (wallet.sealed, sealed) {
+            (true, true) => println!("The wallet file is already sealed"),
+            (false, false) => println!("The wallet file is already in the per-keypair format"),
+            (false, true) => println!(
+                "The wallet file is sealed, the number of keypairs and the default keypair are hidden"
+            ),
+            (true, false) => println!("The wallet file is migrated to the per-keypair format"),
+        }
+        wallet.sealed = sealed;
+        Ok(())
+    }
+}
@@ -0,0 +1,38 @@
+// Solwalrs, A simple and easy to use CLI Solana wallet
+// Copyright (C) 2022  Solwalrs contributors <https://github.com/TheAwiteb/solwalrs/graphs/contributors>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/gpl-3.0.html>.
+
+use clap::Parser;
+
+use crate::app::AppArgs;
+use crate::errors::Result as SolwalrsResult;
+use crate::wallet::Profiles;
+
+/// Switch the active wallet profile
+#[derive(Parser, Debug)]
+pub struct UseCommand {
+    /// The name of the profile, `default` for the default profile
+    pub name: String,
+}
+
+impl UseCommand {
+    /// Set the active wallet profile
+    pub fn run(&self, profiles: &mut Profiles, args: &AppArgs) -> SolwalrsResult<()> {
+        profiles.set_active(&self.name)?;
+        crate::info!(args, "The active profile is `{}`", profiles.active_name());
+        println!("Switched to the wallet profile `{}`", self.name);
+        Ok(())
+    }
+}
@@ -20,7 +20,12 @@ use clap::Parser;
 pub use commands::*;
 
 use crate::errors::Result as SolwalrsResult;
-use crate::wallet::Wallet;
+use crate::wallet::{Commitment, Currency, PriceProvider, PriorityFee, Wallet};
+
+const PASSWORD_SOURCES: &str = "The wallet password is read from the first available source:
+  --password-file, --password-fd, --password-command, the `SOLWALRS_PASSWORD` environment variable,
+  otherwise it's prompted. Using the environment variable is not recommended, other processes may read it.
+  The trailing newline of the password is ignored, and it must be 32 bytes long.";
 
 const COPYRIGHT: &str = "Solwalrs  Copyright (C) 2022  Solwalrs contributors <https://github.com/TheAwiteb/solwalrs/graphs/contributors>
 This program comes with ABSOLUTELY NO WARRANTY; for details see <https://www.gnu.org/licenses/gpl-3.0.html>.
@@ -45,19 +50,68 @@ where
     }
 }
 
-#[derive(Parser, Debug)]
+#[derive(Parser, Debug, Clone)]
 pub struct AppArgs {
     /// The path to the app file
     ///
-    /// don't recommend to change this, default is `apps_data_directory/solwalrs.json`
+    /// don't recommend to change this, default is the wallet file of the selected profile.
+    /// The profiles will be ignored if this is set
     #[clap(long)]
     pub app_file: Option<String>,
+    /// The wallet profile to use, default is the active profile
+    #[clap(short, long)]
+    pub wallet: Option<String>,
+    /// The name of the selected profile, resolved from `--wallet` or the active profile
+    #[clap(skip)]
+    pub profile: Option<String>,
     /// Verbose mode, for debugging
     #[clap(short, long)]
     pub verbose: bool,
-    /// RPC URL, default is `https://api.mainnet-beta.solana.com`
-    #[clap(long, default_value = "https://api.mainnet-beta.solana.com")]
-    pub rpc: url::Url,
+    /// RPC URL, default is the cluster of the selected profile, or `https://api.mainnet-beta.solana.com`
+    ///
+    /// Can be repeated (or comma separated) to set multiple endpoints, the healthy and
+    /// non-lagging endpoint will be used, and the next one will be used if it fails
+    #[clap(long, value_delimiter = ',')]
+    pub rpc: Vec<url::Url>,
+    /// Skip the confirmation prompts, useful for scripts
+    #[clap(short, long)]
+    pub yes: bool,
+    /// The priority fee of the sent transactions, in micro-lamports per compute unit.
+    ///
+    /// Use `auto` to derive it from the recent prioritization fees of the written accounts
+    #[clap(long)]
+    pub priority_fee: Option<PriorityFee>,
+    /// The percentile of the recent prioritization fees to use with `--priority-fee auto`
+    #[clap(long, default_value = "75", value_parser = clap::value_parser!(u8).range(0..=100))]
+    pub priority_fee_percentile: u8,
+    /// The compute unit limit of the sent transactions
+    #[clap(long)]
+    pub compute_units: Option<u32>,
+    /// The commitment level to wait for after sending a transaction
+    #[clap(long, value_enum, default_value = "confirmed")]
+    pub commitment: Commitment,
+    /// The maximum time to wait for a transaction confirmation, in seconds
+    #[clap(long, default_value = "90")]
+    pub timeout: u64,
+    /// Read the wallet password from the given file, instead of prompting for it
+    #[clap(long, conflicts_with_all = ["password_fd", "password_command"])]
+    pub password_file: Option<std::path::PathBuf>,
+    /// Read the wallet password from the given file descriptor, e.g. `3` with `3<secret`
+    #[clap(long, conflicts_with = "password_command")]
+    pub password_fd: Option<u32>,
+    /// Run the given command and use its output as the wallet password, e.g. `pass show solwalrs`
+    #[clap(long)]
+    pub password_command: Option<String>,
+    /// The price sources to try in order, default is the price sources of the selected profile,
+    /// or `solscan,coingecko,jupiter,pyth`
+    ///
+    /// Can be repeated (or comma separated), the next source will be used if one fails
+    #[clap(long, value_enum, value_delimiter = ',')]
+    pub price_sources: Vec<PriceProvider>,
+    /// The fiat currency of the shown values, an ISO 4217 code e.g. `EUR`.
+    /// Default is the currency of the selected profile, or `USD`
+    #[clap(long)]
+    pub currency: Option<Currency>,
 }
 
 #[derive(Parser, Debug)]
@@ -75,10 +129,23 @@ pub enum Commands {
     Clean(CleanCommand),
     #[clap(visible_alias = "p")]
     Price(PriceCommand),
+    #[clap(subcommand)]
+    Tx(tx::TxCommand),
+    #[clap(subcommand, visible_alias = "c")]
+    Contacts(contacts::ContactsCommand),
+    #[clap(subcommand, visible_alias = "w")]
+    Wallet(wallet::WalletCommand),
+    #[clap(subcommand)]
+    Agent(agent::AgentCommand),
+    #[clap(subcommand)]
+    Backup(backup::BackupCommand),
+    #[clap(subcommand)]
+    Restore(restore::RestoreCommand),
+    Pay(pay::PayCommand),
 }
 
 #[derive(Parser, Debug)]
-#[clap(version, about, long_about = COPYRIGHT)]
+#[clap(version, about, long_about = COPYRIGHT, after_help = PASSWORD_SOURCES)]
 pub struct App {
     #[clap(subcommand)]
     pub command: Option<Commands>,
@@ -90,6 +157,23 @@ impl Commands {
     /// Whether the command needs a wallet
     pub fn needs_wallet(&self) -> bool {
         use Commands::*;
-        !matches!(self, Completions(_) | Clean(_) | Price(_))
+        match self {
+            Completions(_) | Clean(_) | Price(_) | Agent(_) => false,
+            Wallet(command) => command.needs_wallet(),
+            Backup(command) => command.needs_wallet(),
+            Restore(command) => command.needs_wallet(),
+            Pay(command) => command.needs_wallet(),
+            _ => true,
+        }
+    }
+
+    /// Whether the selected wallet should be printed before the command output
+    pub fn shows_header(&self) -> bool {
+        use Commands::*;
+        match self {
+            Completions(_) | Agent(agent::AgentCommand::Serve(_)) => false,
+            Price(command) => command.shows_header(),
+            _ => true,
+        }
     }
 }
@@ -17,6 +17,11 @@
 use std::process::{ExitCode as StdExitCode, Termination};
 use sysexits::ExitCode;
 
+/// The exit code when the payment is found but doesn't match the request, no other error uses it
+const PAYMENT_MISMATCH_EXIT_CODE: u8 = 3;
+/// The exit code when no payment is found before the timeout, no other error uses it
+const PAYMENT_TIMEOUT_EXIT_CODE: u8 = 4;
+
 /// Solwalrs errors
 #[derive(Debug, thiserror::Error)]
 pub enum Error {
@@ -32,6 +37,12 @@ pub enum Error {
     /// Error with the wallet
     #[error("{0}")]
     Wallet(String),
+    /// The wallet file failed the integrity check, it's modified, truncated or rolled back
+    #[error("The wallet file failed the integrity check, {0}. Restore it from a backup using `solwalrs restore wallet`")]
+    TamperedWallet(String),
+    /// The wallet file is locked by another Solwalrs process
+    #[error("The wallet `{0}` is in use by another Solwalrs process, try again when it's done")]
+    WalletInUse(String),
     /// Error with the keypair
     #[error("{0}")]
     Keypair(String),
@@ -41,6 +52,29 @@ pub enum Error {
     /// Error that keypair doesn't exist
     #[error("The keypair `{0}` doesn't exist")]
     KeyPairNotFound(String),
+    /// Error with the contact alias
+    #[error("The contact alias `@{0}` is already taken, please choose another alias")]
+    DuplicateContact(String),
+    /// Invalid contact alias
+    #[error("`@{0}` is not a valid contact alias, only letters, digits, `-` and `_` are allowed")]
+    InvalidContactAlias(String),
+    /// Error that contact doesn't exist
+    #[error("The contact `@{0}` doesn't exist")]
+    ContactNotFound(String),
+    /// Error with the profile name
+    #[error("The wallet profile `{0}` already exists, please choose another name")]
+    DuplicateProfile(String),
+    /// Error that profile doesn't exist
+    #[error(
+        "The wallet profile `{0}` doesn't exist, create it using `solwalrs wallet create {0}`"
+    )]
+    ProfileNotFound(String),
+    /// Invalid profile name
+    #[error("`{0}` is not a valid profile name, only letters, digits, `-` and `_` are allowed")]
+    InvalidProfileName(String),
+    /// Invalid address
+    #[error("`{0}` is not a valid address, contact `@alias` or keypair name")]
+    InvalidAddress(String),
     /// Invalid private key
     #[error("The private key of `{0}` is invalid")]
     InvalidPrivateKey(String),
@@ -57,6 +91,29 @@ pub enum Error {
     /// Request error
     #[error("Request error: {0}")]
     RequestError(String),
+    /// The transaction simulation failed
+    #[error("The transaction simulation failed: {0}")]
+    SimulationFailed(String),
+    /// The user didn't confirm the operation
+    #[error("The operation was cancelled")]
+    Cancelled,
+    /// The transaction failed
+    #[error("{0}")]
+    TransactionFailed(String),
+    /// The blockhash of the transaction expired before it's confirmed
+    #[error(
+        "The transaction `{0}` expired before it's confirmed, the blockhash is no longer valid"
+    )]
+    TransactionExpired(String),
+    /// The transaction isn't confirmed before the timeout
+    #[error("Timed out while waiting for the transaction `{0}` to be confirmed")]
+    Timeout(String),
+    /// The payment transaction doesn't match the Solana Pay request
+    #[error("The payment transaction `{0}` doesn't match the request, {1}")]
+    PaymentMismatch(String, String),
+    /// No payment is found before the timeout
+    #[error("Timed out while waiting for a payment with the reference `{0}`")]
+    PaymentTimeout(String),
     /// Other errors
     #[error("{0}")]
     Other(String),
@@ -68,8 +125,20 @@ impl Error {
         use Error::*;
         match self {
             AppDataDir(_) | IO(_) => ExitCode::IoErr.report(),
-            InvalidPassword(_) => ExitCode::DataErr.report(),
-            DuplicateKeyPairName(_) => ExitCode::Usage.report(),
+            InvalidPassword(_) | TamperedWallet(_) => ExitCode::DataErr.report(),
+            DuplicateKeyPairName(_)
+            | DuplicateContact(_)
+            | DuplicateProfile(_)
+            | InvalidProfileName(_)
+            | InvalidContactAlias(_) => ExitCode::Usage.report(),
+            // The transaction is rejected by the chain, in the simulation or after it's sent
+            TransactionFailed(_) | SimulationFailed(_) => ExitCode::Protocol.report(),
+            Cancelled => StdExitCode::FAILURE,
+            TransactionExpired(_) | WalletInUse(_) => ExitCode::TempFail.report(),
+            Timeout(_) => ExitCode::Unavailable.report(),
+            // The scripts depend on the `pay watch` results, so they have their own exit codes
+            PaymentMismatch(..) => StdExitCode::from(PAYMENT_MISMATCH_EXIT_CODE),
+            PaymentTimeout(_) => StdExitCode::from(PAYMENT_TIMEOUT_EXIT_CODE),
             _ => ExitCode::Software.report(),
         }
     }
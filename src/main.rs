@@ -14,8 +14,12 @@
 // You should have received a copy of the GNU General Public License
 // along with this program.  If not, see <https://www.gnu.org/licenses/gpl-3.0.html>.
 
+// The `ClientError` of `solana-client` is large, and it's returned from the `Rpc::call` closures
+#![allow(clippy::result_large_err)]
+
 mod app;
 mod errors;
+mod font;
 #[macro_use]
 mod log;
 mod utils;
@@ -23,27 +27,53 @@ mod wallet;
 
 use clap::Parser;
 use std::process::ExitCode as StdExitCode;
+use zeroize::Zeroizing;
 
 use crate::{
     app::App,
-    wallet::{cache::Cache, Wallet},
+    wallet::{cache::Cache, AgentClient, Profiles, WalletLock},
 };
 use errors::Result as SolwalrsResult;
 
-fn try_main(app: &App) -> SolwalrsResult<()> {
+fn try_main(app: &mut App) -> SolwalrsResult<()> {
     use app::Commands::*;
 
     info!(&app.args, "Solwalrs v{}", env!("CARGO_PKG_VERSION"));
+    Profiles::load(&app.args)?.apply(&mut app.args)?;
     info!(&app.args, "The app args is: {:?}", app.args);
     if let Some(command) = &app.command {
         info!(&app.args, "The command is {command:?}");
+        // The header of the output, to know which wallet is used
+        if command.shows_header() {
+            match (&app.args.profile, &app.args.app_file) {
+                (Some(profile), _) => println!("Wallet: {profile}"),
+                (None, Some(app_file)) => println!("Wallet: `{app_file}`"),
+                (None, None) => {}
+            }
+        }
 
-        let mut wallet = Wallet::new();
-        let mut password = String::new();
+        let mut wallet = wallet::Wallet::new();
+        let mut password = Zeroizing::new(String::new());
         let mut cache = Cache::load(&app.args)?;
+        // Only one process can use the wallet at a time, the lock is held until the wallet is saved
+        let _lock = command
+            .needs_wallet()
+            .then(|| WalletLock::acquire(&app.args))
+            .transpose()?;
+        // The wallet is loaded from the agent if it's running, otherwise the password is prompted
+        let agent = if command.needs_wallet() {
+            AgentClient::connect(&app.args)?
+        } else {
+            None
+        };
         if command.needs_wallet() {
-            password = utils::get_password()?;
-            wallet = Wallet::load(&password, &app.args)?;
+            match &agent {
+                Some(agent) => wallet = agent.load_wallet(&app.args)?,
+                None => {
+                    password = utils::get_password(&app.args)?;
+                    wallet = wallet::Wallet::load(&password, &app.args)?;
+                }
+            }
         }
 
         match command {
@@ -54,18 +84,28 @@ fn try_main(app: &App) -> SolwalrsResult<()> {
             Completions(completions_command) => completions_command.run(),
             Clean(clean_command) => clean_command.run(&app.args)?,
             Price(price_command) => price_command.run(&app.args, &mut cache)?,
+            Tx(tx_command) => tx_command.run(&mut wallet, &app.args)?,
+            Contacts(contacts_command) => contacts_command.run(&mut wallet, &app.args)?,
+            Wallet(wallet_command) => wallet_command.run(&mut wallet, &app.args)?,
+            Agent(agent_command) => agent_command.run(&app.args)?,
+            Backup(backup_command) => backup_command.run(&mut wallet, &app.args)?,
+            Restore(restore_command) => restore_command.run(&mut wallet, &app.args)?,
+            Pay(pay_command) => pay_command.run(&mut wallet, &app.args)?,
         };
         cache.save(&app.args)?;
         if command.needs_wallet() {
-            wallet.export(&password, &app.args)?;
+            match &agent {
+                Some(agent) => agent.store_wallet(&wallet, &app.args)?,
+                None => wallet.export(&password, &app.args)?,
+            }
         }
     }
     Ok(())
 }
 
 fn main() -> StdExitCode {
-    let app = App::parse();
-    if let Err(error) = try_main(&app) {
+    let mut app = App::parse();
+    if let Err(error) = try_main(&mut app) {
         error!(&app.args, "There is an error: {error:?}");
         eprintln!("Solwalrs: {error}");
         return error.exit_code();
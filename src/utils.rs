@@ -14,12 +14,119 @@
 // You should have received a copy of the GNU General Public License
 // along with this program.  If not, see <https://www.gnu.org/licenses/gpl-3.0.html>.
 
+use std::{
+    env, fs,
+    io::{self, Read, Write},
+    path::Path,
+    process::Command,
+};
+
 use super::errors::{Error as SolwalrsError, Result as SolwalrsResult};
+use crate::app::AppArgs;
+use crate::font::draw_text;
+use clap::ValueEnum;
+use image::{GrayImage, Luma};
+use zeroize::Zeroizing;
+
+/// The environment variable of the wallet password
+pub const PASSWORD_ENV: &str = "SOLWALRS_PASSWORD";
+
+/// Remove the trailing newline of the password, if any
+fn trim_newline(mut password: String) -> String {
+    if password.ends_with('\n') {
+        password.pop();
+        if password.ends_with('\r') {
+            password.pop();
+        }
+    }
+    password
+}
+
+/// Read the password from the given file
+fn read_password_file(path: &Path) -> SolwalrsResult<String> {
+    fs::read_to_string(path).map(trim_newline).map_err(|err| {
+        SolwalrsError::Other(format!(
+            "Failed to read the password file `{}`: {err}",
+            path.display()
+        ))
+    })
+}
+
+/// Read the password from the given file descriptor
+#[cfg(unix)]
+fn read_password_fd(fd: u32) -> SolwalrsResult<String> {
+    let mut password = String::new();
+    fs::File::open(format!("/dev/fd/{fd}"))
+        .and_then(|mut file| file.read_to_string(&mut password))
+        .map_err(|err| {
+            SolwalrsError::Other(format!(
+                "Failed to read the password from the file descriptor {fd}: {err}"
+            ))
+        })?;
+    Ok(trim_newline(password))
+}
+
+/// Read the password from the given file descriptor
+#[cfg(not(unix))]
+fn read_password_fd(_fd: u32) -> SolwalrsResult<String> {
+    Err(SolwalrsError::Other(
+        "The `--password-fd` option is only supported on Unix".to_owned(),
+    ))
+}
 
-/// Get the password from stdin, and return it as a String
-pub fn get_password() -> SolwalrsResult<String> {
-    let password = rpassword::prompt_password("Enter the wallet password: ")
-        .map_err(|err| SolwalrsError::Other(format!("Failed to get password: {}", err)))?;
+/// Run the given command with the shell, and returns its output as the password
+fn run_password_command(command: &str) -> SolwalrsResult<String> {
+    #[cfg(unix)]
+    let output = Command::new("sh").args(["-c", command]).output();
+    #[cfg(not(unix))]
+    let output = Command::new("cmd").args(["/C", command]).output();
+
+    let output = output.map_err(|err| {
+        SolwalrsError::Other(format!("Failed to run the password command: {err}"))
+    })?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(SolwalrsError::Other(format!(
+            "The password command failed with {}{}",
+            output.status,
+            if stderr.trim().is_empty() {
+                String::new()
+            } else {
+                format!(": {}", stderr.trim())
+            }
+        )));
+    }
+    String::from_utf8(output.stdout)
+        .map(trim_newline)
+        .map_err(|err| {
+            drop(Zeroizing::new(err.into_bytes()));
+            SolwalrsError::Other("The password command output is not UTF-8".to_owned())
+        })
+}
+
+/// Get the password from the first available source, `--password-file`, `--password-fd`,
+/// `--password-command` and `SOLWALRS_PASSWORD` environment variable, otherwise prompt for it.
+/// The password is zeroized on drop
+pub fn get_password(args: &AppArgs) -> SolwalrsResult<Zeroizing<String>> {
+    let password = Zeroizing::new(if let Some(path) = &args.password_file {
+        crate::info!(args, "Reading the password from `{}`", path.display());
+        read_password_file(path)?
+    } else if let Some(fd) = args.password_fd {
+        crate::info!(args, "Reading the password from the file descriptor {fd}");
+        read_password_fd(fd)?
+    } else if let Some(command) = &args.password_command {
+        crate::info!(args, "Reading the password from the command `{command}`");
+        run_password_command(command)?
+    } else if let Ok(password) = env::var(PASSWORD_ENV) {
+        prettylog::warn(&format!(
+            "Reading the wallet password from the `{PASSWORD_ENV}` environment variable, \
+            other processes of your user can read it. Prefer `--password-file` or `--password-command`"
+        ));
+        password
+    } else {
+        rpassword::prompt_password("Enter the wallet password: ")
+            .map_err(|err| SolwalrsError::Other(format!("Failed to get password: {}", err)))?
+    });
 
     if password.len() != 32 {
         return Err(SolwalrsError::InvalidPassword(
@@ -29,6 +136,377 @@ pub fn get_password() -> SolwalrsResult<String> {
     Ok(password)
 }
 
+/// Ask the user to confirm, returns `true` if the user answered with `y` or `yes`.
+/// Will return `true` without asking if the `--yes` flag is set
+pub fn confirm(message: &str, args: &AppArgs) -> SolwalrsResult<bool> {
+    if args.yes {
+        crate::info!(args, "Skipping the confirmation, the `--yes` flag is set");
+        return Ok(true);
+    }
+    print!("{message} [y/N]: ");
+    io::stdout()
+        .flush()
+        .map_err(|err| SolwalrsError::IO(format!("Failed to flush stdout: {err}")))?;
+    let mut answer = String::new();
+    io::stdin()
+        .read_line(&mut answer)
+        .map_err(|err| SolwalrsError::Other(format!("Failed to read from stdin: {err}")))?;
+    Ok(matches!(answer.trim().to_lowercase().as_str(), "y" | "yes"))
+}
+
+/// The output format of a QR code
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum QrFormat {
+    /// PNG image
+    Png,
+    /// SVG image
+    Svg,
+    /// Text with ANSI background colours, for the terminals
+    Ansi,
+    /// Plain ASCII text, two characters per module, easy to copy
+    Ascii,
+    /// Text with unicode half blocks, two modules per character
+    Unicode,
+}
+
+impl QrFormat {
+    /// Guess the format from the extension, `.svg`, `.txt` for ASCII, otherwise PNG
+    pub fn from_path(path: &Path) -> Self {
+        match path
+            .extension()
+            .map(|extension| extension.to_string_lossy().to_lowercase())
+            .as_deref()
+        {
+            Some("svg") => Self::Svg,
+            Some("txt") => Self::Ascii,
+            _ => Self::Png,
+        }
+    }
+
+    /// Whether the format is an image format
+    pub fn is_image(self) -> bool {
+        matches!(self, Self::Png | Self::Svg)
+    }
+}
+
+/// The error correction level of a QR code, higher levels can be read when the code is damaged but make it denser
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum QrEcLevel {
+    /// Recovers 7% of the data
+    L,
+    /// Recovers 15% of the data
+    M,
+    /// Recovers 25% of the data
+    Q,
+    /// Recovers 30% of the data
+    H,
+}
+
+impl From<QrEcLevel> for qrcode::EcLevel {
+    fn from(level: QrEcLevel) -> Self {
+        match level {
+            QrEcLevel::L => Self::L,
+            QrEcLevel::M => Self::M,
+            QrEcLevel::Q => Self::Q,
+            QrEcLevel::H => Self::H,
+        }
+    }
+}
+
+/// How a QR code is rendered
+#[derive(Debug, Clone)]
+pub struct QrStyle {
+    /// The minimum width of the images, in pixels
+    pub size: u32,
+    /// The width of the light margin around the QR code, in modules
+    pub quiet_zone: u32,
+    /// Swap the dark and light colours
+    pub invert: bool,
+    /// The caption lines under the QR code
+    pub caption: Vec<String>,
+}
+
+impl Default for QrStyle {
+    fn default() -> Self {
+        Self {
+            size: 256,
+            quiet_zone: 4,
+            invert: false,
+            caption: Vec::new(),
+        }
+    }
+}
+
+/// Create the QR code of the given data
+pub fn qr_code(data: &str) -> SolwalrsResult<qrcode::QrCode> {
+    qrcode::QrCode::new(data)
+        .map_err(|err| SolwalrsError::Other(format!("Failed to create the QR code: {err}")))
+}
+
+/// Render the QR code for the terminal, with the default style
+pub fn render_qr_code(qr_code: &qrcode::QrCode) -> String {
+    render_qr_text(qr_code, QrFormat::Unicode, &QrStyle::default())
+}
+
+/// Render the QR code as text, the default colours are for the terminals with a dark background.
+/// The caption lines are added under the QR code
+pub fn render_qr_text(qr_code: &qrcode::QrCode, format: QrFormat, style: &QrStyle) -> String {
+    let width = qr_code.width();
+    let quiet_zone = style.quiet_zone as usize;
+    let colors = qr_code.to_colors();
+    // The light modules are filled by default, so they're bright on a dark background
+    let is_filled = |x: usize, y: usize| {
+        let is_dark = x >= quiet_zone
+            && y >= quiet_zone
+            && x < width + quiet_zone
+            && y < width + quiet_zone
+            && colors[(y - quiet_zone) * width + x - quiet_zone] == qrcode::Color::Dark;
+        is_dark == style.invert
+    };
+    let size = width + 2 * quiet_zone;
+    let mut lines: Vec<String> = match format {
+        QrFormat::Unicode => (0..size)
+            .step_by(2)
+            .map(|y| {
+                (0..size)
+                    .map(
+                        |x| match (is_filled(x, y), y + 1 < size && is_filled(x, y + 1)) {
+                            (true, true) => '\u{2588}',
+                            (true, false) => '\u{2580}',
+                            (false, true) => '\u{2584}',
+                            (false, false) => ' ',
+                        },
+                    )
+                    .collect()
+            })
+            .collect(),
+        QrFormat::Ascii => (0..size)
+            .map(|y| {
+                (0..size)
+                    .map(|x| if is_filled(x, y) { "##" } else { "  " })
+                    .collect()
+            })
+            .collect(),
+        // The ANSI colours don't depend on the terminal background, white for the light modules
+        QrFormat::Ansi | QrFormat::Png | QrFormat::Svg => (0..size)
+            .map(|y| {
+                let line: String = (0..size)
+                    .map(|x| {
+                        if is_filled(x, y) {
+                            "\x1b[47m  "
+                        } else {
+                            "\x1b[40m  "
+                        }
+                    })
+                    .collect();
+                format!("{line}\x1b[0m")
+            })
+            .collect(),
+    };
+    lines.extend(style.caption.iter().cloned());
+    lines.join("\n")
+}
+
+/// Returns the dark and light colours of the QR code images
+fn qr_image_colors(style: &QrStyle) -> (u8, u8) {
+    if style.invert {
+        (255, 0)
+    } else {
+        (0, 255)
+    }
+}
+
+/// Render the QR code as a grayscale image, at least `style.size` pixels wide.
+/// The caption lines are drawn under the QR code, the image is widened if they don't fit
+pub fn render_qr_image(qr_code: &qrcode::QrCode, style: &QrStyle) -> GrayImage {
+    let (dark, light) = qr_image_colors(style);
+    let code = qrcode::render::Renderer::<Luma<u8>>::new(
+        &qr_code.to_colors(),
+        qr_code.width(),
+        style.quiet_zone,
+    )
+    .dark_color(Luma([dark]))
+    .light_color(Luma([light]))
+    .min_dimensions(style.size, style.size)
+    .build();
+    if style.caption.is_empty() {
+        return code;
+    }
+
+    let module = code.width() / (qr_code.width() as u32 + 2 * style.quiet_zone);
+    let longest = style
+        .caption
+        .iter()
+        .map(|line| line.chars().count() as u32)
+        .max()
+        .unwrap_or_default()
+        .max(1);
+    let scale = (code.width() / (longest * 6 + 2)).clamp(1, module.max(1));
+    let line_height = 10 * scale;
+    let width = code.width().max((longest * 6 + 2) * scale);
+    let height = code.height() + style.caption.len() as u32 * line_height + module.max(scale);
+    let mut image = GrayImage::from_pixel(width, height, Luma([light]));
+    image::imageops::replace(&mut image, &code, (width - code.width()) / 2, 0);
+    for (idx, line) in style.caption.iter().enumerate() {
+        let line_width = line.chars().count() as u32 * 6 * scale;
+        draw_text(
+            &mut image,
+            width.saturating_sub(line_width) / 2,
+            code.height() + idx as u32 * line_height,
+            scale,
+            line,
+            Luma([dark]),
+        );
+    }
+    image
+}
+
+/// Render the QR code as an SVG document, at least `style.size` pixels wide, with the caption under it
+pub fn render_qr_svg(qr_code: &qrcode::QrCode, style: &QrStyle) -> String {
+    let (dark, light) = qr_image_colors(style);
+    let width = qr_code.width() as u32;
+    let quiet_zone = style.quiet_zone;
+    let module = style.size.div_ceil(width + 2 * quiet_zone).max(1);
+    let size = (width + 2 * quiet_zone) * module;
+    let longest = style
+        .caption
+        .iter()
+        .map(|line| line.chars().count() as u32)
+        .max()
+        .unwrap_or_default()
+        .max(1);
+    // The monospace characters are about 0.6 of the font size wide
+    let font_size = (size * 10 / (longest * 6 + 2)).clamp(8, module.max(8) * 2);
+    let line_height = font_size * 5 / 4;
+    let height = if style.caption.is_empty() {
+        size
+    } else {
+        size + style.caption.len() as u32 * line_height + module
+    };
+
+    let path: String = qr_code
+        .to_colors()
+        .iter()
+        .enumerate()
+        .filter(|(_, color)| **color == qrcode::Color::Dark)
+        .map(|(idx, _)| {
+            format!(
+                "M{},{}h{module}v{module}h-{module}z",
+                (idx as u32 % width + quiet_zone) * module,
+                (idx as u32 / width + quiet_zone) * module
+            )
+        })
+        .collect();
+    let mut svg = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{size}\" height=\"{height}\" viewBox=\"0 0 {size} {height}\">\n\
+         <rect width=\"100%\" height=\"100%\" fill=\"rgb({light},{light},{light})\"/>\n\
+         <path d=\"{path}\" fill=\"rgb({dark},{dark},{dark})\" shape-rendering=\"crispEdges\"/>\n"
+    );
+    for (idx, line) in style.caption.iter().enumerate() {
+        svg.push_str(&format!(
+            "<text x=\"{}\" y=\"{}\" font-family=\"monospace\" font-size=\"{font_size}\" text-anchor=\"middle\" fill=\"rgb({dark},{dark},{dark})\">{}</text>\n",
+            size / 2,
+            size + (idx as u32 + 1) * line_height,
+            escape_xml(line)
+        ));
+    }
+    svg.push_str("</svg>\n");
+    svg
+}
+
+/// Save the QR code with the default style, the format is guessed from the extension
+pub fn save_qr_code(qr_code: &qrcode::QrCode, path: &Path, args: &AppArgs) -> SolwalrsResult<()> {
+    save_styled_qr_code(
+        qr_code,
+        path,
+        QrFormat::from_path(path),
+        &QrStyle::default(),
+        args,
+    )
+}
+
+/// Save the QR code in the given format and style, the text formats are saved as text files
+pub fn save_styled_qr_code(
+    qr_code: &qrcode::QrCode,
+    path: &Path,
+    format: QrFormat,
+    style: &QrStyle,
+    args: &AppArgs,
+) -> SolwalrsResult<()> {
+    crate::info!(args, "Saving QR code to {path:?} as {format:?}");
+    let result = match format {
+        // Other image formats are guessed from the extension, e.g. `.jpg`
+        QrFormat::Png => image::ImageFormat::from_path(path)
+            .or(Ok(image::ImageFormat::Png))
+            .and_then(|image_format| {
+                render_qr_image(qr_code, style).save_with_format(path, image_format)
+            })
+            .map_err(|err| err.to_string()),
+        QrFormat::Svg => {
+            fs::write(path, render_qr_svg(qr_code, style)).map_err(|err| err.to_string())
+        }
+        QrFormat::Ansi | QrFormat::Ascii | QrFormat::Unicode => {
+            fs::write(path, render_qr_text(qr_code, format, style) + "\n")
+                .map_err(|err| err.to_string())
+        }
+    };
+    result.map_err(|err| {
+        SolwalrsError::IO(format!(
+            "Failed to save QR code to {}: {err}",
+            path.display()
+        ))
+    })?;
+    crate::info!(args, "Saved QR code to `{path:?}`");
+    Ok(())
+}
+
+/// Read the QR code from the image at the given path
+pub fn read_qr_code(path: &Path, args: &AppArgs) -> SolwalrsResult<String> {
+    crate::info!(args, "Reading the QR code from {path:?}");
+    let image = image::open(path).map_err(|err| {
+        SolwalrsError::IO(format!(
+            "Failed to open the image {}: {err}",
+            path.display()
+        ))
+    })?;
+    let data = decode_qr_code(&image.to_luma8())?;
+    crate::info!(args, "The QR code data is `{data}`");
+    Ok(data)
+}
+
+/// Decode the QR code in the image, dark on light or light on dark
+fn decode_qr_code(image: &GrayImage) -> SolwalrsResult<String> {
+    [false, true]
+        .into_iter()
+        .find_map(|invert| {
+            let mut prepared = rqrr::PreparedImage::prepare_from_greyscale(
+                image.width() as usize,
+                image.height() as usize,
+                |x, y| {
+                    let Luma([luma]) = *image.get_pixel(x as u32, y as u32);
+                    if invert {
+                        u8::MAX - luma
+                    } else {
+                        luma
+                    }
+                },
+            );
+            prepared
+                .detect_grids()
+                .into_iter()
+                .find_map(|grid| grid.decode().ok().map(|(_, data)| data))
+        })
+        .ok_or_else(|| SolwalrsError::Other("No readable QR code is found in the image".to_owned()))
+}
+
+/// Escape the XML special characters of the text
+pub fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
 /// Return current timestamp in seconds (since the UNIX epoch)
 pub fn get_timestamp() -> u64 {
     std::time::SystemTime::now()
@@ -36,3 +514,138 @@ pub fn get_timestamp() -> u64 {
         .unwrap()
         .as_secs()
 }
+
+#[cfg(test)]
+mod tests {
+    use clap::Parser;
+
+    use super::*;
+
+    #[test]
+    fn trim_newline_in_place() {
+        let password = String::from("0123456789abcdef0123456789abcdef\r\n");
+        let ptr = password.as_ptr();
+        let password = trim_newline(password);
+        assert_eq!(password, "0123456789abcdef0123456789abcdef");
+        // The password isn't copied into a new buffer
+        assert_eq!(password.as_ptr(), ptr);
+        assert_eq!(trim_newline("password\n\n".to_owned()), "password\n");
+    }
+
+    #[test]
+    fn password_file_is_validated() {
+        let path = env::temp_dir().join(format!("solwalrs-password-{}", std::process::id()));
+        let args = AppArgs::parse_from(["solwalrs", "--password-file", path.to_str().unwrap()]);
+
+        fs::write(&path, "0123456789abcdef0123456789abcdef\n").unwrap();
+        let password: Zeroizing<String> = get_password(&args).unwrap();
+        assert_eq!(password.as_str(), "0123456789abcdef0123456789abcdef");
+
+        fs::write(&path, "short\n").unwrap();
+        let result = get_password(&args);
+        fs::remove_file(&path).unwrap();
+        assert!(matches!(result, Err(SolwalrsError::InvalidPassword(_))));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn password_command_output() {
+        let args = AppArgs::parse_from([
+            "solwalrs",
+            "--password-command",
+            "echo 0123456789abcdef0123456789abcdef",
+        ]);
+        assert_eq!(
+            get_password(&args).unwrap().as_str(),
+            "0123456789abcdef0123456789abcdef"
+        );
+        let args = AppArgs::parse_from(["solwalrs", "--password-command", "exit 1"]);
+        assert!(get_password(&args).is_err());
+    }
+
+    #[test]
+    fn qr_text_formats() {
+        let qr_code = qr_code("solwalrs").unwrap();
+        let size = qr_code.width() + 8;
+        let style = QrStyle {
+            caption: vec!["caption".to_owned()],
+            ..QrStyle::default()
+        };
+        let ascii = render_qr_text(&qr_code, QrFormat::Ascii, &style);
+        let lines: Vec<_> = ascii.lines().collect();
+        assert_eq!(lines.len(), size + 1);
+        assert!(lines[..size].iter().all(|line| line.len() == size * 2));
+        // The quiet zone is filled, the first finder pattern starts after it
+        assert_eq!(&lines[4][..10], "########  ");
+        assert_eq!(lines[size], "caption");
+
+        let inverted = render_qr_text(
+            &qr_code,
+            QrFormat::Ascii,
+            &QrStyle {
+                invert: true,
+                quiet_zone: 0,
+                ..QrStyle::default()
+            },
+        );
+        // The dark modules are filled, the finder pattern is 7 modules wide then the separator
+        assert!(inverted.starts_with("##############  "));
+        let unicode = render_qr_code(&qr_code);
+        assert_eq!(unicode.lines().count(), size.div_ceil(2));
+        assert!(unicode.lines().all(|line| line.chars().count() == size));
+    }
+
+    #[test]
+    fn qr_image_with_caption() {
+        let address = "7Tf3pF3uaGYWMfXRSKxXdoFFBmpzxyyFG1NUyTczCshP";
+        let qr_code = qr_code(address).unwrap();
+        for invert in [false, true] {
+            let style = QrStyle {
+                size: 300,
+                invert,
+                caption: vec!["alice".to_owned(), address.to_owned()],
+                ..QrStyle::default()
+            };
+            let image = render_qr_image(&qr_code, &style);
+            assert!(image.width() >= 300);
+            assert!(image.height() > image.width());
+            assert_eq!(decode_qr_code(&image).unwrap(), address);
+
+            let svg = render_qr_svg(&qr_code, &style);
+            assert!(svg.contains(&format!(">{address}</text>")));
+        }
+    }
+
+    #[test]
+    fn decode_qr_images() {
+        let data = "solana:https%3A%2F%2Fexample.com%2Fsolana-pay%3Forder%3D12345";
+        let image = qrcode::QrCode::new(data)
+            .unwrap()
+            .render::<Luma<u8>>()
+            .module_dimensions(5, 5)
+            .build();
+        assert_eq!(decode_qr_code(&image).unwrap(), data);
+        assert_eq!(
+            decode_qr_code(&image::imageops::rotate90(&image)).unwrap(),
+            data
+        );
+        // Scaled by a fraction, the modules are not aligned to the pixels
+        let scaled = image::imageops::resize(
+            &image,
+            image.width() * 7 / 5,
+            image.height() * 7 / 5,
+            image::imageops::FilterType::Triangle,
+        );
+        assert_eq!(decode_qr_code(&scaled).unwrap(), data);
+        // Light modules on a dark background, with a margin
+        let mut inverted = image.clone();
+        image::imageops::invert(&mut inverted);
+        let mut framed =
+            GrayImage::from_pixel(inverted.width() + 80, inverted.height() + 40, Luma([30]));
+        image::imageops::overlay(&mut framed, &inverted, 50, 10);
+        assert_eq!(decode_qr_code(&framed).unwrap(), data);
+
+        let noise = GrayImage::from_fn(200, 100, |x, y| Luma([((x * y) % 256) as u8]));
+        assert!(decode_qr_code(&noise).is_err());
+    }
+}
@@ -0,0 +1,470 @@
+// Solwalrs, A simple and easy to use CLI Solana wallet
+// Copyright (C) 2022  Solwalrs contributors <https://github.com/TheAwiteb/solwalrs/graphs/contributors>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/gpl-3.0.html>.
+
+//! The session agent, holds the decrypted wallet in memory behind a Unix socket,
+//! so the password is entered once per session instead of on every command.
+//!
+//! The protocol is one JSON request and one JSON response per connection, each on a single line.
+
+use std::{
+    io::{BufRead, BufReader, Write},
+    path::{Path, PathBuf},
+    time::{Duration, Instant},
+};
+
+use base58::FromBase58;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use zeroize::{Zeroize, Zeroizing};
+
+use super::{utils, Contact, ImportType, KeyPair, Wallet};
+use crate::{
+    app::AppArgs,
+    errors::{Error as SolwalrsError, Result as SolwalrsResult},
+};
+
+/// The default idle timeout of the agent, in seconds
+pub const DEFAULT_IDLE_TIMEOUT: u64 = 15 * 60;
+
+/// A keypair sent over the agent socket, the private key is zeroized on drop
+#[derive(Serialize, Deserialize)]
+struct PlainKeyPair {
+    name: String,
+    private_key: String,
+    is_default: bool,
+    tags: Vec<String>,
+    note: Option<String>,
+}
+
+/// A contact sent over the agent socket
+#[derive(Serialize, Deserialize)]
+struct PlainContact {
+    alias: String,
+    address: String,
+    note: Option<String>,
+}
+
+/// A wallet sent over the agent socket
+#[derive(Serialize, Deserialize)]
+pub struct PlainWallet {
+    keypairs: Vec<PlainKeyPair>,
+    contacts: Vec<PlainContact>,
+    sealed: bool,
+}
+
+/// A request to the agent
+#[derive(Serialize, Deserialize)]
+enum Request {
+    /// Get the decrypted wallet
+    Load,
+    /// Replace the wallet and export it to the wallet file
+    Store(PlainWallet),
+    /// Get the agent status
+    Status,
+    /// Lock the wallet and stop the agent
+    Stop,
+}
+
+/// A response from the agent
+#[derive(Serialize, Deserialize)]
+enum Response {
+    /// The decrypted wallet
+    Wallet(PlainWallet),
+    /// The agent status
+    Status(AgentStatus),
+    /// The request is done
+    Ok,
+    /// The request failed
+    Error(String),
+}
+
+/// The status of the running agent
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AgentStatus {
+    /// The process id of the agent
+    pub pid: u32,
+    /// The wallet file that the agent holds
+    pub wallet_file: String,
+    /// The number of the keypairs in the wallet
+    pub keypairs: usize,
+    /// The idle timeout of the agent, in seconds
+    pub idle_timeout: u64,
+    /// The seconds remaining before the agent locks the wallet
+    pub locks_in: u64,
+}
+
+/// The client of the agent
+#[derive(Debug)]
+pub struct AgentClient {
+    /// The agent socket path
+    socket: PathBuf,
+}
+
+impl Drop for PlainKeyPair {
+    fn drop(&mut self) {
+        self.private_key.zeroize();
+    }
+}
+
+impl PlainWallet {
+    /// Create a plain wallet from the given wallet
+    fn from_wallet(wallet: &Wallet) -> Self {
+        Self {
+            keypairs: wallet
+                .keypairs
+                .iter()
+                .map(|keypair| PlainKeyPair {
+                    name: keypair.name.clone(),
+                    private_key: keypair.private_key.to_string(),
+                    is_default: keypair.is_default,
+                    tags: keypair.tags.clone(),
+                    note: keypair.note.clone(),
+                })
+                .collect(),
+            contacts: wallet
+                .contacts
+                .iter()
+                .map(|contact| PlainContact {
+                    alias: contact.alias.clone(),
+                    address: contact.address.to_string(),
+                    note: contact.note.clone(),
+                })
+                .collect(),
+            sealed: wallet.sealed,
+        }
+    }
+
+    /// Create the wallet back from the plain wallet
+    fn to_wallet(&self, args: &AppArgs) -> SolwalrsResult<Wallet> {
+        let keypairs = self
+            .keypairs
+            .iter()
+            .map(|keypair| {
+                let bytes = Zeroizing::new(keypair.private_key.from_base58().map_err(|_| {
+                    SolwalrsError::Keypair("Failed to decode the private key".to_owned())
+                })?);
+                let mut kp = KeyPair::import(
+                    &keypair.name,
+                    ImportType::from_bytes(bytes)?,
+                    keypair.is_default,
+                    args,
+                )?;
+                kp.tags = keypair.tags.clone();
+                kp.note = keypair.note.clone();
+                Ok(kp)
+            })
+            .collect::<SolwalrsResult<Vec<_>>>()?;
+        let contacts = self
+            .contacts
+            .iter()
+            .map(|contact| {
+                Contact::from_parts(&contact.alias, &contact.address, contact.note.clone())
+            })
+            .collect::<SolwalrsResult<Vec<_>>>()?;
+        Ok(Wallet {
+            keypairs,
+            contacts,
+            revision: 0,
+            sealed: self.sealed,
+        })
+    }
+}
+
+/// Returns the agent socket path of the selected wallet file, every wallet file has its own agent
+pub fn agent_socket_path(args: &AppArgs) -> SolwalrsResult<PathBuf> {
+    let app_file = utils::canonical_app_file_path(args)?;
+    let hash = Sha256::digest(app_file.display().to_string().as_bytes());
+    let project_dirs = utils::project_dirs()?;
+    let agent_dir = match project_dirs.runtime_dir() {
+        Some(runtime_dir) => runtime_dir.to_path_buf(),
+        None => utils::app_cache_dir()?,
+    }
+    .join("agent");
+    create_private_dir(&agent_dir)?;
+    Ok(agent_dir.join(format!(
+        "{}.sock",
+        hash[..8]
+            .iter()
+            .map(|byte| format!("{byte:02x}"))
+            .collect::<String>()
+    )))
+}
+
+/// Create the directory if it doesn't exist, only the owner can access it
+fn create_private_dir(dir: &Path) -> SolwalrsResult<()> {
+    std::fs::create_dir_all(dir).map_err(|err| {
+        SolwalrsError::AppDataDir(format!("Failed to create the agent directory: {err}"))
+    })?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(dir, std::fs::Permissions::from_mode(0o700)).map_err(|err| {
+            SolwalrsError::AppDataDir(format!(
+                "Failed to set the permissions of the agent directory: {err}"
+            ))
+        })?;
+    }
+    Ok(())
+}
+
+impl AgentClient {
+    /// Connect to the agent of the selected wallet file, returns `None` if the agent isn't running
+    pub fn connect(args: &AppArgs) -> SolwalrsResult<Option<Self>> {
+        let socket = agent_socket_path(args)?;
+        if !socket.exists() {
+            crate::info!(args, "The agent isn't running, there is no socket file");
+            return Ok(None);
+        }
+        #[cfg(unix)]
+        if std::os::unix::net::UnixStream::connect(&socket).is_ok() {
+            crate::info!(args, "Connected to the agent `{}`", socket.display());
+            return Ok(Some(Self { socket }));
+        }
+        crate::warn!(
+            args,
+            "Failed to connect to the agent `{}`, ignoring it",
+            socket.display()
+        );
+        Ok(None)
+    }
+
+    /// Send a request to the agent and returns its response
+    fn request(&self, request: &Request) -> SolwalrsResult<Response> {
+        #[cfg(unix)]
+        {
+            let mut stream =
+                std::os::unix::net::UnixStream::connect(&self.socket).map_err(|err| {
+                    SolwalrsError::IO(format!("Failed to connect to the agent: {err}"))
+                })?;
+            let mut line = serde_json::to_string(request).map_err(|err| {
+                SolwalrsError::Other(format!("Failed to serialize the agent request: {err}"))
+            })?;
+            line.push('\n');
+            let written = stream.write_all(line.as_bytes());
+            line.zeroize();
+            written.map_err(|err| {
+                SolwalrsError::IO(format!("Failed to send the request to the agent: {err}"))
+            })?;
+            let mut line = Zeroizing::new(String::new());
+            BufReader::new(stream).read_line(&mut line).map_err(|err| {
+                SolwalrsError::IO(format!("Failed to read the agent response: {err}"))
+            })?;
+            let response = serde_json::from_str(&line).map_err(|err| {
+                SolwalrsError::Other(format!("Invalid response from the agent: {err}"))
+            })?;
+            match response {
+                Response::Error(err) => Err(SolwalrsError::Other(format!("Agent error: {err}"))),
+                response => Ok(response),
+            }
+        }
+        #[cfg(not(unix))]
+        {
+            let _ = request;
+            Err(SolwalrsError::Other(
+                "The agent is only supported on Unix".to_owned(),
+            ))
+        }
+    }
+
+    /// Get the decrypted wallet from the agent
+    pub fn load_wallet(&self, args: &AppArgs) -> SolwalrsResult<Wallet> {
+        crate::info!(args, "Loading the wallet from the agent");
+        match self.request(&Request::Load)? {
+            Response::Wallet(wallet) => wallet.to_wallet(args),
+            _ => Err(SolwalrsError::Other(
+                "Unexpected response from the agent".to_owned(),
+            )),
+        }
+    }
+
+    /// Send the wallet to the agent, the agent will export it to the wallet file
+    pub fn store_wallet(&self, wallet: &Wallet, args: &AppArgs) -> SolwalrsResult<()> {
+        crate::info!(args, "Storing the wallet in the agent");
+        self.request(&Request::Store(PlainWallet::from_wallet(wallet)))
+            .map(|_| ())
+    }
+
+    /// Get the status of the agent
+    pub fn status(&self) -> SolwalrsResult<AgentStatus> {
+        match self.request(&Request::Status)? {
+            Response::Status(status) => Ok(status),
+            _ => Err(SolwalrsError::Other(
+                "Unexpected response from the agent".to_owned(),
+            )),
+        }
+    }
+
+    /// Stop the agent, the wallet will be zeroized
+    pub fn stop(&self) -> SolwalrsResult<()> {
+        self.request(&Request::Stop).map(|_| ())
+    }
+}
+
+/// The agent server, holds the decrypted wallet and its password, both are zeroized on drop
+pub struct Agent<'a> {
+    wallet: Wallet,
+    password: Zeroizing<String>,
+    idle_timeout: Duration,
+    last_activity: Instant,
+    args: &'a AppArgs,
+}
+
+impl<'a> Agent<'a> {
+    /// Create a new agent, will load the wallet with the given password
+    pub fn new(
+        password: Zeroizing<String>,
+        idle_timeout: Duration,
+        args: &'a AppArgs,
+    ) -> SolwalrsResult<Self> {
+        let wallet = Wallet::load(&password, args)?;
+        Ok(Self {
+            wallet,
+            password,
+            idle_timeout,
+            last_activity: Instant::now(),
+            args,
+        })
+    }
+
+    /// Handle a request, returns the response and whether the agent should stop
+    fn handle(&mut self, request: Request) -> (Response, bool) {
+        let args = self.args;
+        match request {
+            Request::Load => (
+                Response::Wallet(PlainWallet::from_wallet(&self.wallet)),
+                false,
+            ),
+            Request::Store(plain) => {
+                let stored = plain.to_wallet(args).and_then(|mut wallet| {
+                    wallet.export(&self.password, args)?;
+                    Ok(wallet)
+                });
+                match stored {
+                    Ok(wallet) => {
+                        self.wallet = wallet;
+                        (Response::Ok, false)
+                    }
+                    Err(err) => (Response::Error(err.to_string()), false),
+                }
+            }
+            Request::Status => (
+                Response::Status(AgentStatus {
+                    pid: std::process::id(),
+                    wallet_file: utils::app_file_path(args)
+                        .map(|path| path.display().to_string())
+                        .unwrap_or_default(),
+                    keypairs: self.wallet.keypairs.len(),
+                    idle_timeout: self.idle_timeout.as_secs(),
+                    locks_in: self
+                        .idle_timeout
+                        .saturating_sub(self.last_activity.elapsed())
+                        .as_secs(),
+                }),
+                false,
+            ),
+            Request::Stop => (Response::Ok, true),
+        }
+    }
+
+    /// Serve the requests on the agent socket until the idle timeout is reached or the agent is stopped.
+    /// The `ready` callback is called after the socket is created
+    #[cfg(unix)]
+    pub fn serve(mut self, ready: impl FnOnce(&Path)) -> SolwalrsResult<()> {
+        use std::os::unix::{fs::PermissionsExt, net::UnixListener};
+
+        let args = self.args;
+        let socket = agent_socket_path(args)?;
+        if socket.exists() {
+            if AgentClient::connect(args)?.is_some() {
+                return Err(SolwalrsError::Other(
+                    "The agent is already running for this wallet".to_owned(),
+                ));
+            }
+            crate::info!(args, "Removing the stale socket `{}`", socket.display());
+            let _ = std::fs::remove_file(&socket);
+        }
+        let listener = UnixListener::bind(&socket).map_err(|err| {
+            SolwalrsError::IO(format!("Failed to create the agent socket: {err}"))
+        })?;
+        std::fs::set_permissions(&socket, std::fs::Permissions::from_mode(0o600)).map_err(
+            |err| {
+                SolwalrsError::IO(format!(
+                    "Failed to set the permissions of the agent socket: {err}"
+                ))
+            },
+        )?;
+        listener.set_nonblocking(true).map_err(|err| {
+            SolwalrsError::IO(format!("Failed to configure the agent socket: {err}"))
+        })?;
+        ready(&socket);
+
+        loop {
+            if self.last_activity.elapsed() >= self.idle_timeout {
+                crate::info!(args, "The agent is idle, locking the wallet");
+                break;
+            }
+            let stream = match listener.accept() {
+                Ok((stream, _)) => stream,
+                Err(err) if err.kind() == std::io::ErrorKind::WouldBlock => {
+                    std::thread::sleep(Duration::from_millis(100));
+                    continue;
+                }
+                Err(err) => {
+                    crate::warn!(args, "Failed to accept a connection: {err}");
+                    continue;
+                }
+            };
+            // A request that isn't sent in 5 seconds is dropped, so a client can't block the agent
+            let _ = stream.set_nonblocking(false);
+            let _ = stream.set_read_timeout(Some(Duration::from_secs(5)));
+            let mut line = Zeroizing::new(String::new());
+            if BufReader::new(&stream).read_line(&mut line).is_err() {
+                continue;
+            }
+            let (response, stop) = match serde_json::from_str::<Request>(&line) {
+                Ok(request) => {
+                    // Checking the status doesn't keep the agent alive
+                    let is_status = matches!(request, Request::Status);
+                    let handled = self.handle(request);
+                    if !is_status {
+                        self.last_activity = Instant::now();
+                    }
+                    handled
+                }
+                Err(err) => (Response::Error(format!("Invalid request: {err}")), false),
+            };
+            if let Ok(mut line) = serde_json::to_string(&response) {
+                line.push('\n');
+                let _ = (&stream).write_all(line.as_bytes());
+                line.zeroize();
+            }
+            if stop {
+                crate::info!(args, "The agent is stopped, locking the wallet");
+                break;
+            }
+        }
+        let _ = std::fs::remove_file(&socket);
+        Ok(())
+    }
+
+    /// The agent is only supported on Unix
+    #[cfg(not(unix))]
+    pub fn serve(self, _ready: impl FnOnce(&Path)) -> SolwalrsResult<()> {
+        Err(SolwalrsError::Other(
+            "The agent is only supported on Unix".to_owned(),
+        ))
+    }
+}
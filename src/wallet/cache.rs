@@ -18,7 +18,7 @@ use std::{fs::File, io::BufReader};
 
 use serde::{Deserialize, Serialize};
 
-use super::{Price, Tokens};
+use super::{Asset, FxRate, Price, FX_RATE_TTL, PRICE_TTL};
 use crate::{
     app::AppArgs,
     errors::{Error as SolwalrsError, Result as SolwalrsResult},
@@ -30,12 +30,18 @@ use crate::{
 pub struct Cache {
     /// Prices of tokens
     pub prices: Vec<Price>,
+    /// Exchange rates of the currencies from USD
+    #[serde(default)]
+    pub fx_rates: Vec<FxRate>,
 }
 
 impl Cache {
     /// Create a new cache instance
     pub fn new() -> Self {
-        Self { prices: Vec::new() }
+        Self {
+            prices: Vec::new(),
+            fx_rates: Vec::new(),
+        }
     }
 
     /// Load the cache from cache file, or create a new one if it doesn't exist
@@ -54,6 +60,7 @@ impl Cache {
             let mut cache: Cache = serde_json::from_reader(reader)
                 .map_err(|e| SolwalrsError::IO(format!("Failed to load cache file: {e}")))?;
             cache.clear_prices();
+            cache.clear_fx_rates();
             crate::info!(args, "Cache file loaded: {:#?}", cache);
             Ok(cache)
         } else {
@@ -70,7 +77,7 @@ impl Cache {
     /// This is to prevent the cache from getting too big
     pub fn clear_prices(&mut self) {
         self.prices
-            .retain(|price| price.timestamp + (5 * 60) > utils::get_timestamp());
+            .retain(|price| price.timestamp + PRICE_TTL > utils::get_timestamp());
     }
 
     /// Add a price to the cache, returns the added price
@@ -81,15 +88,15 @@ impl Cache {
             panic!("Price symbol is None");
         }
         // Remove old prices of the same token
-        self.prices.retain(|p| p != &price);
+        self.prices.retain(|p| p.symbol != price.symbol);
         self.prices.push(price);
         self.prices.last().unwrap()
     }
 
-    /// Get a price from the cache, if it exists, pass `None` to token to get the price of SOL
-    pub fn get_price(&self, token: Option<&Tokens>, args: &AppArgs) -> Option<&Price> {
+    /// Get the price of the asset from the cache, if it exists
+    pub fn get_price(&self, asset: &Asset, args: &AppArgs) -> Option<&Price> {
         crate::info!(args, "Getting price from cache");
-        let symbol = token.map(Tokens::name).unwrap_or("sol").to_uppercase();
+        let symbol = asset.symbol();
         let price = self
             .prices
             .iter()
@@ -98,6 +105,37 @@ impl Cache {
         price
     }
 
+    /// Clear the exchange rates from cache, older than an hour
+    pub fn clear_fx_rates(&mut self) {
+        self.fx_rates
+            .retain(|fx_rate| fx_rate.timestamp + FX_RATE_TTL > utils::get_timestamp());
+    }
+
+    /// Add an exchange rate to the cache, replacing the old rate of the same currency
+    pub fn add_fx_rate(&mut self, fx_rate: FxRate) {
+        self.fx_rates
+            .retain(|rate| rate.currency != fx_rate.currency);
+        self.fx_rates.push(fx_rate);
+    }
+
+    /// Get the exchange rate of the currency from the cache, if it exists and isn't older than an hour
+    pub fn get_fx_rate(&self, currency: &str, args: &AppArgs) -> Option<&FxRate> {
+        crate::info!(args, "Getting the {currency} exchange rate from cache");
+        let fx_rate = self.fx_rates.iter().find(|fx_rate| {
+            fx_rate.currency == currency && fx_rate.timestamp + FX_RATE_TTL > utils::get_timestamp()
+        });
+        crate::info_or_warn!(args, fx_rate, "Exchange rate found in cache: {fx_rate:?}"; "Exchange rate not found in cache");
+        fx_rate
+    }
+
+    /// Get the last known exchange rate of the currency from the cache, even if it's older than an hour.
+    /// The stale rates are removed when the cache is loaded, so it's only kept during a session like `price --watch`
+    pub fn get_last_fx_rate(&self, currency: &str) -> Option<&FxRate> {
+        self.fx_rates
+            .iter()
+            .find(|fx_rate| fx_rate.currency == currency)
+    }
+
     /// Save the cache to cache file
     pub fn save(&mut self, args: &AppArgs) -> SolwalrsResult<()> {
         crate::info!(args, "Saving cache file");
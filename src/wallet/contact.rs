@@ -0,0 +1,133 @@
+// Solwalrs, A simple and easy to use CLI Solana wallet
+// Copyright (C) 2022  Solwalrs contributors <https://github.com/TheAwiteb/solwalrs/graphs/contributors>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/gpl-3.0.html>.
+
+use serde::{Deserialize, Serialize};
+use solana_sdk::pubkey::Pubkey;
+
+use super::utils;
+use crate::{
+    app::AppArgs,
+    errors::{Error as SolwalrsError, Result as SolwalrsResult},
+};
+
+/// A named external address, in the address book (decrypted)
+#[derive(Debug, Clone)]
+pub struct Contact {
+    /// The alias of the contact, without the `@` prefix
+    pub alias: String,
+    /// The address of the contact
+    pub address: Pubkey,
+    /// A note about the contact
+    pub note: Option<String>,
+}
+
+/// A contact with encrypted data
+#[derive(Debug, Serialize, Deserialize)]
+pub struct EncryptedContact {
+    /// The encrypted alias of the contact
+    alias: String,
+    /// The encrypted address of the contact
+    address: String,
+    /// The encrypted note of the contact
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    note: Option<String>,
+}
+
+impl Contact {
+    /// Create a new contact, the `@` prefix of the alias will be removed.
+    /// Will return `Error::InvalidContactAlias` if the alias is invalid
+    pub fn new(alias: &str, address: &str, note: Option<String>) -> SolwalrsResult<Self> {
+        Self::from_parts(Self::validate_alias(alias)?, address, note)
+    }
+
+    /// Create a contact without validating the alias, for the contacts that are already in the wallet
+    pub(crate) fn from_parts(
+        alias: &str,
+        address: &str,
+        note: Option<String>,
+    ) -> SolwalrsResult<Self> {
+        Ok(Self {
+            alias: alias.trim_start_matches('@').to_owned(),
+            address: address
+                .parse()
+                .map_err(|_| SolwalrsError::InvalidAddress(address.to_owned()))?,
+            note,
+        })
+    }
+
+    /// Returns the alias without the `@` prefix, will return `Error::InvalidContactAlias` if it's invalid.
+    /// Like the profile names, only letters, digits, `-` and `_` are allowed, so it can be typed as `@alias`
+    pub fn validate_alias(alias: &str) -> SolwalrsResult<&str> {
+        let alias = alias.strip_prefix('@').unwrap_or(alias);
+        if alias.is_empty()
+            || !alias
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+        {
+            return Err(SolwalrsError::InvalidContactAlias(alias.to_owned()));
+        }
+        Ok(alias)
+    }
+
+    /// Encrypt the contact with the given password, will return the encrypted contact.
+    #[must_use = "encrypting the contact will return the encrypted contact"]
+    pub fn encrypt(self, password: &[u8], args: &AppArgs) -> SolwalrsResult<EncryptedContact> {
+        crate::info!(args, "Trying to encrypt the contact `{}`", self.alias);
+        Ok(EncryptedContact {
+            alias: utils::encrypt(password, self.alias.as_bytes())?,
+            address: utils::encrypt(password, self.address.to_string().as_bytes())?,
+            note: self
+                .note
+                .map(|note| utils::encrypt(password, note.as_bytes()))
+                .transpose()?,
+        })
+    }
+}
+
+impl EncryptedContact {
+    /// Decrypt the contact with the given password, will return the decrypted contact.
+    /// Will return `Error::InvalidPassword` if the password is not correct.
+    #[must_use = "decrypting the contact will return the decrypted contact"]
+    pub fn decrypt(self, password: &[u8], args: &AppArgs) -> SolwalrsResult<Contact> {
+        crate::info!(args, "Trying to decrypt a contact");
+        let alias = utils::decrypt_string(password, &self.alias)?;
+        let address = utils::decrypt_string(password, &self.address)?;
+        let note = self
+            .note
+            .map(|note| utils::decrypt_string(password, &note).map(|note| note.to_string()))
+            .transpose()?;
+        crate::info!(args, "Contact `{}` decrypted successfully", *alias);
+        Contact::from_parts(&alias, &address, note)
+    }
+}
+
+/// An address resolved from a `@alias`, a keypair name or a base58 address
+#[derive(Debug, Clone)]
+pub struct ResolvedAddress {
+    /// The resolved address
+    pub address: Pubkey,
+    /// What the address resolved from, e.g. `contact @alias`, `None` if the input is an address
+    pub source: Option<String>,
+}
+
+impl std::fmt::Display for ResolvedAddress {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.source {
+            Some(source) => write!(f, "{} ({source})", self.address),
+            None => write!(f, "{}", self.address),
+        }
+    }
+}
@@ -0,0 +1,233 @@
+// Solwalrs, A simple and easy to use CLI Solana wallet
+// Copyright (C) 2022  Solwalrs contributors <https://github.com/TheAwiteb/solwalrs/graphs/contributors>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/gpl-3.0.html>.
+
+use std::{fmt, str::FromStr};
+
+use serde::{Deserialize, Serialize};
+
+use super::cache::Cache;
+use crate::{
+    app::AppArgs,
+    errors::{Error as SolwalrsError, Result as SolwalrsResult},
+    utils,
+};
+
+const FX_API: &str = "https://open.er-api.com/v6/latest/USD";
+/// The time to keep the exchange rates in the cache, in seconds
+pub const FX_RATE_TTL: u64 = 60 * 60;
+
+/// A fiat currency, the prices are converted from USD to it
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct Currency(String);
+
+/// The exchange rate of a currency from USD
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct FxRate {
+    /// The ISO 4217 code of the currency
+    pub currency: String,
+    /// The amount of the currency in one USD
+    pub rate: f64,
+    /// The timestamp of the request (in seconds since the Unix epoch)
+    pub timestamp: u64,
+}
+
+impl Default for Currency {
+    fn default() -> Self {
+        Self("USD".to_owned())
+    }
+}
+
+impl FromStr for Currency {
+    type Err = SolwalrsError;
+
+    fn from_str(code: &str) -> SolwalrsResult<Self> {
+        if code.len() != 3 || !code.chars().all(|c| c.is_ascii_alphabetic()) {
+            return Err(SolwalrsError::Other(format!(
+                "Invalid currency `{code}`, expected an ISO 4217 code, e.g. `EUR`"
+            )));
+        }
+        Ok(Self(code.to_ascii_uppercase()))
+    }
+}
+
+impl TryFrom<String> for Currency {
+    type Error = SolwalrsError;
+
+    fn try_from(code: String) -> SolwalrsResult<Self> {
+        code.parse()
+    }
+}
+
+impl From<Currency> for String {
+    fn from(currency: Currency) -> Self {
+        currency.0
+    }
+}
+
+impl fmt::Display for Currency {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl Currency {
+    /// Returns the selected currency, `--currency` or the currency of the selected profile, default is USD
+    pub fn selected(args: &AppArgs) -> Self {
+        args.currency.clone().unwrap_or_default()
+    }
+
+    /// Returns the ISO 4217 code of the currency
+    pub fn code(&self) -> &str {
+        &self.0
+    }
+
+    /// Returns the symbol written before the amount, `None` if the code is written after it
+    pub fn symbol(&self) -> Option<&'static str> {
+        Some(match self.code() {
+            "USD" => "$",
+            "EUR" => "€",
+            "GBP" => "£",
+            "JPY" | "CNY" => "¥",
+            "INR" => "₹",
+            "KRW" => "₩",
+            "BRL" => "R$",
+            "CAD" => "CA$",
+            "AUD" => "A$",
+            "NGN" => "₦",
+            "UAH" => "₴",
+            "ILS" => "₪",
+            "PHP" => "₱",
+            "TRY" => "₺",
+            _ => return None,
+        })
+    }
+
+    /// Returns the number of the decimal places of the currency
+    pub fn decimals(&self) -> usize {
+        match self.code() {
+            "JPY" | "KRW" | "VND" | "CLP" | "ISK" | "UGX" | "PYG" => 0,
+            "BHD" | "KWD" | "OMR" | "JOD" | "TND" | "LYD" | "IQD" => 3,
+            _ => 2,
+        }
+    }
+
+    /// Format the value with the currency symbol and decimal places, e.g. `€12.34` or `12.34 SAR`
+    pub fn format(&self, value: f64) -> String {
+        self.format_with_decimals(value, self.decimals())
+    }
+
+    /// Format the price, like `format` but small prices keep 4 significant digits, e.g. `$0.001234`
+    pub fn format_price(&self, value: f64) -> String {
+        let significant = if value.abs() > 0.0 && value.abs() < 1.0 {
+            (3 - value.abs().log10().floor() as i32).max(0) as usize
+        } else {
+            0
+        };
+        self.format_with_decimals(value, self.decimals().max(significant))
+    }
+
+    /// Format the value with the given decimal places
+    fn format_with_decimals(&self, value: f64, decimals: usize) -> String {
+        let sign = if value < 0.0 { "-" } else { "" };
+        let amount = format!("{:.decimals$}", value.abs());
+        match self.symbol() {
+            Some(symbol) => format!("{sign}{symbol}{amount}"),
+            None => format!("{sign}{amount} {}", self.code()),
+        }
+    }
+
+    /// Returns the exchange rate of the currency from USD, from the cache if it's less than an hour old.
+    /// The last known rate is used if the request fails
+    pub fn rate(&self, args: &AppArgs, cache: &mut Cache) -> SolwalrsResult<f64> {
+        if self.code() == "USD" {
+            return Ok(1.0);
+        }
+        if let Some(fx_rate) = cache.get_fx_rate(self.code(), args) {
+            return Ok(fx_rate.rate);
+        }
+        match self.send_request(args) {
+            Ok(rate) => {
+                cache.add_fx_rate(FxRate {
+                    currency: self.code().to_owned(),
+                    rate,
+                    timestamp: utils::get_timestamp(),
+                });
+                Ok(rate)
+            }
+            Err(err) => match cache.get_last_fx_rate(self.code()) {
+                Some(fx_rate) => {
+                    crate::warn!(
+                        args,
+                        "Using the stale {self} exchange rate, the request failed: {err}"
+                    );
+                    Ok(fx_rate.rate)
+                }
+                None => Err(err),
+            },
+        }
+    }
+
+    /// Request the exchange rate of the currency from USD
+    fn send_request(&self, args: &AppArgs) -> SolwalrsResult<f64> {
+        crate::info!(args, "Sending request to get the {self} exchange rate");
+        let response: serde_json::Value = reqwest::blocking::get(FX_API)
+            .and_then(|response| response.error_for_status())
+            .map_err(|err| SolwalrsError::RequestError(err.to_string()))?
+            .json()
+            .map_err(|err| {
+                SolwalrsError::Other(format!("Failed to parse the exchange rates: {err}"))
+            })?;
+        crate::info!(args, "Got the exchange rates {response}");
+        response["rates"][self.code()].as_f64().ok_or_else(|| {
+            SolwalrsError::Other(format!(
+                "There is no exchange rate of the currency `{self}`"
+            ))
+        })
+    }
+
+    /// Convert the USD value to the currency
+    pub fn convert(&self, usd: f64, args: &AppArgs, cache: &mut Cache) -> SolwalrsResult<f64> {
+        Ok(usd * self.rate(args, cache)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_currency() {
+        assert_eq!("eur".parse::<Currency>().unwrap().code(), "EUR");
+        assert!("EURO".parse::<Currency>().is_err());
+        assert!("E1R".parse::<Currency>().is_err());
+        assert_eq!(Currency::default().code(), "USD");
+    }
+
+    #[test]
+    fn format_currency() {
+        let currency = |code: &str| code.parse::<Currency>().unwrap();
+        assert_eq!(currency("usd").format(1234.567), "$1234.57");
+        assert_eq!(currency("EUR").format(-2.5), "-€2.50");
+        assert_eq!(currency("SAR").format(12.345), "12.35 SAR");
+        assert_eq!(currency("JPY").format(150.6), "¥151");
+        assert_eq!(currency("KWD").format(1.23456), "1.235 KWD");
+        assert_eq!(currency("USD").format_price(0.001234567), "$0.001235");
+        assert_eq!(currency("USD").format_price(0.5), "$0.5000");
+        assert_eq!(currency("USD").format_price(23.4567), "$23.46");
+        assert_eq!(currency("JPY").format_price(0.0), "¥0");
+    }
+}
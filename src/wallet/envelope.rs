@@ -0,0 +1,149 @@
+// Solwalrs, A simple and easy to use CLI Solana wallet
+// Copyright (C) 2022  Solwalrs contributors <https://github.com/TheAwiteb/solwalrs/graphs/contributors>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/gpl-3.0.html>.
+
+//! The authenticated envelope of the wallet file, a MAC over the whole wallet and a revision counter.
+//!
+//! The MAC key is derived from the password, so a modified, reordered or truncated wallet file
+//! is detected when it's loaded. The last seen revision of every wallet file is kept in the app
+//! data directory, so an older copy of the wallet file (rollback) is detected too.
+
+use std::{collections::HashMap, fs::File, io::BufReader, path::PathBuf};
+
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use zeroize::Zeroizing;
+
+use super::utils::{app_data_dir, canonical_app_file_path, write_atomically};
+use crate::{
+    app::AppArgs,
+    errors::{Error as SolwalrsError, Result as SolwalrsResult},
+};
+
+/// The current version of the wallet file, the files without a version are version 0 (no envelope).
+/// Version 2 adds the sealed format
+pub const WALLET_VERSION: u32 = 2;
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// The last seen revision of every wallet file, the key is the absolute path of the wallet file
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Revisions(HashMap<String, u64>);
+
+/// Derive the MAC key from the password
+fn mac_key(password: &[u8]) -> Zeroizing<Vec<u8>> {
+    // SAFETY: HMAC accepts keys of any length
+    let mut mac = HmacSha256::new_from_slice(password).unwrap();
+    mac.update(b"solwalrs wallet mac key");
+    Zeroizing::new(mac.finalize().into_bytes().to_vec())
+}
+
+/// Returns the HMAC-SHA256 of the given parts with the MAC key of the password
+fn hmac(password: &[u8], parts: &[&[u8]]) -> HmacSha256 {
+    // SAFETY: HMAC accepts keys of any length
+    let mut mac = HmacSha256::new_from_slice(&mac_key(password)).unwrap();
+    for part in parts {
+        mac.update(part);
+    }
+    mac
+}
+
+/// Returns the key check of the password, it's used to tell a wrong password apart from a modified wallet
+pub fn key_check(password: &[u8]) -> String {
+    base64::encode(hmac(password, &[b"key check"]).finalize().into_bytes())
+}
+
+/// Whether the given key check belongs to the password, in constant time
+pub fn verify_key_check(password: &[u8], check: &str) -> bool {
+    base64::decode(check)
+        .is_ok_and(|check| hmac(password, &[b"key check"]).verify_slice(&check).is_ok())
+}
+
+/// Returns the MAC of the given authenticated data
+pub fn wallet_mac(password: &[u8], data: &[u8]) -> String {
+    base64::encode(hmac(password, &[b"wallet", data]).finalize().into_bytes())
+}
+
+/// Whether the given MAC is the MAC of the authenticated data, in constant time
+pub fn verify_wallet_mac(password: &[u8], data: &[u8], mac: &str) -> bool {
+    base64::decode(mac).is_ok_and(|mac| {
+        hmac(password, &[b"wallet", data])
+            .verify_slice(&mac)
+            .is_ok()
+    })
+}
+
+/// Returns the revisions file path
+fn revisions_file_path() -> SolwalrsResult<PathBuf> {
+    app_data_dir().map(|data| data.join("revisions.json"))
+}
+
+impl Revisions {
+    /// Load the last seen revisions, or empty revisions if the file doesn't exist
+    pub fn load(args: &AppArgs) -> SolwalrsResult<Self> {
+        let revisions_file = revisions_file_path()?;
+        if !revisions_file.exists() {
+            crate::info!(args, "There is no revisions file");
+            return Ok(Self::default());
+        }
+        let file = File::open(&revisions_file)
+            .map_err(|err| SolwalrsError::IO(format!("Failed to open revisions file: {err}")))?;
+        serde_json::from_reader(BufReader::new(file))
+            .map_err(|err| SolwalrsError::IO(format!("Failed to load revisions file: {err}")))
+    }
+
+    /// Save the last seen revisions
+    pub fn save(&self, args: &AppArgs) -> SolwalrsResult<()> {
+        crate::info!(args, "Saving the revisions {self:?}");
+        let contents = serde_json::to_vec_pretty(self)
+            .map_err(|err| SolwalrsError::IO(format!("Failed to save revisions file: {err}")))?;
+        write_atomically(&revisions_file_path()?, &contents)
+    }
+
+    /// Returns the last seen revision of the selected wallet file
+    pub fn last_seen(args: &AppArgs) -> SolwalrsResult<Option<u64>> {
+        let app_file = canonical_app_file_path(args)?;
+        Ok(Self::load(args)?
+            .0
+            .get(&app_file.display().to_string())
+            .copied())
+    }
+
+    /// Record the revision of the selected wallet file, if it's newer than the last seen revision
+    pub fn record(revision: u64, args: &AppArgs) -> SolwalrsResult<()> {
+        let app_file = canonical_app_file_path(args)?.display().to_string();
+        let mut revisions = Self::load(args)?;
+        if revisions
+            .0
+            .get(&app_file)
+            .is_none_or(|last_seen| *last_seen < revision)
+        {
+            revisions.0.insert(app_file, revision);
+            revisions.save(args)?;
+        }
+        Ok(())
+    }
+
+    /// Forget the revision of the selected wallet file, used when the wallet file is removed
+    pub fn forget(args: &AppArgs) -> SolwalrsResult<()> {
+        let app_file = canonical_app_file_path(args)?.display().to_string();
+        let mut revisions = Self::load(args)?;
+        if revisions.0.remove(&app_file).is_some() {
+            revisions.save(args)?;
+        }
+        Ok(())
+    }
+}
@@ -18,6 +18,9 @@ use base58::{FromBase58, ToBase58};
 use ed25519_dalek::{PublicKey, SecretKey};
 use rand::rngs::OsRng;
 use serde::{Deserialize, Serialize};
+use solana_sdk::{pubkey::Pubkey, signature::Keypair};
+use wildmatch::WildMatch;
+use zeroize::Zeroizing;
 
 use super::{short_public_key, utils, Tokens};
 use crate::{
@@ -25,7 +28,7 @@ use crate::{
     errors::{Error as SolwalrsError, Result as SolwalrsResult},
 };
 
-/// A keypair with clean data (decrypted)
+/// A keypair with clean data (decrypted), the secret key and the private key are zeroized on drop
 pub struct KeyPair {
     /// The name of the keypair
     pub name: String,
@@ -34,9 +37,24 @@ pub struct KeyPair {
     /// The secret key of the keypair
     pub secret_key: SecretKey,
     /// The private key of the keypair (public and secret keys), base58 encoded
-    pub private_key: String,
+    pub private_key: Zeroizing<String>,
     /// Is this keypair the default keypair
     pub is_default: bool,
+    /// The tags of the keypair
+    pub tags: Vec<String>,
+    /// A note about the keypair
+    pub note: Option<String>,
+}
+
+/// The metadata of the keypair, it's encrypted as one JSON object
+#[derive(Default, Serialize, Deserialize)]
+struct KeyPairMetadata {
+    /// The tags of the keypair
+    #[serde(default)]
+    tags: Vec<String>,
+    /// A note about the keypair
+    #[serde(default)]
+    note: Option<String>,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -47,8 +65,10 @@ pub struct EncryptedKeyPair {
     /// The encrypted private key of the keypair (public and secret keys) , base54 encoded
     private_key: String,
     /// Is this keypair the default keypair, (unencrypted)
-    #[serde(default)] // Default value is false
     is_default: bool,
+    /// The encrypted metadata of the keypair (tags and note), `None` if the keypair doesn't have metadata
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    metadata: Option<String>,
 }
 
 impl Clone for KeyPair {
@@ -61,9 +81,12 @@ impl Clone for KeyPair {
             // Copy is implemented for PublicKey
             public_key: self.public_key,
             // SAFETY: We know that the secret key is valid, so we can create a new one from the bytes
-            secret_key: SecretKey::from_bytes(&self.secret_key.to_bytes()).unwrap(),
+            secret_key: SecretKey::from_bytes(&*Zeroizing::new(self.secret_key.to_bytes()))
+                .unwrap(),
             private_key: self.private_key.clone(),
             is_default: self.is_default,
+            tags: self.tags.clone(),
+            note: self.note.clone(),
         }
     }
 }
@@ -75,6 +98,8 @@ impl std::fmt::Debug for KeyPair {
             .field("public_key", &short_public_key(&self.public_key))
             .field("private_key", &"***")
             .field("is_default", &self.is_default)
+            .field("tags", &self.tags)
+            .field("note", &self.note)
             .finish()
     }
 }
@@ -89,17 +114,18 @@ impl std::fmt::Debug for EncryptedKeyPair {
     }
 }
 
-/// The type of the keypair to import, private key or secret key.
+/// The type of the keypair to import, private key or secret key. The bytes are zeroized on drop
 pub enum ImportType {
     /// The private key, which is the public and secret key combined. (64 bytes)
-    PrivateKey { bytes: Vec<u8> },
+    PrivateKey { bytes: Zeroizing<Vec<u8>> },
     /// The secret key, which is the private key without the public key. (32 bytes)
-    SecretKey { bytes: Vec<u8> },
+    SecretKey { bytes: Zeroizing<Vec<u8>> },
 }
 
 impl ImportType {
     /// From bytes array
-    pub fn from_bytes(bytes: Vec<u8>) -> SolwalrsResult<Self> {
+    pub fn from_bytes(bytes: impl Into<Zeroizing<Vec<u8>>>) -> SolwalrsResult<Self> {
+        let bytes = bytes.into();
         let length = bytes.len();
         // private key is 64 bytes, secret key is 32 bytes.
         if length == 64 {
@@ -112,6 +138,7 @@ impl ImportType {
     }
     /// Parse the import type from the string.
     pub fn parse(input: String) -> SolwalrsResult<Self> {
+        let input = Zeroizing::new(input);
         if input.starts_with('[') && input.ends_with(']') {
             // parse a string vector to vec<u8>
             let bytes = input
@@ -120,12 +147,14 @@ impl ImportType {
                 .split(',')
                 .map(|s| s.trim().parse::<u8>())
                 .collect::<Result<Vec<_>, _>>()
+                .map(Zeroizing::new)
                 .map_err(|err| SolwalrsError::Other(format!("Failed to parse bytes: {err}")))?;
             Self::from_bytes(bytes)
         } else {
             // base58 encoded, so need to decode it.
             let bytes = input
                 .from_base58()
+                .map(Zeroizing::new)
                 .map_err(|err| SolwalrsError::Other(format!("Failed to decode base58: {err:?}")))?;
             Self::from_bytes(bytes)
         }
@@ -137,13 +166,15 @@ impl KeyPair {
     pub fn new(name: impl Into<String>, default: bool) -> Self {
         let mut rng = OsRng::default();
         let keypair = ed25519_dalek::Keypair::generate(&mut rng);
-        let private_key = keypair.to_bytes().to_base58();
+        let private_key = Zeroizing::new(Zeroizing::new(keypair.to_bytes()).to_base58());
         Self {
             name: name.into(),
             public_key: keypair.public,
             secret_key: keypair.secret,
             private_key,
             is_default: default,
+            tags: Vec::new(),
+            note: None,
         }
     }
 
@@ -151,7 +182,7 @@ impl KeyPair {
     /// Note: the private key must be 64 bytes long, will return `Error::InvalidPrivateKey` if the private key is not 64 bytes long.
     pub fn from_private_key(
         name: impl Into<String>,
-        private_key: Vec<u8>,
+        private_key: Zeroizing<Vec<u8>>,
         is_default: bool,
         args: &AppArgs,
     ) -> SolwalrsResult<Self> {
@@ -169,15 +200,17 @@ impl KeyPair {
             name,
             public_key: keypair.public,
             secret_key: keypair.secret,
-            private_key: private_key.to_base58(),
+            private_key: Zeroizing::new(private_key.to_base58()),
             is_default,
+            tags: Vec::new(),
+            note: None,
         })
     }
 
     /// Import a keypair from a secret key, secret key is 32 bytes long.
     pub fn from_secret_key(
         name: impl Into<String>,
-        bytes: Vec<u8>,
+        bytes: Zeroizing<Vec<u8>>,
         is_default: bool,
         args: &AppArgs,
     ) -> SolwalrsResult<Self> {
@@ -195,8 +228,10 @@ impl KeyPair {
             name,
             public_key,
             secret_key,
-            private_key: bytes.to_base58(),
+            private_key: Zeroizing::new(bytes.to_base58()),
             is_default,
+            tags: Vec::new(),
+            note: None,
         })
     }
 
@@ -230,17 +265,81 @@ impl KeyPair {
 
         let name = utils::encrypt(password, self.name.as_bytes().to_base58().as_bytes())?;
         let private_key = utils::encrypt(password, self.private_key.as_bytes())?;
+        let metadata = if self.tags.is_empty() && self.note.is_none() {
+            None
+        } else {
+            let metadata = serde_json::to_vec(&KeyPairMetadata {
+                tags: self.tags,
+                note: self.note,
+            })
+            .map_err(|err| {
+                SolwalrsError::Keypair(format!("Failed to serialize the keypair metadata: {err}"))
+            })?;
+            Some(utils::encrypt(password, &metadata)?)
+        };
         crate::info!(args, "Keypair `{}` encrypted successfully", self.name);
         Ok(EncryptedKeyPair {
             name,
             private_key,
             is_default: self.is_default,
+            metadata,
         })
     }
 
-    pub fn qr_code(&self) -> qrcode::QrCode {
-        // SAFETY: the public key is always 32 bytes long, so it will never panic.
-        qrcode::QrCode::new(self.public_key.as_bytes().to_base58()).unwrap()
+    /// Add the tags to the keypair, the tags that it already has are skipped
+    pub fn add_tags(&mut self, tags: &[String]) {
+        for tag in tags {
+            if !self.tags.contains(tag) {
+                self.tags.push(tag.clone());
+            }
+        }
+    }
+
+    /// Remove the tags from the keypair
+    pub fn remove_tags(&mut self, tags: &[String]) {
+        self.tags.retain(|tag| !tags.contains(tag));
+    }
+
+    /// Whether the keypair name, tags or note matches the given glob pattern (case-insensitive)
+    pub fn matches(&self, pattern: &str) -> bool {
+        let pattern = WildMatch::new(&pattern.to_lowercase());
+        pattern.matches(&self.name.to_lowercase())
+            || self
+                .tags
+                .iter()
+                .any(|tag| pattern.matches(&tag.to_lowercase()))
+            || self
+                .note
+                .as_ref()
+                .is_some_and(|note| pattern.matches(&note.to_lowercase()))
+    }
+
+    /// Returns the public key of the keypair as a Solana `Pubkey`
+    pub fn pubkey(&self) -> Pubkey {
+        Pubkey::new_from_array(self.public_key.to_bytes())
+    }
+
+    /// Returns the 64 bytes of the keypair, the secret key followed by the public key
+    pub fn keypair_bytes(&self) -> Zeroizing<Vec<u8>> {
+        let mut bytes = Zeroizing::new(self.secret_key.to_bytes().to_vec());
+        bytes.extend_from_slice(self.public_key.as_bytes());
+        bytes
+    }
+
+    /// Returns the keypair as a Solana `Keypair`, to sign transactions with it
+    pub fn signer(&self) -> SolwalrsResult<Keypair> {
+        Keypair::from_bytes(&self.keypair_bytes())
+            .map_err(|_| SolwalrsError::InvalidPrivateKey(self.name.clone()))
+    }
+
+    /// Returns the QR code of the public key, with the given error correction level
+    pub fn qr_code(&self, ec_level: qrcode::EcLevel) -> qrcode::QrCode {
+        // SAFETY: the public key is always 32 bytes long, it fits in any error correction level, so it will never panic.
+        qrcode::QrCode::with_error_correction_level(
+            self.public_key.as_bytes().to_base58(),
+            ec_level,
+        )
+        .unwrap()
     }
 
     /// Get the balance of the keypair
@@ -279,18 +378,111 @@ impl EncryptedKeyPair {
     pub fn decrypt(self, password: &[u8], args: &AppArgs) -> SolwalrsResult<KeyPair> {
         crate::info!(args, "Trying to decrypt a keypair");
         let name = String::from_utf8(
-            utils::decrypt(password, &self.name)?
+            utils::decrypt_string(password, &self.name)?
                 .from_base58()
                 .map_err(|_| {
                     SolwalrsError::Keypair("Failed to decrypt the keypair name".to_string())
                 })?,
         )
         .map_err(|_| SolwalrsError::Keypair("Failed to decrypt the keypair name".to_string()))?;
-        let private_key = utils::decrypt(password, &self.private_key)?
+        let private_key = utils::decrypt_string(password, &self.private_key)?
             .from_base58()
+            .map(Zeroizing::new)
             .map_err(|_| SolwalrsError::Keypair("Failed to decode the private key".to_owned()))?;
+        let metadata = self
+            .metadata
+            .map(|metadata| {
+                serde_json::from_str::<KeyPairMetadata>(&utils::decrypt_string(
+                    password, &metadata,
+                )?)
+                .map_err(|_| {
+                    SolwalrsError::Keypair("Failed to decrypt the keypair metadata".to_owned())
+                })
+            })
+            .transpose()?
+            .unwrap_or_default();
         crate::info!(args, "Keypair `{}` decrypted successfully", name);
 
-        KeyPair::from_private_key(name, private_key, self.is_default, args)
+        let mut keypair = KeyPair::from_private_key(name, private_key, self.is_default, args)?;
+        keypair.tags = metadata.tags;
+        keypair.note = metadata.note;
+        Ok(keypair)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::Parser;
+    use zeroize::Zeroize;
+
+    use super::*;
+
+    const PASSWORD: &[u8] = b"0123456789abcdef0123456789abcdef";
+
+    fn args() -> AppArgs {
+        AppArgs::parse_from(["solwalrs"])
+    }
+
+    /// Compile time check, the value is wiped when it's dropped
+    fn assert_zeroizing<T: Zeroize>(_: &Zeroizing<T>) {}
+
+    #[test]
+    fn secret_material_is_zeroizing() {
+        let keypair = KeyPair::new("test", false);
+        assert_zeroizing(&keypair.private_key);
+
+        let bytes = Zeroizing::new(keypair.private_key.from_base58().unwrap());
+        match ImportType::from_bytes(bytes).unwrap() {
+            ImportType::PrivateKey { bytes } => assert_zeroizing(&bytes),
+            ImportType::SecretKey { .. } => panic!("The private key is 64 bytes"),
+        }
+        match ImportType::parse(keypair.secret_key.as_bytes().to_base58()).unwrap() {
+            ImportType::SecretKey { bytes } => assert_zeroizing(&bytes),
+            ImportType::PrivateKey { .. } => panic!("The secret key is 32 bytes"),
+        }
+    }
+
+    #[test]
+    fn debug_hides_secrets() {
+        let keypair = KeyPair::new("test", false);
+        let secret_key = keypair.secret_key.as_bytes();
+        for debug in [format!("{keypair:?}"), format!("{:?}", keypair.clone())] {
+            assert!(!debug.contains(keypair.private_key.as_str()));
+            assert!(!debug.contains(&secret_key.to_base58()));
+            assert!(!debug.contains(&format!("{secret_key:?}")));
+        }
+
+        let encrypted = keypair.clone().encrypt(PASSWORD, &args()).unwrap();
+        let debug = format!("{encrypted:?}");
+        assert!(!debug.contains(&encrypted.private_key));
+        assert!(!debug.contains(keypair.private_key.as_str()));
+    }
+
+    #[test]
+    fn clone_keeps_the_secret() {
+        let keypair = KeyPair::new("test", true);
+        let cloned = keypair.clone();
+        assert_eq!(cloned.private_key, keypair.private_key);
+        assert_eq!(cloned.secret_key.as_bytes(), keypair.secret_key.as_bytes());
+        assert_eq!(cloned.public_key, keypair.public_key);
+    }
+
+    #[test]
+    fn encrypt_decrypt_roundtrip() {
+        let mut keypair = KeyPair::new("test", true);
+        keypair.tags = vec!["savings".to_owned()];
+        keypair.note = Some("cold storage".to_owned());
+
+        let decrypted = keypair
+            .clone()
+            .encrypt(PASSWORD, &args())
+            .unwrap()
+            .decrypt(PASSWORD, &args())
+            .unwrap();
+        assert_eq!(decrypted.name, keypair.name);
+        assert_eq!(decrypted.private_key, keypair.private_key);
+        assert_eq!(decrypted.tags, keypair.tags);
+        assert_eq!(decrypted.note, keypair.note);
+        assert!(decrypted.is_default);
     }
 }
@@ -0,0 +1,64 @@
+// Solwalrs, A simple and easy to use CLI Solana wallet
+// Copyright (C) 2022  Solwalrs contributors <https://github.com/TheAwiteb/solwalrs/graphs/contributors>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/gpl-3.0.html>.
+
+use std::fs::{File, OpenOptions};
+
+use fs2::FileExt;
+
+use super::utils::{app_file_path, sibling_path};
+use crate::{
+    app::AppArgs,
+    errors::{Error as SolwalrsError, Result as SolwalrsResult},
+};
+
+/// An advisory lock of the wallet file, so only one Solwalrs process uses the wallet at a time.
+/// The lock is released when it's dropped
+#[derive(Debug)]
+pub struct WalletLock {
+    /// The lock file, `<wallet file>.lock`
+    file: File,
+}
+
+impl WalletLock {
+    /// Lock the selected wallet file, will return `Error::WalletInUse` if another process holds the lock
+    pub fn acquire(args: &AppArgs) -> SolwalrsResult<Self> {
+        let app_file = app_file_path(args)?;
+        let path = sibling_path(&app_file, "lock");
+        crate::info!(args, "Trying to lock the wallet `{}`", path.display());
+        let file = OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(&path)
+            .map_err(|err| SolwalrsError::IO(format!("Failed to open the lock file: {err}")))?;
+        file.try_lock_exclusive().map_err(|err| {
+            if err.raw_os_error() == fs2::lock_contended_error().raw_os_error() {
+                SolwalrsError::WalletInUse(app_file.display().to_string())
+            } else {
+                SolwalrsError::IO(format!("Failed to lock the wallet file: {err}"))
+            }
+        })?;
+        crate::info!(args, "The wallet is locked");
+        Ok(Self { file })
+    }
+}
+
+impl Drop for WalletLock {
+    fn drop(&mut self) {
+        // The lock is released when the file is closed anyway
+        let _ = self.file.unlock();
+    }
+}
@@ -0,0 +1,139 @@
+// Solwalrs, A simple and easy to use CLI Solana wallet
+// Copyright (C) 2022  Solwalrs contributors <https://github.com/TheAwiteb/solwalrs/graphs/contributors>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/gpl-3.0.html>.
+
+//! The migrations of the wallet file layout, a chain of steps from every historical version to the current one.
+//!
+//! The steps run on the JSON of the wallet file when it's loaded, before it's deserialized, so
+//! the wallet structs only know the current layout. The `version` of the file isn't changed by the
+//! steps, the authentication envelope is verified with the rules of the version that wrote it.
+//! The file is written with the current version the next time the wallet is exported
+
+use serde_json::{Map, Value};
+
+use super::WALLET_VERSION;
+use crate::{
+    app::AppArgs,
+    errors::{Error as SolwalrsError, Result as SolwalrsResult},
+};
+
+/// A migration step of the wallet file layout, from the version `from` to `from + 1`
+pub struct Migration {
+    /// The version that the step migrates from
+    pub from: u32,
+    /// What the step does
+    pub description: &'static str,
+    /// Migrate the wallet file JSON, returns the changes that are made
+    migrate: fn(&mut Map<String, Value>) -> Vec<String>,
+}
+
+/// A migration step that is applied to the wallet file, with the changes it made
+pub struct AppliedMigration {
+    /// The applied migration step
+    pub migration: &'static Migration,
+    /// The changes made by the step
+    pub changes: Vec<String>,
+}
+
+/// The migration steps, ordered by the version they migrate from
+pub const MIGRATIONS: &[Migration] = &[
+    Migration {
+        from: 0,
+        description: "Add the authentication envelope, a key check, a MAC and a revision counter",
+        migrate: migrate_v0,
+    },
+    Migration {
+        from: 1,
+        description: "Support the sealed format, all the keypairs and contacts in one padded blob",
+        migrate: |_| Vec::new(),
+    },
+];
+
+/// Version 0, the files before the envelope. The revision, the contacts and the default flag of the keypairs may be missing
+fn migrate_v0(wallet: &mut Map<String, Value>) -> Vec<String> {
+    let mut changes = Vec::new();
+    if !wallet.contains_key("revision") {
+        wallet.insert("revision".to_owned(), Value::from(0));
+        changes.push("Add the revision counter, starting from 0".to_owned());
+    }
+    if !wallet.contains_key("contacts") {
+        wallet.insert("contacts".to_owned(), Value::Array(Vec::new()));
+        changes.push("Add an empty address book".to_owned());
+    }
+    let keypairs = wallet
+        .get_mut("keypairs")
+        .and_then(Value::as_array_mut)
+        .map(|keypairs| {
+            keypairs
+                .iter_mut()
+                .filter_map(Value::as_object_mut)
+                .filter(|keypair| !keypair.contains_key("is_default"))
+                .map(|keypair| keypair.insert("is_default".to_owned(), Value::Bool(false)))
+                .count()
+        })
+        .unwrap_or_default();
+    if keypairs != 0 {
+        changes.push(format!(
+            "Mark {keypairs} keypair(s) without the default flag as not default"
+        ));
+    }
+    changes.push("Sign the wallet file with the password, when it's exported".to_owned());
+    changes
+}
+
+/// Returns the version of the wallet file JSON, the files without a version are version 0
+pub fn wallet_version(wallet: &Value) -> SolwalrsResult<u32> {
+    match wallet.get("version") {
+        None => Ok(0),
+        Some(version) => version
+            .as_u64()
+            .and_then(|version| u32::try_from(version).ok())
+            .ok_or_else(|| {
+                SolwalrsError::TamperedWallet(format!("its version `{version}` is invalid"))
+            }),
+    }
+}
+
+/// Migrate the wallet file JSON to the current layout, returns the applied steps.
+///
+/// Will return `Error::Wallet` if the wallet file is newer than the supported version
+pub fn migrate(wallet: &mut Value, args: &AppArgs) -> SolwalrsResult<Vec<AppliedMigration>> {
+    let version = wallet_version(wallet)?;
+    if version > WALLET_VERSION {
+        return Err(SolwalrsError::Wallet(format!(
+            "The wallet file version is {version}, but the supported version is {WALLET_VERSION}, please update Solwalrs"
+        )));
+    }
+    let object = wallet
+        .as_object_mut()
+        .ok_or_else(|| SolwalrsError::TamperedWallet("it's not a JSON object".to_owned()))?;
+    let applied = MIGRATIONS
+        .iter()
+        .filter(|migration| migration.from >= version)
+        .map(|migration| {
+            crate::info!(
+                args,
+                "Migrating the wallet file from version {} to {}",
+                migration.from,
+                migration.from + 1
+            );
+            AppliedMigration {
+                migration,
+                changes: (migration.migrate)(object),
+            }
+        })
+        .collect();
+    Ok(applied)
+}
@@ -20,38 +20,117 @@ use crate::{
     app::AppArgs,
     errors::{Error as SolwalrsError, Result as SolwalrsResult},
 };
+use base58::ToBase58;
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use zeroize::Zeroizing;
 
+mod agent;
 pub mod cache;
+mod contact;
+mod currency;
+mod envelope;
 mod keypair;
+mod lock;
+mod migration;
+mod paper;
 mod price;
+mod price_history;
+mod profile;
+mod rpc;
+mod sender;
+mod shamir;
+mod solana_pay;
 mod spl;
+mod transaction;
 mod utils;
 
+pub use agent::*;
+pub use contact::*;
+pub use currency::*;
+pub use envelope::*;
 pub use keypair::*;
+pub use lock::*;
+pub use migration::*;
+pub use paper::*;
 pub use price::*;
+pub use price_history::*;
+pub use profile::*;
+pub use rpc::*;
+pub use sender::*;
+pub use shamir::*;
+pub use solana_pay::*;
 pub use spl::*;
+pub use transaction::*;
 pub use utils::*;
 
 /// The clean wallet (decrypted)
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Wallet {
     /// Wallet keypairs
     pub keypairs: Vec<keypair::KeyPair>,
+    /// The address book, named external addresses
+    pub contacts: Vec<Contact>,
+    /// The revision of the wallet file that the wallet is loaded from, `0` for a new wallet
+    pub revision: u64,
+    /// Whether the wallet is stored in the sealed format, all the keypairs and contacts in one padded blob
+    pub sealed: bool,
 }
 
 /// The encrypted wallet
 #[derive(Debug, Serialize, Deserialize)]
 pub struct EncryptedWallet {
+    /// The version of the wallet file, `0` for the old files without the authentication envelope
+    #[serde(default)]
+    version: u32,
+    /// The revision of the wallet file, it's increased every time the wallet is exported
+    revision: u64,
     /// encrypted wallet keypairs
     keypairs: Vec<keypair::EncryptedKeyPair>,
+    /// encrypted address book
+    contacts: Vec<EncryptedContact>,
+    /// The sealed format, the keypairs and the contacts encrypted in one blob padded to a size bucket.
+    /// The `keypairs` and `contacts` are empty if it's set
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    sealed: Option<String>,
+    /// The key check of the password, to tell a wrong password apart from a modified wallet file
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    key_check: Option<String>,
+    /// The MAC of the version, the revision, the keypairs, the contacts and the sealed blob
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    mac: Option<String>,
 }
 
+/// The authenticated data of the wallet file, the MAC is calculated over its JSON
+#[derive(Serialize)]
+struct AuthenticatedWallet<'a> {
+    version: u32,
+    revision: u64,
+    keypairs: &'a [keypair::EncryptedKeyPair],
+    contacts: &'a [EncryptedContact],
+    #[serde(skip_serializing_if = "Option::is_none")]
+    sealed: Option<&'a str>,
+}
+
+/// The content of the sealed blob
+#[derive(Serialize, Deserialize)]
+struct SealedContents {
+    keypairs: Vec<keypair::EncryptedKeyPair>,
+    contacts: Vec<EncryptedContact>,
+}
+
+/// The minimum size of the sealed blob content, it's padded to the next power of two of its size.
+/// So the size of the wallet file doesn't tell the number of the keypairs
+const SEALED_BUCKET: usize = 4096;
+
 impl Wallet {
     /// Create a new wallet instance
     pub fn new() -> Self {
         Self {
             keypairs: Vec::new(),
+            contacts: Vec::new(),
+            revision: 0,
+            sealed: false,
         }
     }
 
@@ -82,27 +161,78 @@ impl Wallet {
         }
     }
 
-    /// Encrypt the wallet with the given password.
+    /// Load the wallet from a copy of the wallet file, e.g. `solwalrs.json.bak`. The copy is verified with the
+    /// password, but it's older than the last seen revision so the rollback check is skipped.
+    /// Exporting it saves it as a new revision of the wallet file
+    pub fn load_backup(backup: &Path, password: &str, args: &AppArgs) -> SolwalrsResult<Wallet> {
+        crate::info!(
+            args,
+            "Trying to load the wallet copy `{}`",
+            backup.display()
+        );
+        let enc_wallet = EncryptedWallet::from_app_file(backup, args)?;
+        enc_wallet.verify(password.as_bytes(), None)?;
+        enc_wallet.decrypt_unverified(password, args)
+    }
+
+    /// Encrypt the wallet with the given password, the revision will be the next of the last seen revision.
     /// The password must be 32 bytes long. will return `Error::InvalidPassword` if the password is not 32 bytes long.
     #[must_use = "encrypting the wallet will return the encrypted wallet"]
     pub fn encrypt(self, password: &str, args: &AppArgs) -> SolwalrsResult<EncryptedWallet> {
-        crate::info!(args, "Trying to encrypt the wallet");
+        let revision = Revisions::last_seen(args)?
+            .unwrap_or_default()
+            .max(self.revision)
+            + 1;
+        self.encrypt_with_revision(password, revision, args)
+    }
+
+    /// Encrypt the wallet with the given password and revision, and seal it with the authentication envelope
+    fn encrypt_with_revision(
+        self,
+        password: &str,
+        revision: u64,
+        args: &AppArgs,
+    ) -> SolwalrsResult<EncryptedWallet> {
+        crate::info!(
+            args,
+            "Trying to encrypt the wallet, the revision is {revision}"
+        );
         let password = password.as_bytes();
         let enc_keypairs = self
             .keypairs
             .into_iter()
             .map(|keypair| keypair.encrypt(password, args))
             .collect::<SolwalrsResult<Vec<_>>>()?;
+        let enc_contacts = self
+            .contacts
+            .into_iter()
+            .map(|contact| contact.encrypt(password, args))
+            .collect::<SolwalrsResult<Vec<_>>>()?;
         crate::info!(args, "Wallet encrypted successfully");
-        Ok(EncryptedWallet {
+        let mut enc_wallet = EncryptedWallet {
+            version: WALLET_VERSION,
+            revision,
             keypairs: enc_keypairs,
-        })
+            contacts: enc_contacts,
+            sealed: None,
+            key_check: Some(key_check(password)),
+            mac: None,
+        };
+        if self.sealed {
+            enc_wallet.seal(password, args)?;
+        }
+        enc_wallet.mac = Some(wallet_mac(password, &enc_wallet.authenticated_data()?));
+        Ok(enc_wallet)
     }
 
     /// Export the wallet to the app data file
-    pub fn export(self, password: &str, args: &AppArgs) -> SolwalrsResult<()> {
-        let enc_wallet = self.encrypt(password, args)?;
-        enc_wallet.export(args)
+    pub fn export(&mut self, password: &str, args: &AppArgs) -> SolwalrsResult<()> {
+        let enc_wallet = self.clone().encrypt(password, args)?;
+        let revision = enc_wallet.revision;
+        enc_wallet.export(args)?;
+        // The wallet is at the exported revision now, the next export continues from it
+        self.revision = revision;
+        Ok(())
     }
 
     /// Add a keypair to the wallet, if the keypair name already exists, it will return `Error::DuplicateKeyPairName`
@@ -155,6 +285,85 @@ impl Wallet {
         keypair
     }
 
+    /// Get a mutable keypair from the wallet, if the keypair name doesn't exist, it will return `Error::KeyPairNotFound`
+    pub fn get_keypair_mut(
+        &mut self,
+        name: &str,
+        args: &AppArgs,
+    ) -> SolwalrsResult<&mut keypair::KeyPair> {
+        crate::info!(args, "Trying to get {name} from the wallet");
+        self.keypairs
+            .iter_mut()
+            .find(|keypair| keypair.name == name)
+            .ok_or_else(|| SolwalrsError::KeyPairNotFound(name.to_string()))
+    }
+
+    /// Rename a keypair, will return `Error::KeyPairNotFound` if the old name doesn't exist,
+    /// and `Error::DuplicateKeyPairName` if the new name already exists
+    /// Note: this function will not rename the keypair in the wallet file, you need to call `Wallet::export` to do that
+    pub fn rename_keypair(
+        &mut self,
+        old_name: &str,
+        new_name: &str,
+        args: &AppArgs,
+    ) -> SolwalrsResult<()> {
+        crate::info!(args, "Trying to rename `{old_name}` to `{new_name}`");
+        if old_name == new_name {
+            // Renaming a keypair to its current name does nothing
+            return self.get_keypair(old_name, args).map(|_| ());
+        }
+        if self.get_keypair(new_name, args).is_ok() {
+            return Err(SolwalrsError::DuplicateKeyPairName(new_name.to_owned()));
+        }
+        self.get_keypair_mut(old_name, args)?.name = new_name.to_owned();
+        Ok(())
+    }
+
+    /// Returns the keypairs that have the given tag and match the given glob pattern, like `list --tag --search`
+    pub fn filter_keypairs<'a>(
+        &'a self,
+        tag: Option<&'a str>,
+        pattern: Option<&'a str>,
+    ) -> impl Iterator<Item = &'a keypair::KeyPair> {
+        self.keypairs.iter().filter(move |keypair| {
+            tag.is_none_or(|tag| keypair.tags.iter().any(|kp_tag| kp_tag == tag))
+                && pattern.is_none_or(|pattern| keypair.matches(pattern))
+        })
+    }
+
+    /// Set the given keypair as the default keypair, will return `Error::KeyPairNotFound` if the keypair doesn't exist
+    /// Note: this function will not change the wallet file, you need to call `Wallet::export` to do that
+    pub fn set_default_keypair(&mut self, name: &str, args: &AppArgs) -> SolwalrsResult<()> {
+        crate::info!(args, "Trying to set `{name}` as the default keypair");
+        // Make sure the keypair exists before unsetting the current default keypair
+        self.get_keypair(name, args)?;
+        self.keypairs
+            .iter_mut()
+            .for_each(|keypair| keypair.is_default = keypair.name == name);
+        Ok(())
+    }
+
+    /// Returns the keypair of the given address (base58 encoded public key), if it's in the wallet
+    pub fn keypair_by_address(&self, address: &str) -> Option<&keypair::KeyPair> {
+        self.keypairs
+            .iter()
+            .find(|keypair| keypair.public_key.as_bytes().to_base58() == address)
+    }
+
+    /// Returns the address with the keypair name or the contact alias if the address is in the wallet,
+    /// e.g. `address (name)` or `address (@alias)`
+    pub fn annotate_address(&self, address: &str) -> String {
+        self.keypair_by_address(address)
+            .map(|keypair| format!("{address} ({})", keypair.name))
+            .or_else(|| {
+                self.contacts
+                    .iter()
+                    .find(|contact| contact.address.to_string() == address)
+                    .map(|contact| format!("{address} (@{})", contact.alias))
+            })
+            .unwrap_or_else(|| address.to_owned())
+    }
+
     /// Delete a keypair from the wallet, if the keypair name doesn't exist, it will return `Error::KeyPairNotFound`
     /// Note: this function will not delete the keypair from the wallet file, you need to call `Wallet::export` to do that
     pub fn delete_keypair(
@@ -172,6 +381,90 @@ impl Wallet {
         Ok(self.keypairs.remove(index?))
     }
 
+    /// Add a contact to the address book, if the alias already exists, it will return `Error::DuplicateContact`
+    /// Note: this function will not add the contact to the wallet file, you need to call `Wallet::export` to do that
+    pub fn add_contact(&mut self, contact: Contact, args: &AppArgs) -> SolwalrsResult<()> {
+        crate::info!(args, "Trying to add {contact:?} to the address book");
+        if self.get_contact(&contact.alias, args).is_ok() {
+            return Err(SolwalrsError::DuplicateContact(contact.alias));
+        }
+        self.contacts.push(contact);
+        Ok(())
+    }
+
+    /// Get a contact from the address book, the alias can start with `@`.
+    /// If the alias doesn't exist, it will return `Error::ContactNotFound`
+    pub fn get_contact(&self, alias: &str, args: &AppArgs) -> SolwalrsResult<&Contact> {
+        crate::info!(args, "Trying to get the contact `{alias}`");
+        let alias = alias.trim_start_matches('@');
+        self.contacts
+            .iter()
+            .find(|contact| contact.alias == alias)
+            .ok_or_else(|| SolwalrsError::ContactNotFound(alias.to_owned()))
+    }
+
+    /// Remove a contact from the address book, if the alias doesn't exist, it will return `Error::ContactNotFound`
+    /// Note: this function will not remove the contact from the wallet file, you need to call `Wallet::export` to do that
+    pub fn remove_contact(&mut self, alias: &str, args: &AppArgs) -> SolwalrsResult<Contact> {
+        crate::info!(args, "Trying to remove the contact `{alias}`");
+        let alias = alias.trim_start_matches('@');
+        let index = self
+            .contacts
+            .iter()
+            .position(|contact| contact.alias == alias)
+            .ok_or_else(|| SolwalrsError::ContactNotFound(alias.to_owned()))?;
+        Ok(self.contacts.remove(index))
+    }
+
+    /// Rename a contact, will return `Error::ContactNotFound` if the old alias doesn't exist,
+    /// `Error::InvalidContactAlias` if the new alias is invalid and `Error::DuplicateContact` if it already exists
+    pub fn rename_contact(
+        &mut self,
+        old_alias: &str,
+        new_alias: &str,
+        args: &AppArgs,
+    ) -> SolwalrsResult<()> {
+        let new_alias = Contact::validate_alias(new_alias)?;
+        let old_alias = old_alias.trim_start_matches('@');
+        // Renaming a contact to its current alias does nothing
+        if old_alias != new_alias && self.get_contact(new_alias, args).is_ok() {
+            return Err(SolwalrsError::DuplicateContact(new_alias.to_owned()));
+        }
+        let contact = self
+            .contacts
+            .iter_mut()
+            .find(|contact| contact.alias == old_alias)
+            .ok_or_else(|| SolwalrsError::ContactNotFound(old_alias.to_owned()))?;
+        contact.alias = new_alias.to_owned();
+        Ok(())
+    }
+
+    /// Resolve the given input to an address, the input can be a `@alias` of a contact,
+    /// a keypair name or a base58 address
+    pub fn resolve_address(&self, input: &str, args: &AppArgs) -> SolwalrsResult<ResolvedAddress> {
+        crate::info!(args, "Trying to resolve `{input}` to an address");
+        if input.starts_with('@') {
+            let contact = self.get_contact(input, args)?;
+            return Ok(ResolvedAddress {
+                address: contact.address,
+                source: Some(format!("contact @{}", contact.alias)),
+            });
+        }
+        if let Ok(keypair) = self.get_keypair(input, args) {
+            return Ok(ResolvedAddress {
+                address: keypair.pubkey(),
+                source: Some(format!("keypair `{}`", keypair.name)),
+            });
+        }
+        input
+            .parse()
+            .map(|address| ResolvedAddress {
+                address,
+                source: None,
+            })
+            .map_err(|_| SolwalrsError::InvalidAddress(input.to_owned()))
+    }
+
     /// Returns the default keypair, if there is no default keypair, it will return `Error::NoDefaultKeyPair`
     pub fn default_keypair(&self, args: &AppArgs) -> SolwalrsResult<&keypair::KeyPair> {
         crate::info!(
@@ -201,9 +494,12 @@ impl EncryptedWallet {
         let file = File::open(file_path)
             .map_err(|err| SolwalrsError::Wallet(format!("Failed to open wallet file: {}", err)))?;
         let reader = BufReader::new(file);
-        let wallet: Self = serde_json::from_reader(reader).map_err(|err| {
-            SolwalrsError::Wallet(format!("Failed to deserialize wallet: {}", err))
+        let mut wallet: Value = serde_json::from_reader(reader).map_err(|err| {
+            SolwalrsError::TamperedWallet(format!(
+                "it can't be parsed, it may be truncated ({err})"
+            ))
         })?;
+        let wallet = Self::from_value(&mut wallet, args)?;
         crate::info!(
             args,
             "Ecrypted wallet imported successfully from {}",
@@ -212,23 +508,144 @@ impl EncryptedWallet {
         Ok(wallet)
     }
 
-    /// Decrypt the wallet with the given password.
+    /// Migrate the wallet file JSON to the current layout and deserialize it
+    fn from_value(wallet: &mut Value, args: &AppArgs) -> SolwalrsResult<Self> {
+        migrate(wallet, args)?;
+        Self::deserialize(wallet.take())
+            .map_err(|err| SolwalrsError::TamperedWallet(format!("its layout is invalid ({err})")))
+    }
+
+    /// Returns the authenticated data of the wallet file
+    fn authenticated_data(&self) -> SolwalrsResult<Vec<u8>> {
+        serde_json::to_vec(&AuthenticatedWallet {
+            version: self.version,
+            revision: self.revision,
+            keypairs: &self.keypairs,
+            contacts: &self.contacts,
+            sealed: self.sealed.as_deref(),
+        })
+        .map_err(|err| SolwalrsError::Wallet(format!("Failed to serialize wallet: {err}")))
+    }
+
+    /// Verify the authentication envelope of the wallet file, `last_seen` is the last seen revision of it.
+    ///
+    /// Will return `Error::InvalidPassword` if the password is not correct, and `Error::TamperedWallet`
+    /// if the wallet file is modified or rolled back to an older revision
+    fn verify(&self, password: &[u8], last_seen: Option<u64>) -> SolwalrsResult<()> {
+        if self.version == 0 {
+            // The old wallet files don't have the envelope, it will be added when the wallet is exported
+            return match last_seen {
+                Some(last_seen) => Err(SolwalrsError::TamperedWallet(format!(
+                    "the authentication envelope is removed, but the revision {last_seen} of the wallet file was seen before"
+                ))),
+                None => Ok(()),
+            };
+        }
+        let (Some(check), Some(mac)) = (&self.key_check, &self.mac) else {
+            return Err(SolwalrsError::TamperedWallet(
+                "the authentication envelope is incomplete".to_owned(),
+            ));
+        };
+        if !verify_key_check(password, check) {
+            return Err(SolwalrsError::InvalidPassword(
+                "The password is not correct".to_owned(),
+            ));
+        }
+        if !verify_wallet_mac(password, &self.authenticated_data()?, mac) {
+            return Err(SolwalrsError::TamperedWallet(
+                "it was modified outside of Solwalrs".to_owned(),
+            ));
+        }
+        if let Some(last_seen) = last_seen.filter(|last_seen| *last_seen > self.revision) {
+            return Err(SolwalrsError::TamperedWallet(format!(
+                "it was rolled back to the revision {}, but the revision {last_seen} was seen before",
+                self.revision
+            )));
+        }
+        Ok(())
+    }
+
+    /// Verify the authentication envelope and decrypt the wallet with the given password.
     /// The password must be 32 bytes long. will return `Error::InvalidPassword` if the password is not 32 bytes long.
     #[must_use = "decrypting the wallet will return the decrypted wallet"]
     pub fn decrypt(self, password: &str, args: &AppArgs) -> SolwalrsResult<Wallet> {
+        crate::info!(
+            args,
+            "Trying to verify the wallet, its revision is {}",
+            self.revision
+        );
+        self.verify(password.as_bytes(), Revisions::last_seen(args)?)?;
+        if self.version == 0 {
+            crate::warn!(args, "The wallet file has no authentication envelope");
+        } else {
+            Revisions::record(self.revision, args)?;
+        }
+        self.decrypt_unverified(password, args)
+    }
+
+    /// Decrypt the wallet without verifying its authentication envelope
+    fn decrypt_unverified(mut self, password: &str, args: &AppArgs) -> SolwalrsResult<Wallet> {
         crate::info!(args, "Trying to decrypt the wallet");
         let password = password.as_bytes();
+        let sealed = self.sealed.is_some();
+        self.unseal(password, args)?;
         let mut keypairs = self
             .keypairs
             .into_iter()
             .map(|keypair| keypair.decrypt(password, args))
             .collect::<SolwalrsResult<Vec<_>>>()?;
+        let mut contacts = self
+            .contacts
+            .into_iter()
+            .map(|contact| contact.decrypt(password, args))
+            .collect::<SolwalrsResult<Vec<_>>>()?;
         crate::info!(args, "Wallet decrypted successfully");
 
-        // Sort the keypairs by name
+        // Sort the keypairs and contacts by name
         keypairs.sort_by(|a, b| a.name.cmp(&b.name));
+        contacts.sort_by(|a, b| a.alias.cmp(&b.alias));
+
+        Ok(Wallet {
+            keypairs,
+            contacts,
+            revision: self.revision,
+            sealed,
+        })
+    }
+
+    /// Move the keypairs and the contacts into the sealed blob, padded to the size bucket
+    fn seal(&mut self, password: &[u8], args: &AppArgs) -> SolwalrsResult<()> {
+        let mut contents = Zeroizing::new(
+            serde_json::to_vec(&SealedContents {
+                keypairs: std::mem::take(&mut self.keypairs),
+                contacts: std::mem::take(&mut self.contacts),
+            })
+            .map_err(|err| SolwalrsError::Wallet(format!("Failed to serialize wallet: {err}")))?,
+        );
+        // JSON ignores the trailing whitespace, so the padding is spaces
+        let padded_len = contents.len().max(SEALED_BUCKET).next_power_of_two();
+        crate::info!(
+            args,
+            "Sealing the wallet, {} bytes padded to {padded_len} bytes",
+            contents.len()
+        );
+        contents.resize(padded_len, b' ');
+        self.sealed = Some(utils::encrypt(password, &contents)?);
+        Ok(())
+    }
 
-        Ok(Wallet { keypairs })
+    /// Move the keypairs and the contacts out of the sealed blob, if the wallet is sealed
+    fn unseal(&mut self, password: &[u8], args: &AppArgs) -> SolwalrsResult<()> {
+        if let Some(sealed) = self.sealed.take() {
+            crate::info!(args, "Unsealing the wallet");
+            let contents: SealedContents =
+                serde_json::from_slice(&utils::decrypt(password, &sealed)?).map_err(|err| {
+                    SolwalrsError::Wallet(format!("Failed to deserialize the sealed wallet: {err}"))
+                })?;
+            self.keypairs = contents.keypairs;
+            self.contacts = contents.contacts;
+        }
+        Ok(())
     }
 
     /// Export the wallet to the app data file, if the app data file doesn't exist, it will create it
@@ -241,11 +658,22 @@ impl EncryptedWallet {
             self
         );
 
-        let file = std::fs::File::create(&app_file).map_err(|err| {
-            SolwalrsError::AppDataDir(format!("Failed to create wallet file: {}", err))
-        })?;
-        serde_json::to_writer(file, &self)
+        let contents = serde_json::to_vec(&self)
             .map_err(|err| SolwalrsError::Wallet(format!("Failed to serialize wallet: {}", err)))?;
+        if app_file.exists() {
+            let backup = utils::sibling_path(&app_file, "bak");
+            crate::info!(
+                args,
+                "Keeping the previous wallet in `{}`",
+                backup.display()
+            );
+            let previous = std::fs::read(&app_file).map_err(|err| {
+                SolwalrsError::IO(format!("Failed to read the previous wallet file: {err}"))
+            })?;
+            utils::write_atomically(&backup, &previous)?;
+        }
+        utils::write_atomically(&app_file, &contents)?;
+        Revisions::record(self.revision, args)?;
         crate::info!(
             args,
             "Wallet exported successfully to {}",
@@ -255,3 +683,322 @@ impl EncryptedWallet {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use clap::Parser;
+    use serde_json::Value;
+
+    use super::*;
+
+    const PASSWORD: &str = "0123456789abcdef0123456789abcdef";
+
+    fn args() -> AppArgs {
+        AppArgs::parse_from(["solwalrs"])
+    }
+
+    /// Returns an enveloped wallet file with two keypairs, as JSON
+    fn enveloped_wallet(revision: u64) -> Value {
+        let mut wallet = Wallet::new();
+        wallet.keypairs.push(KeyPair::new("first", true));
+        wallet.keypairs.push(KeyPair::new("second", false));
+        let enc_wallet = wallet
+            .encrypt_with_revision(PASSWORD, revision, &args())
+            .unwrap();
+        serde_json::to_value(enc_wallet).unwrap()
+    }
+
+    /// Load the wallet file JSON, like `EncryptedWallet::from_app_file`
+    fn load(mut wallet: Value) -> EncryptedWallet {
+        EncryptedWallet::from_value(&mut wallet, &args()).unwrap()
+    }
+
+    fn verify(wallet: Value, password: &str, last_seen: Option<u64>) -> SolwalrsResult<()> {
+        load(wallet).verify(password.as_bytes(), last_seen)
+    }
+
+    #[test]
+    fn enveloped_wallet_is_verified() {
+        assert!(verify(enveloped_wallet(3), PASSWORD, Some(3)).is_ok());
+        assert!(verify(enveloped_wallet(3), PASSWORD, None).is_ok());
+        let wallet = load(enveloped_wallet(3))
+            .decrypt_unverified(PASSWORD, &args())
+            .unwrap();
+        assert_eq!(wallet.keypairs.len(), 2);
+        assert_eq!(wallet.revision, 3);
+    }
+
+    #[test]
+    fn rolled_back_copy_is_restored() {
+        let backup = std::env::temp_dir().join(format!("solwalrs-{}.json.bak", std::process::id()));
+        std::fs::write(&backup, enveloped_wallet(2).to_string()).unwrap();
+        let wallet = Wallet::load_backup(&backup, PASSWORD, &args());
+        let wrong_password = Wallet::load_backup(&backup, &PASSWORD.to_uppercase(), &args());
+        std::fs::remove_file(&backup).unwrap();
+        // The rollback check is skipped, but the password and the MAC are verified
+        assert_eq!(wallet.unwrap().revision, 2);
+        assert!(matches!(
+            wrong_password,
+            Err(SolwalrsError::InvalidPassword(_))
+        ));
+    }
+
+    #[test]
+    fn wrong_password() {
+        assert!(matches!(
+            verify(
+                enveloped_wallet(1),
+                "fedcba9876543210fedcba9876543210",
+                None
+            ),
+            Err(SolwalrsError::InvalidPassword(_))
+        ));
+    }
+
+    #[test]
+    fn modified_wallet() {
+        let mut default_changed = enveloped_wallet(1);
+        default_changed["keypairs"][1]["is_default"] = Value::Bool(true);
+
+        let mut reordered = enveloped_wallet(1);
+        reordered["keypairs"].as_array_mut().unwrap().reverse();
+
+        let mut truncated = enveloped_wallet(1);
+        truncated["keypairs"].as_array_mut().unwrap().pop();
+
+        let mut revision_changed = enveloped_wallet(1);
+        revision_changed["revision"] = Value::from(5);
+
+        for wallet in [default_changed, reordered, truncated, revision_changed] {
+            assert!(matches!(
+                verify(wallet, PASSWORD, None),
+                Err(SolwalrsError::TamperedWallet(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn rolled_back_wallet() {
+        assert!(matches!(
+            verify(enveloped_wallet(2), PASSWORD, Some(3)),
+            Err(SolwalrsError::TamperedWallet(_))
+        ));
+    }
+
+    #[test]
+    fn removed_envelope() {
+        let mut legacy = enveloped_wallet(2);
+        let object = legacy.as_object_mut().unwrap();
+        for key in ["version", "revision", "key_check", "mac"] {
+            object.remove(key);
+        }
+        // The old wallet files are accepted, unless an enveloped revision was seen before
+        assert!(verify(legacy.clone(), PASSWORD, None).is_ok());
+        assert!(matches!(
+            verify(legacy, PASSWORD, Some(2)),
+            Err(SolwalrsError::TamperedWallet(_))
+        ));
+    }
+
+    #[test]
+    fn sealed_wallet_hides_metadata() {
+        let sealed_file = |keypairs: usize| {
+            let mut wallet = Wallet::new();
+            wallet.sealed = true;
+            for idx in 0..keypairs {
+                wallet
+                    .keypairs
+                    .push(KeyPair::new(format!("kp{idx}"), idx == 0));
+            }
+            serde_json::to_value(wallet.encrypt_with_revision(PASSWORD, 1, &args()).unwrap())
+                .unwrap()
+        };
+        let (one, three) = (sealed_file(1), sealed_file(3));
+        assert_eq!(one["keypairs"], Value::Array(Vec::new()));
+        assert_eq!(
+            one["sealed"].as_str().unwrap().len(),
+            three["sealed"].as_str().unwrap().len()
+        );
+        assert!(!three.to_string().contains("is_default"));
+
+        assert!(verify(three.clone(), PASSWORD, None).is_ok());
+        let wallet = load(three).decrypt_unverified(PASSWORD, &args()).unwrap();
+        assert!(wallet.sealed);
+        assert_eq!(wallet.keypairs.len(), 3);
+        assert!(wallet.keypairs[0].is_default);
+    }
+
+    /// The wallet files written by each historical version, all of them have the keypairs `alice`
+    /// (default) and `bob`. Version 1 and 2 have the contact `@carol` too
+    const FIXTURES: &[(u32, &str)] = &[
+        (0, include_str!("fixtures/wallet-v0.json")),
+        (1, include_str!("fixtures/wallet-v1.json")),
+        (2, include_str!("fixtures/wallet-v2.json")),
+    ];
+
+    fn fixture(version: u32) -> Value {
+        let (_, fixture) = FIXTURES
+            .iter()
+            .find(|(fixture_version, _)| *fixture_version == version)
+            .unwrap();
+        serde_json::from_str(fixture).unwrap()
+    }
+
+    #[test]
+    fn rename_keypairs() {
+        let mut wallet = Wallet::new();
+        wallet.keypairs.push(KeyPair::new("first", true));
+        wallet.keypairs.push(KeyPair::new("second", false));
+        assert!(wallet.rename_keypair("first", "first", &args()).is_ok());
+        assert!(matches!(
+            wallet.rename_keypair("first", "second", &args()),
+            Err(SolwalrsError::DuplicateKeyPairName(_))
+        ));
+        assert!(matches!(
+            wallet.rename_keypair("third", "third", &args()),
+            Err(SolwalrsError::KeyPairNotFound(_))
+        ));
+        wallet.rename_keypair("first", "main", &args()).unwrap();
+        assert_eq!(wallet.default_keypair(&args()).unwrap().name, "main");
+    }
+
+    #[test]
+    fn keypair_tags_and_notes() {
+        let mut wallet = Wallet::new();
+        for name in ["team-alice", "team-bob", "savings"] {
+            wallet.keypairs.push(KeyPair::new(name, false));
+        }
+        let tags = |tags: &[&str]| tags.iter().map(|tag| tag.to_string()).collect::<Vec<_>>();
+        wallet.keypairs[0].add_tags(&tags(&["team", "hot", "team"]));
+        wallet.keypairs[1].add_tags(&tags(&["team", "cold"]));
+        wallet.keypairs[1].remove_tags(&tags(&["cold", "unknown"]));
+        wallet.keypairs[2].note = Some("The Long Term savings".to_owned());
+        assert_eq!(wallet.keypairs[0].tags, ["team", "hot"]);
+        assert_eq!(wallet.keypairs[1].tags, ["team"]);
+
+        let names = |tag: Option<&str>, pattern: Option<&str>| {
+            wallet
+                .filter_keypairs(tag, pattern)
+                .map(|keypair| keypair.name.clone())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(names(None, None), ["team-alice", "team-bob", "savings"]);
+        assert_eq!(names(Some("team"), None), ["team-alice", "team-bob"]);
+        assert!(names(Some("te"), None).is_empty());
+        assert_eq!(names(None, Some("HOT")), ["team-alice"]);
+        assert_eq!(names(None, Some("*long term*")), ["savings"]);
+        assert_eq!(names(Some("team"), Some("*bob")), ["team-bob"]);
+
+        // The tags and the notes are kept after encrypting the wallet
+        let enc_wallet = wallet.encrypt_with_revision(PASSWORD, 1, &args()).unwrap();
+        let wallet = load(serde_json::to_value(enc_wallet).unwrap())
+            .decrypt_unverified(PASSWORD, &args())
+            .unwrap();
+        let alice = wallet.get_keypair("team-alice", &args()).unwrap();
+        assert_eq!(alice.tags, ["team", "hot"]);
+        let savings = wallet.get_keypair("savings", &args()).unwrap();
+        assert_eq!(savings.note.as_deref(), Some("The Long Term savings"));
+    }
+
+    #[test]
+    fn contact_aliases() {
+        const ADDRESS: &str = "11111111111111111111111111111111";
+        for alias in ["@", "", "my friend", "@bob@", "café"] {
+            assert!(matches!(
+                Contact::new(alias, ADDRESS, None),
+                Err(SolwalrsError::InvalidContactAlias(_))
+            ));
+        }
+        let mut wallet = Wallet::new();
+        for alias in ["@alice", "bob_2"] {
+            wallet
+                .add_contact(Contact::new(alias, ADDRESS, None).unwrap(), &args())
+                .unwrap();
+        }
+        assert!(matches!(
+            wallet.add_contact(Contact::new("bob_2", ADDRESS, None).unwrap(), &args()),
+            Err(SolwalrsError::DuplicateContact(_))
+        ));
+        assert!(wallet.rename_contact("@alice", "alice", &args()).is_ok());
+        assert!(matches!(
+            wallet.rename_contact("alice", "@bob_2", &args()),
+            Err(SolwalrsError::DuplicateContact(_))
+        ));
+        assert!(matches!(
+            wallet.rename_contact("alice", "al ice", &args()),
+            Err(SolwalrsError::InvalidContactAlias(_))
+        ));
+        wallet.rename_contact("alice", "carol", &args()).unwrap();
+        assert_eq!(wallet.contacts[0].alias, "carol");
+    }
+
+    #[test]
+    fn migrations_reach_the_current_version() {
+        for (idx, migration) in MIGRATIONS.iter().enumerate() {
+            assert_eq!(migration.from, idx as u32);
+        }
+        assert_eq!(MIGRATIONS.len() as u32, WALLET_VERSION);
+        assert_eq!(FIXTURES.len() as u32, WALLET_VERSION + 1);
+    }
+
+    #[test]
+    fn historical_wallet_files_are_loaded() {
+        for (version, _) in FIXTURES {
+            let wallet = load(fixture(*version));
+            assert!(wallet.verify(PASSWORD.as_bytes(), None).is_ok());
+            let wallet = wallet.decrypt_unverified(PASSWORD, &args()).unwrap();
+            let names: Vec<_> = wallet.keypairs.iter().map(|kp| kp.name.as_str()).collect();
+            assert_eq!(names, ["alice", "bob"], "version {version}");
+            assert!(wallet.keypairs[0].is_default);
+            assert!(!wallet.keypairs[1].is_default);
+            assert_eq!(wallet.sealed, *version == 2);
+            if *version > 0 {
+                assert_eq!(wallet.keypairs[0].tags, ["treasury"]);
+                assert_eq!(wallet.contacts[0].alias, "carol");
+            }
+        }
+    }
+
+    #[test]
+    fn migration_steps_of_historical_versions() {
+        for (version, _) in FIXTURES {
+            let mut wallet = fixture(*version);
+            let applied = migrate(&mut wallet, &args()).unwrap();
+            let steps: Vec<_> = applied.iter().map(|step| step.migration.from).collect();
+            assert_eq!(steps, (*version..WALLET_VERSION).collect::<Vec<_>>());
+            // The steps don't change the version, the envelope is verified with its rules
+            assert_eq!(wallet_version(&wallet).unwrap(), *version);
+        }
+
+        let mut legacy = fixture(0);
+        let applied = migrate(&mut legacy, &args()).unwrap();
+        assert!(applied[0]
+            .changes
+            .contains(&"Mark 1 keypair(s) without the default flag as not default".to_owned()));
+        assert_eq!(legacy["revision"], Value::from(0));
+        assert_eq!(legacy["contacts"], Value::Array(Vec::new()));
+    }
+
+    #[test]
+    fn migrated_wallet_is_exported_with_the_current_version() {
+        let wallet = load(fixture(0))
+            .decrypt_unverified(PASSWORD, &args())
+            .unwrap();
+        let exported =
+            serde_json::to_value(wallet.encrypt_with_revision(PASSWORD, 1, &args()).unwrap())
+                .unwrap();
+        assert_eq!(wallet_version(&exported).unwrap(), WALLET_VERSION);
+        assert!(migrate(&mut exported.clone(), &args()).unwrap().is_empty());
+        assert!(verify(exported, PASSWORD, Some(1)).is_ok());
+    }
+
+    #[test]
+    fn newer_wallet_file_is_rejected() {
+        let mut newer = fixture(WALLET_VERSION);
+        newer["version"] = Value::from(WALLET_VERSION + 1);
+        assert!(matches!(
+            migrate(&mut newer, &args()),
+            Err(SolwalrsError::Wallet(_))
+        ));
+    }
+}
@@ -0,0 +1,373 @@
+// Solwalrs, A simple and easy to use CLI Solana wallet
+// Copyright (C) 2022  Solwalrs contributors <https://github.com/TheAwiteb/solwalrs/graphs/contributors>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/gpl-3.0.html>.
+
+//! The printable paper wallet of a keypair, a page with the address QR code and an optional
+//! passphrase-encrypted secret QR code, rendered as PNG or SVG.
+//!
+//! The secret is encrypted BIP38-style, the key is derived from the passphrase with scrypt and
+//! salted with the hash of the address, so a wrong passphrase is detected when it's decrypted
+
+use std::path::Path;
+
+use base58::{FromBase58, ToBase58};
+use image::{GrayImage, Luma};
+use qrcode::QrCode;
+use sha2::{Digest, Sha256};
+use zeroize::Zeroizing;
+
+use super::KeyPair;
+use crate::{
+    app::AppArgs,
+    errors::{Error as SolwalrsError, Result as SolwalrsResult},
+    font, utils,
+};
+
+/// The prefix of the encrypted paper wallet secret
+pub const PAPER_SECRET_PREFIX: &str = "solwalrs-paper-";
+
+/// The page size, A4 at 150 DPI
+const PAGE_WIDTH: u32 = 1240;
+const PAGE_HEIGHT: u32 = 1754;
+/// The page margin
+const MARGIN: u32 = 80;
+/// The size of the QR codes
+const QR_SIZE: u32 = 500;
+/// The number of the characters per line of the wrapped secret
+const LINE_CHARS: usize = 56;
+
+/// The scrypt parameters of the secret encryption, the same as BIP38 (N = 2^14, r = 8, p = 8)
+const SCRYPT_LOG_N: u8 = 14;
+const SCRYPT_R: u32 = 8;
+const SCRYPT_P: u32 = 8;
+
+/// An element of the paper wallet page, the position is the top left corner in pixels
+enum Element {
+    /// A text line, the characters are `6 * scale` pixels wide and `8 * scale` pixels high
+    Text {
+        x: u32,
+        y: u32,
+        scale: u32,
+        text: String,
+    },
+    /// A QR code, fitted into a `size` pixels square
+    QrCode {
+        x: u32,
+        y: u32,
+        size: u32,
+        qr_code: QrCode,
+    },
+}
+
+/// The printable paper wallet of a keypair
+pub struct PaperWallet {
+    /// The name of the keypair
+    pub name: String,
+    /// The address of the keypair
+    pub address: String,
+    /// The passphrase-encrypted secret of the keypair, if it's included
+    pub encrypted_secret: Option<String>,
+    /// The creation date of the paper wallet, `YYYY-MM-DD`
+    pub created: String,
+}
+
+/// Returns the address hash, the salt of the secret encryption and its check
+fn address_hash(address: &str) -> [u8; 4] {
+    let hash = Sha256::digest(Sha256::digest(address.as_bytes()));
+    let mut address_hash = [0; 4];
+    address_hash.copy_from_slice(&hash[..4]);
+    address_hash
+}
+
+/// Derive the encryption key of the secret from the passphrase
+fn derive_key(passphrase: &str, salt: &[u8]) -> SolwalrsResult<Zeroizing<Vec<u8>>> {
+    // SAFETY: the parameters are valid
+    let params = scrypt::Params::new(SCRYPT_LOG_N, SCRYPT_R, SCRYPT_P).unwrap();
+    let mut key = Zeroizing::new(vec![0; 32]);
+    scrypt::scrypt(passphrase.as_bytes(), salt, &params, &mut key)
+        .map_err(|err| SolwalrsError::Other(format!("Failed to derive the key: {err}")))?;
+    Ok(key)
+}
+
+/// Encrypt the secret of the keypair with the passphrase, `solwalrs-paper-<address hash>-<fernet token>`
+pub fn encrypt_paper_secret(keypair: &KeyPair, passphrase: &str) -> SolwalrsResult<String> {
+    let address_hash = address_hash(&keypair.pubkey().to_string());
+    let key = derive_key(passphrase, &address_hash)?;
+    Ok(format!(
+        "{PAPER_SECRET_PREFIX}{}-{}",
+        address_hash.to_base58(),
+        super::utils::encrypt(&key, &keypair.keypair_bytes())?
+    ))
+}
+
+/// Decrypt the secret of a paper wallet, returns the 64 bytes of the keypair.
+/// Will return `Error::InvalidPassword` if the passphrase is not correct
+pub fn decrypt_paper_secret(secret: &str, passphrase: &str) -> SolwalrsResult<Zeroizing<Vec<u8>>> {
+    let invalid = || SolwalrsError::Other("The paper wallet secret is invalid".to_owned());
+    let (salt, token) = secret
+        .trim()
+        .strip_prefix(PAPER_SECRET_PREFIX)
+        .and_then(|secret| secret.split_once('-'))
+        .ok_or_else(invalid)?;
+    let salt = salt.from_base58().map_err(|_| invalid())?;
+    let key = derive_key(passphrase, &salt)?;
+    let bytes = super::utils::decrypt(&key, token)
+        .map_err(|_| SolwalrsError::InvalidPassword("The passphrase is not correct".to_owned()))?;
+    // The public key is the last 32 bytes, its address hash is the salt
+    if bytes.len() != 64 || address_hash(&bytes[32..].to_base58()) != *salt {
+        return Err(invalid());
+    }
+    Ok(bytes)
+}
+
+impl PaperWallet {
+    /// Create the paper wallet of the keypair, the secret is included if the passphrase is given
+    pub fn new(
+        keypair: &KeyPair,
+        passphrase: Option<&str>,
+        args: &AppArgs,
+    ) -> SolwalrsResult<Self> {
+        crate::info!(
+            args,
+            "Creating the paper wallet of `{}`, the secret is included: {}",
+            keypair.name,
+            passphrase.is_some()
+        );
+        Ok(Self {
+            name: keypair.name.clone(),
+            address: keypair.pubkey().to_string(),
+            encrypted_secret: passphrase
+                .map(|passphrase| encrypt_paper_secret(keypair, passphrase))
+                .transpose()?,
+            created: chrono::Local::now().format("%Y-%m-%d").to_string(),
+        })
+    }
+
+    /// The checksum of the printed address and secret, to check the transcription of them
+    pub fn checksum(&self) -> String {
+        let mut hasher = Sha256::new();
+        hasher.update(self.address.as_bytes());
+        hasher.update(b"\n");
+        hasher.update(self.encrypted_secret.as_deref().unwrap_or_default());
+        hasher.finalize()[..4]
+            .iter()
+            .map(|byte| format!("{byte:02x}"))
+            .collect()
+    }
+
+    /// Returns the elements of the page
+    fn layout(&self) -> SolwalrsResult<Vec<Element>> {
+        let text = |x, y, scale, text: &str| Element::Text {
+            x,
+            y,
+            scale,
+            text: text.to_owned(),
+        };
+
+        let mut elements = vec![
+            text(MARGIN, MARGIN, 6, "Solwalrs paper wallet"),
+            text(MARGIN, 170, 3, &format!("Keypair: {}", self.name)),
+            text(MARGIN, 210, 3, &format!("Created: {}", self.created)),
+        ];
+        let address_x = match self.encrypted_secret {
+            Some(_) => MARGIN,
+            None => (PAGE_WIDTH - QR_SIZE) / 2,
+        };
+        elements.push(Element::QrCode {
+            x: address_x,
+            y: 290,
+            size: QR_SIZE,
+            qr_code: utils::qr_code(&self.address)?,
+        });
+        elements.push(text(address_x, 810, 3, "Address (share it)"));
+        if let Some(secret) = &self.encrypted_secret {
+            let secret_x = PAGE_WIDTH - MARGIN - QR_SIZE;
+            elements.push(Element::QrCode {
+                x: secret_x,
+                y: 290,
+                size: QR_SIZE,
+                qr_code: utils::qr_code(secret)?,
+            });
+            elements.push(text(secret_x, 810, 3, "Secret (keep it private)"));
+        }
+
+        let mut y = 900;
+        elements.push(text(MARGIN, y, 3, "Address:"));
+        y += 40;
+        elements.push(text(MARGIN, y, 3, &self.address));
+        y += 80;
+        if let Some(secret) = &self.encrypted_secret {
+            elements.push(text(MARGIN, y, 3, "Encrypted secret:"));
+            for line in secret.as_bytes().chunks(LINE_CHARS) {
+                y += 40;
+                // The secret is ASCII, so the chunks are valid UTF-8
+                elements.push(text(MARGIN, y, 3, &String::from_utf8_lossy(line)));
+            }
+            y += 80;
+        }
+        elements.push(text(
+            MARGIN,
+            y,
+            3,
+            &format!("Checksum: {}", self.checksum()),
+        ));
+        if self.encrypted_secret.is_some() {
+            elements.push(text(
+                MARGIN,
+                PAGE_HEIGHT - MARGIN - 24,
+                3,
+                "Import the secret with: solwalrs import <name>",
+            ));
+        }
+        Ok(elements)
+    }
+
+    /// Render the paper wallet as a PNG image (grayscale)
+    pub fn to_png(&self) -> SolwalrsResult<GrayImage> {
+        let mut page = GrayImage::from_pixel(PAGE_WIDTH, PAGE_HEIGHT, Luma([255]));
+        for element in self.layout()? {
+            match element {
+                Element::Text { x, y, scale, text } => {
+                    font::draw_text(&mut page, x, y, scale, &text, Luma([0]))
+                }
+                Element::QrCode {
+                    x,
+                    y,
+                    size,
+                    qr_code,
+                } => {
+                    let image = qr_code
+                        .render::<Luma<u8>>()
+                        .quiet_zone(false)
+                        .max_dimensions(size, size)
+                        .build();
+                    image::imageops::replace(&mut page, &image, x, y);
+                }
+            }
+        }
+        Ok(page)
+    }
+
+    /// Render the paper wallet as an SVG document, the page is A4
+    pub fn to_svg(&self) -> SolwalrsResult<String> {
+        let mut svg = format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"210mm\" height=\"297mm\" viewBox=\"0 0 {PAGE_WIDTH} {PAGE_HEIGHT}\">\n\
+             <rect width=\"100%\" height=\"100%\" fill=\"#fff\"/>\n"
+        );
+        for element in self.layout()? {
+            match element {
+                Element::Text { x, y, scale, text } => svg.push_str(&format!(
+                    "<text x=\"{x}\" y=\"{}\" font-family=\"monospace\" font-size=\"{}\">{}</text>\n",
+                    y + 7 * scale,
+                    10 * scale,
+                    utils::escape_xml(&text)
+                )),
+                Element::QrCode {
+                    x,
+                    y,
+                    size,
+                    qr_code,
+                } => {
+                    let width = qr_code.width();
+                    let module = size as usize / width;
+                    let path: String = qr_code
+                        .to_colors()
+                        .iter()
+                        .enumerate()
+                        .filter(|(_, color)| **color == qrcode::Color::Dark)
+                        .map(|(idx, _)| {
+                            format!(
+                                "M{},{}h{module}v{module}h-{module}z",
+                                x as usize + idx % width * module,
+                                y as usize + idx / width * module
+                            )
+                        })
+                        .collect();
+                    svg.push_str(&format!("<path d=\"{path}\" fill=\"#000\"/>\n"));
+                }
+            }
+        }
+        svg.push_str("</svg>\n");
+        Ok(svg)
+    }
+
+    /// Save the paper wallet, as SVG if the extension is `svg`, otherwise as PNG
+    pub fn save(&self, path: &Path, args: &AppArgs) -> SolwalrsResult<()> {
+        let extension = path
+            .extension()
+            .map(|extension| extension.to_string_lossy().to_lowercase());
+        crate::info!(
+            args,
+            "Saving the paper wallet of `{}` to {path:?}",
+            self.name
+        );
+        let result = match extension.as_deref() {
+            Some("svg") => std::fs::write(path, self.to_svg()?).map_err(|err| err.to_string()),
+            Some("png") => self.to_png()?.save(path).map_err(|err| err.to_string()),
+            _ => return Err(SolwalrsError::Other(
+                "The paper wallet can be saved as PNG or SVG, use the `.png` or `.svg` extension"
+                    .to_owned(),
+            )),
+        };
+        result.map_err(|err| {
+            SolwalrsError::IO(format!(
+                "Failed to save the paper wallet to {}: {err}",
+                path.display()
+            ))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::Parser;
+
+    use super::*;
+
+    fn args() -> AppArgs {
+        AppArgs::parse_from(["solwalrs"])
+    }
+
+    #[test]
+    fn encrypted_secret_roundtrip() {
+        let keypair = KeyPair::new("paper", false);
+        let secret = encrypt_paper_secret(&keypair, "correct horse").unwrap();
+        assert!(secret.starts_with(PAPER_SECRET_PREFIX));
+        assert_eq!(
+            *decrypt_paper_secret(&secret, "correct horse").unwrap(),
+            *keypair.keypair_bytes()
+        );
+        assert!(matches!(
+            decrypt_paper_secret(&secret, "wrong horse"),
+            Err(SolwalrsError::InvalidPassword(_))
+        ));
+    }
+
+    #[test]
+    fn page_without_secret() {
+        let mut keypair = KeyPair::new("<alice & bob>", false);
+        keypair.is_default = true;
+        let paper = PaperWallet::new(&keypair, None, &args()).unwrap();
+        let svg = paper.to_svg().unwrap();
+        assert!(svg.contains(&paper.address));
+        assert!(svg.contains("&lt;alice &amp; bob&gt;"));
+        assert!(!svg.contains("Encrypted secret"));
+        assert_eq!(paper.checksum().len(), 8);
+
+        let png = paper.to_png().unwrap();
+        assert_eq!(png.dimensions(), (PAGE_WIDTH, PAGE_HEIGHT));
+        assert!(png.pixels().any(|pixel| pixel.0 == [0]));
+    }
+}
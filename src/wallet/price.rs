@@ -14,15 +14,31 @@
 // You should have received a copy of the GNU General Public License
 // along with this program.  If not, see <https://www.gnu.org/licenses/gpl-3.0.html>.
 
+use std::{fmt, str::FromStr};
+
+use clap::ValueEnum;
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use solana_sdk::pubkey::Pubkey;
 
-use super::{cache::Cache, Tokens};
+use super::{cache::Cache, Rpc, Tokens};
 use crate::{
     app::AppArgs,
     errors::{Error as SolwalrsError, Result as SolwalrsResult},
+    utils,
 };
 
-const PRICE_API: &str = "https://api.solscan.io/market?symbol=";
+const SOLSCAN_API: &str = "https://api.solscan.io/market?symbol=";
+const COINGECKO_API: &str = "https://api.coingecko.com/api/v3/simple";
+const JUPITER_API: &str = "https://lite-api.jup.ag/price/v2?ids=";
+/// The mint of the wrapped SOL, used to get the SOL price from Jupiter
+const WRAPPED_SOL_MINT: &str = "So11111111111111111111111111111111111111112";
+/// The time to keep the prices in the cache, in seconds
+pub const PRICE_TTL: u64 = 5 * 60;
+/// The Pyth price accounts older than this (in seconds) are considered stale
+const PYTH_MAX_AGE: u64 = 5 * 60;
+/// The genesis hash of the mainnet-beta cluster, the Pyth price accounts are on it only
+const MAINNET_GENESIS_HASH: &str = "5eykt4UsFv8P8NJdTREpY1vzqKqZKvdpKuc147dw2N9d";
 
 /// Data that contains the price
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -30,9 +46,9 @@ pub struct Data {
     /// The price
     #[serde(rename = "priceUsdt")]
     pub price: f64,
-    /// The price change in the last 24 hours
-    #[serde(rename = "priceChange24h")]
-    pub price_change_24h: f64,
+    /// The price change in the last 24 hours, `None` if the source doesn't provide it
+    #[serde(rename = "priceChange24h", default)]
+    pub price_change_24h: Option<f64>,
 }
 
 /// Price data
@@ -47,35 +63,457 @@ pub struct Price {
     pub timestamp: u64,
     /// The symbol of the token
     pub symbol: Option<String>,
+    /// The source of the price, `None` for the prices cached before the sources were added
+    #[serde(default)]
+    pub source: Option<PriceProvider>,
+}
+
+/// An asset to get the price of, SOL, a supported token or any token mint
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Asset {
+    /// The native SOL
+    Sol,
+    /// A supported SPL token
+    Token(Tokens),
+    /// An SPL token by its mint
+    Mint(Pubkey),
+}
+
+/// A source of the token/SOL prices in USD
+pub trait PriceSource {
+    /// Fetch the price data of the asset
+    fn fetch(&self, asset: &Asset, args: &AppArgs) -> SolwalrsResult<Data>;
+}
+
+/// The price providers, in the default priority order
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum PriceProvider {
+    /// The solscan market API
+    Solscan,
+    /// The CoinGecko simple price API
+    Coingecko,
+    /// The Jupiter price API
+    Jupiter,
+    /// The Pyth price accounts, read through the RPC endpoints. Mainnet-beta only
+    Pyth,
+}
+
+/// The solscan market API
+pub struct Solscan;
+/// The CoinGecko simple price API
+pub struct CoinGecko;
+/// The Jupiter price API, the prices are in USDC
+pub struct Jupiter;
+/// The Pyth oracle price accounts, they exist on the mainnet-beta cluster only,
+/// so it's skipped when the RPC endpoints are of another cluster
+pub struct Pyth;
+
+impl PriceProvider {
+    /// Returns the source of the provider
+    pub fn source(self) -> &'static dyn PriceSource {
+        match self {
+            Self::Solscan => &Solscan,
+            Self::Coingecko => &CoinGecko,
+            Self::Jupiter => &Jupiter,
+            Self::Pyth => &Pyth,
+        }
+    }
+
+    /// Returns the provider name
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Solscan => "solscan",
+            Self::Coingecko => "coingecko",
+            Self::Jupiter => "jupiter",
+            Self::Pyth => "pyth",
+        }
+    }
+
+    /// Returns the providers to try in order, `--price-sources` (or the profile price sources)
+    /// otherwise all the providers in the default order
+    pub fn priority(args: &AppArgs) -> Vec<Self> {
+        if args.price_sources.is_empty() {
+            Self::value_variants().to_vec()
+        } else {
+            args.price_sources.clone()
+        }
+    }
+}
+
+impl Asset {
+    /// Returns the symbol of the asset, the mint address if it's not a supported token
+    pub fn symbol(&self) -> String {
+        match self {
+            Self::Sol => "SOL".to_owned(),
+            Self::Token(token) => token.name().to_uppercase(),
+            Self::Mint(mint) => mint.to_string(),
+        }
+    }
+
+    /// Returns the mint of the asset, the wrapped SOL mint for SOL
+    pub fn mint(&self) -> Pubkey {
+        match self {
+            // SAFETY: The wrapped SOL mint is valid
+            Self::Sol => WRAPPED_SOL_MINT.parse().unwrap(),
+            Self::Token(token) => token.mint(),
+            Self::Mint(mint) => *mint,
+        }
+    }
+
+    /// Returns the CoinGecko id of the asset, `None` if it's not a supported token
+    pub fn coingecko_id(&self) -> Option<&'static str> {
+        match self {
+            Self::Sol => Some("solana"),
+            Self::Token(Tokens::Usdc) => Some("usd-coin"),
+            Self::Token(Tokens::Usdt) => Some("tether"),
+            Self::Token(Tokens::Srm) => Some("serum"),
+            Self::Mint(_) => None,
+        }
+    }
+
+    /// Returns the error of the sources that don't support the asset
+    fn unsupported(&self, source: PriceProvider) -> SolwalrsError {
+        SolwalrsError::Other(format!(
+            "The {} price source doesn't support `{self}`",
+            source.name()
+        ))
+    }
+}
+
+impl From<Option<&Tokens>> for Asset {
+    fn from(token: Option<&Tokens>) -> Self {
+        token.map_or(Self::Sol, |token| Self::Token(token.clone()))
+    }
+}
+
+impl FromStr for Asset {
+    type Err = SolwalrsError;
+
+    fn from_str(asset: &str) -> SolwalrsResult<Self> {
+        if asset.eq_ignore_ascii_case("sol") {
+            return Ok(Self::Sol);
+        }
+        if let Ok(token) = Tokens::from_str(asset, true) {
+            return Ok(Self::Token(token));
+        }
+        let mint: Pubkey = asset.parse().map_err(|_| {
+            SolwalrsError::Other(format!(
+                "Unknown asset `{asset}`, expected `sol`, a supported token or a token mint"
+            ))
+        })?;
+        Ok(if mint.to_string() == WRAPPED_SOL_MINT {
+            Self::Sol
+        } else {
+            Tokens::from_mint(&mint).map_or(Self::Mint(mint), Self::Token)
+        })
+    }
+}
+
+impl fmt::Display for Asset {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.symbol())
+    }
+}
+
+/// Send a GET request and parse the response as json
+pub fn get_json(url: &str, args: &AppArgs) -> SolwalrsResult<Value> {
+    crate::info!(args, "Sending GET request to `{url}`");
+    let response = reqwest::blocking::get(url)
+        .and_then(|response| response.error_for_status())
+        .map_err(|err| SolwalrsError::RequestError(err.to_string()))?;
+    response
+        .json()
+        .map_err(|err| SolwalrsError::Other(format!("Failed to parse price data: {err}")))
+}
+
+/// Returns the number of the json value, the number may be a string
+fn json_number(value: &Value) -> Option<f64> {
+    value
+        .as_f64()
+        .or_else(|| value.as_str().and_then(|value| value.parse().ok()))
+}
+
+impl PriceSource for Solscan {
+    fn fetch(&self, asset: &Asset, args: &AppArgs) -> SolwalrsResult<Data> {
+        if let Asset::Mint(_) = asset {
+            return Err(asset.unsupported(PriceProvider::Solscan));
+        }
+        let response = get_json(&format!("{SOLSCAN_API}{}", asset.symbol()), args)?;
+        if response["success"].as_bool() != Some(true) {
+            return Err(SolwalrsError::Other(format!(
+                "The solscan request wasn't successful: {response}"
+            )));
+        }
+        serde_json::from_value(response["data"].clone())
+            .map_err(|err| SolwalrsError::Other(format!("Failed to parse price data: {err}")))
+    }
+}
+
+impl PriceSource for CoinGecko {
+    fn fetch(&self, asset: &Asset, args: &AppArgs) -> SolwalrsResult<Data> {
+        let (url, id) = match asset.coingecko_id() {
+            Some(id) => (format!("{COINGECKO_API}/price?ids={id}"), id.to_owned()),
+            None => {
+                let mint = asset.mint();
+                (
+                    format!("{COINGECKO_API}/token_price/solana?contract_addresses={mint}"),
+                    mint.to_string(),
+                )
+            }
+        };
+        let response = get_json(
+            &format!("{url}&vs_currencies=usd&include_24hr_change=true"),
+            args,
+        )?;
+        // The token prices may be keyed by the lowercase mint
+        let data = response
+            .as_object()
+            .and_then(|prices| {
+                prices
+                    .iter()
+                    .find(|(key, _)| key.eq_ignore_ascii_case(&id))
+                    .map(|(_, data)| data)
+            })
+            .ok_or_else(|| {
+                SolwalrsError::Other(format!("There is no CoinGecko price of `{id}`"))
+            })?;
+        Ok(Data {
+            price: json_number(&data["usd"]).ok_or_else(|| {
+                SolwalrsError::Other(format!("There is no CoinGecko price of `{id}`"))
+            })?,
+            price_change_24h: json_number(&data["usd_24h_change"]),
+        })
+    }
+}
+
+impl PriceSource for Jupiter {
+    fn fetch(&self, asset: &Asset, args: &AppArgs) -> SolwalrsResult<Data> {
+        let mint = asset.mint().to_string();
+        let response = get_json(&format!("{JUPITER_API}{mint}"), args)?;
+        Ok(Data {
+            price: json_number(&response["data"][&mint]["price"]).ok_or_else(|| {
+                SolwalrsError::Other(format!("There is no Jupiter price of `{mint}`"))
+            })?,
+            price_change_24h: None,
+        })
+    }
+}
+
+impl Pyth {
+    /// Returns the mainnet-beta Pyth price account of the asset in USD, `None` if there is no price account of it
+    fn price_account(asset: &Asset) -> Option<Pubkey> {
+        let address = match asset {
+            Asset::Sol => "H6ARHf6YXhGYeQfUzQNGk6rDNnLBQKrenN712K4AQJEG",
+            Asset::Token(Tokens::Usdc) => "Gnt27xtC473ZT2Mw5u8wZ68Z3gULkSTb5DuxJy7eJotD",
+            Asset::Token(Tokens::Usdt) => "3vxLXJqLqF3JG5TCbYycbKWRBbCJQLxQmBGCkyqEEefL",
+            Asset::Token(Tokens::Srm) => "3NBReDRTLKMQEKiLD5tGcx4kXbTf88b7f2xLS9UuGjym",
+            Asset::Mint(_) => return None,
+        };
+        // SAFETY: The price accounts are valid
+        Some(address.parse().unwrap())
+    }
+
+    /// Parse the aggregate price of a Pyth price account, returns the price and its publish timestamp.
+    /// Will return an error if it isn't a price account or the price isn't trading
+    fn parse_price_account(data: &[u8]) -> SolwalrsResult<(f64, i64)> {
+        let invalid = || SolwalrsError::Other("Invalid Pyth price account".to_owned());
+        let u32_at = |offset: usize| {
+            data.get(offset..offset + 4)
+                .map(|bytes| u32::from_le_bytes(bytes.try_into().unwrap()))
+                .ok_or_else(invalid)
+        };
+        let i64_at = |offset: usize| {
+            data.get(offset..offset + 8)
+                .map(|bytes| i64::from_le_bytes(bytes.try_into().unwrap()))
+                .ok_or_else(invalid)
+        };
+        // The magic number, the version 2 and the price account type
+        if u32_at(0)? != 0xa1b2c3d4 || u32_at(4)? != 2 || u32_at(8)? != 3 {
+            return Err(invalid());
+        }
+        let exponent = u32_at(20)? as i32;
+        let timestamp = i64_at(96)?;
+        // The aggregate price information, the price, the confidence and the status
+        let price = i64_at(208)?;
+        if u32_at(224)? != 1 {
+            return Err(SolwalrsError::Other(
+                "The Pyth price isn't trading".to_owned(),
+            ));
+        }
+        Ok((price as f64 * 10f64.powi(exponent), timestamp))
+    }
+}
+
+impl PriceSource for Pyth {
+    fn fetch(&self, asset: &Asset, args: &AppArgs) -> SolwalrsResult<Data> {
+        let account =
+            Self::price_account(asset).ok_or_else(|| asset.unsupported(PriceProvider::Pyth))?;
+        let rpc = Rpc::new(args);
+        let genesis_hash = rpc
+            .call(|client| client.get_genesis_hash())
+            .map_err(|err| {
+                SolwalrsError::RpcError(format!(
+                    "Error while getting the cluster genesis hash: {err}"
+                ))
+            })?;
+        if genesis_hash.to_string() != MAINNET_GENESIS_HASH {
+            // Skipped with the reason, it's logged as the failure of this source
+            return Err(SolwalrsError::Other(format!(
+                "The Pyth price accounts are on the mainnet-beta cluster only, \
+                 but the genesis hash of the RPC cluster is `{genesis_hash}`"
+            )));
+        }
+        crate::info!(args, "Reading the Pyth price account `{account}`");
+        let data = rpc
+            .call(|client| client.get_account_data(&account))
+            .map_err(|err| {
+                SolwalrsError::RpcError(format!(
+                    "Error while getting the Pyth price account `{account}`: {err}"
+                ))
+            })?;
+        let (price, timestamp) = Self::parse_price_account(&data)?;
+        if (timestamp.max(0) as u64) + PYTH_MAX_AGE < utils::get_timestamp() {
+            return Err(SolwalrsError::Other(format!(
+                "The Pyth price account `{account}` is stale"
+            )));
+        }
+        Ok(Data {
+            price,
+            price_change_24h: None,
+        })
+    }
 }
 
 impl Price {
-    /// Send request to get the price of a token/SOL in USDT
-    pub fn send_request(token: Option<&Tokens>, args: &AppArgs) -> SolwalrsResult<Self> {
+    /// Send request to get the price of the asset in USD, the price sources are tried in the priority order
+    /// until one of them succeeds
+    pub fn send_request(asset: &Asset, args: &AppArgs) -> SolwalrsResult<Self> {
         crate::info!(args, "Sending request to get price data");
-        let symbol = token.map(Tokens::name).unwrap_or("sol").to_uppercase();
-        // Send a GET request to the price API, and parse the response
-        let response = reqwest::blocking::get(format!("{PRICE_API}{symbol}",))
-            .map_err(|e| SolwalrsError::RequestError(e.to_string()))?;
-        crate::info!(args, "Got price data {response:?}");
-        let mut price = response
-            .json::<Self>()
-            .map_err(|e| SolwalrsError::Other(format!("Failed to parse price data: {e}")))?;
-        price.symbol = Some(symbol);
-        crate::info!(args, "Parsed price data {price:?}");
-        Ok(price)
-    }
-
-    /// Returns the price, pass `None` to `token` to get SOL price.
-    /// If the lsat price data is less than 15 minutes old, it will be used instead of sending a new request.
-    pub fn get_price(
-        token: Option<&Tokens>,
+        let symbol = asset.symbol();
+        let mut errors = Vec::new();
+        for provider in PriceProvider::priority(args) {
+            crate::info!(
+                args,
+                "Getting the price of {symbol} from {}",
+                provider.name()
+            );
+            match provider.source().fetch(asset, args) {
+                Ok(data) => {
+                    let price = Self {
+                        success: true,
+                        data,
+                        timestamp: utils::get_timestamp(),
+                        symbol: Some(symbol),
+                        source: Some(provider),
+                    };
+                    crate::info!(args, "Got price data {price:?}");
+                    return Ok(price);
+                }
+                Err(err) => {
+                    crate::warn!(
+                        args,
+                        "Failed to get the price from {}: {err}",
+                        provider.name()
+                    );
+                    errors.push(format!("{}: {err}", provider.name()));
+                }
+            }
+        }
+        Err(SolwalrsError::RequestError(format!(
+            "Failed to get the price of {symbol} from all the sources, {}",
+            errors.join(", ")
+        )))
+    }
+
+    /// Returns the price of the asset.
+    /// If the lsat price data is less than 5 minutes old, it will be used instead of sending a new request.
+    pub fn get_price(asset: &Asset, args: &AppArgs, cache: &mut Cache) -> SolwalrsResult<Self> {
+        Self::get_fresh_price(asset, PRICE_TTL, args, cache)
+    }
+
+    /// Returns the price of the asset, the cached price is used if it's less than `max_age` seconds old
+    pub fn get_fresh_price(
+        asset: &Asset,
+        max_age: u64,
         args: &AppArgs,
         cache: &mut Cache,
     ) -> SolwalrsResult<Self> {
-        cache
-            .get_price(token, args)
-            .map(|p| Ok(p.clone()))
-            .unwrap_or_else(|| Ok(cache.add_price(Self::send_request(token, args)?).clone()))
+        match cache
+            .get_price(asset, args)
+            .filter(|price| price.timestamp + max_age > utils::get_timestamp())
+        {
+            Some(price) => Ok(price.clone()),
+            None => Ok(cache.add_price(Self::send_request(asset, args)?).clone()),
+        }
+    }
+
+    /// Returns the age of the price in seconds
+    pub fn age(&self) -> u64 {
+        utils::get_timestamp().saturating_sub(self.timestamp)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns a Pyth price account with the given exponent, price, status and timestamp
+    fn price_account(exponent: i32, price: i64, status: u32, timestamp: i64) -> Vec<u8> {
+        let mut data = vec![0; 3312];
+        data[0..4].copy_from_slice(&0xa1b2c3d4u32.to_le_bytes());
+        data[4..8].copy_from_slice(&2u32.to_le_bytes());
+        data[8..12].copy_from_slice(&3u32.to_le_bytes());
+        data[20..24].copy_from_slice(&exponent.to_le_bytes());
+        data[96..104].copy_from_slice(&timestamp.to_le_bytes());
+        data[208..216].copy_from_slice(&price.to_le_bytes());
+        data[224..228].copy_from_slice(&status.to_le_bytes());
+        data
+    }
+
+    #[test]
+    fn parse_pyth_price_account() {
+        let (price, timestamp) =
+            Pyth::parse_price_account(&price_account(-8, 2_345_678_900, 1, 1_700_000_000)).unwrap();
+        assert!((price - 23.456789).abs() < 1e-9);
+        assert_eq!(timestamp, 1_700_000_000);
+
+        // Not trading
+        assert!(Pyth::parse_price_account(&price_account(-8, 100, 2, 0)).is_err());
+        // Not a price account
+        let mut data = price_account(-8, 100, 1, 0);
+        data[8..12].copy_from_slice(&2u32.to_le_bytes());
+        assert!(Pyth::parse_price_account(&data).is_err());
+        assert!(Pyth::parse_price_account(&data[..100]).is_err());
+    }
+
+    #[test]
+    fn parse_asset() {
+        assert_eq!("SOL".parse::<Asset>().unwrap(), Asset::Sol);
+        assert_eq!(WRAPPED_SOL_MINT.parse::<Asset>().unwrap(), Asset::Sol);
+        assert_eq!("usdc".parse::<Asset>().unwrap(), Asset::Token(Tokens::Usdc));
+        assert_eq!(
+            "SRMuApVNdxXokk5GT7XD5cUUgXMBCoAz2LHeuAoKWRt"
+                .parse::<Asset>()
+                .unwrap(),
+            Asset::Token(Tokens::Srm)
+        );
+        let mint: Pubkey = "DezXAZ8z7PnrnRJjz3wXBoRgixCa6xjnB7YaB1pPB263"
+            .parse()
+            .unwrap();
+        let asset: Asset = mint.to_string().parse().unwrap();
+        assert_eq!(asset, Asset::Mint(mint));
+        assert_eq!(asset.symbol(), mint.to_string());
+        assert!("bonk".parse::<Asset>().is_err());
+    }
+
+    #[test]
+    fn cached_price_without_source() {
+        let price: Price = serde_json::from_str(
+            r#"{"success":true,"data":{"priceUsdt":1.5,"priceChange24h":-2.0},"timestamp":1,"symbol":"SOL"}"#,
+        )
+        .unwrap();
+        assert_eq!(price.source, None);
+        assert_eq!(price.data.price_change_24h, Some(-2.0));
     }
 }
@@ -0,0 +1,372 @@
+// Solwalrs, A simple and easy to use CLI Solana wallet
+// Copyright (C) 2022  Solwalrs contributors <https://github.com/TheAwiteb/solwalrs/graphs/contributors>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/gpl-3.0.html>.
+
+use std::{fs, path::PathBuf};
+
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+
+use super::{get_json, utils::app_cache_dir, Asset, Currency};
+use crate::{
+    app::AppArgs,
+    errors::{Error as SolwalrsError, Result as SolwalrsResult},
+    utils,
+};
+
+const COINGECKO_COINS_API: &str = "https://api.coingecko.com/api/v3/coins";
+
+/// The time range of a price history
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ValueEnum)]
+pub enum HistoryRange {
+    /// The last 24 hours, a price every 5 minutes
+    #[value(name = "24h")]
+    #[serde(rename = "24h")]
+    Day,
+    /// The last 7 days, a price every hour
+    #[value(name = "7d")]
+    #[serde(rename = "7d")]
+    Week,
+    /// The last 30 days, a price every hour
+    #[value(name = "30d")]
+    #[serde(rename = "30d")]
+    Month,
+    /// The last year, a price every day
+    #[value(name = "1y")]
+    #[serde(rename = "1y")]
+    Year,
+}
+
+/// A price of the history
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct PricePoint {
+    /// The time of the price (in seconds since the Unix epoch)
+    pub timestamp: i64,
+    /// The price in the currency of the history
+    pub price: f64,
+}
+
+/// The price history of an asset, cached in the app cache directory
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PriceHistory {
+    /// The symbol of the asset
+    pub symbol: String,
+    /// The time range of the history
+    pub range: HistoryRange,
+    /// The currency of the prices
+    pub currency: Currency,
+    /// The prices, oldest first
+    pub prices: Vec<PricePoint>,
+    /// The timestamp of the request (in seconds since the Unix epoch)
+    pub timestamp: u64,
+}
+
+impl HistoryRange {
+    /// Returns the range name, e.g. `7d`
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Day => "24h",
+            Self::Week => "7d",
+            Self::Month => "30d",
+            Self::Year => "1y",
+        }
+    }
+
+    /// Returns the description of the range, e.g. `7 days`
+    pub fn description(self) -> &'static str {
+        match self {
+            Self::Day => "24 hours",
+            Self::Week => "7 days",
+            Self::Month => "30 days",
+            Self::Year => "year",
+        }
+    }
+
+    /// Returns the number of the days of the range
+    pub fn days(self) -> u64 {
+        match self {
+            Self::Day => 1,
+            Self::Week => 7,
+            Self::Month => 30,
+            Self::Year => 365,
+        }
+    }
+
+    /// Returns the time to keep the history in the cache, in seconds, longer ranges change slower
+    pub fn ttl(self) -> u64 {
+        match self {
+            Self::Day => 5 * 60,
+            Self::Week | Self::Month => 60 * 60,
+            Self::Year => 24 * 60 * 60,
+        }
+    }
+
+    /// Returns the shortest range that contains the given age (in seconds), `None` if it's older than a year
+    pub fn containing(age: u64) -> Option<Self> {
+        Self::value_variants()
+            .iter()
+            .copied()
+            .find(|range| age <= range.days() * 24 * 60 * 60)
+    }
+}
+
+impl PriceHistory {
+    /// Returns the cache file of the asset history in the given range and currency
+    fn cache_file(
+        asset: &Asset,
+        range: HistoryRange,
+        currency: &Currency,
+    ) -> SolwalrsResult<PathBuf> {
+        let history_dir = app_cache_dir()?.join("price-history");
+        fs::create_dir_all(&history_dir).map_err(|err| {
+            SolwalrsError::AppDataDir(format!(
+                "Failed to create the price history directory: {err}"
+            ))
+        })?;
+        Ok(history_dir.join(format!(
+            "{}-{}-{}.json",
+            asset.symbol(),
+            range.name(),
+            currency.code()
+        )))
+    }
+
+    /// Load the cached history, `None` if it's not cached or it can't be read
+    fn load(
+        asset: &Asset,
+        range: HistoryRange,
+        currency: &Currency,
+        args: &AppArgs,
+    ) -> Option<Self> {
+        let cache_file = Self::cache_file(asset, range, currency).ok()?;
+        let history = fs::read(&cache_file)
+            .ok()
+            .and_then(|content| Self::parse(&content));
+        crate::info_or_warn!(args, history, "Loaded the price history from `{}`", cache_file.display(); "There is no cached {currency} price history of {asset} in {}", range.name());
+        history
+    }
+
+    /// Parse the cached history, a history without prices is rejected so it's requested again
+    fn parse(content: &[u8]) -> Option<Self> {
+        serde_json::from_slice::<Self>(content)
+            .ok()
+            .filter(|history| !history.prices.is_empty())
+    }
+
+    /// Save the history to the cache
+    fn save(&self, asset: &Asset, args: &AppArgs) -> SolwalrsResult<()> {
+        let cache_file = Self::cache_file(asset, self.range, &self.currency)?;
+        crate::info!(
+            args,
+            "Saving the price history to `{}`",
+            cache_file.display()
+        );
+        let content = serde_json::to_vec(self)
+            .map_err(|err| SolwalrsError::IO(format!("Failed to save the price history: {err}")))?;
+        fs::write(&cache_file, content)
+            .map_err(|err| SolwalrsError::IO(format!("Failed to save the price history: {err}")))
+    }
+
+    /// Fetch the close prices of the asset in the currency from CoinGecko
+    pub fn send_request(
+        asset: &Asset,
+        range: HistoryRange,
+        currency: &Currency,
+        args: &AppArgs,
+    ) -> SolwalrsResult<Self> {
+        crate::info!(
+            args,
+            "Sending request to get the {currency} price history of {asset} in {}",
+            range.name()
+        );
+        let coin = match asset.coingecko_id() {
+            Some(id) => id.to_owned(),
+            None => format!("solana/contract/{}", asset.mint()),
+        };
+        let response = get_json(
+            &format!(
+                "{COINGECKO_COINS_API}/{coin}/market_chart?vs_currency={}&days={}",
+                currency.code().to_lowercase(),
+                range.days()
+            ),
+            args,
+        )?;
+        let prices = response["prices"]
+            .as_array()
+            .ok_or_else(|| {
+                SolwalrsError::Other(format!(
+                    "There is no {currency} price history of `{asset}`: {response}"
+                ))
+            })?
+            .iter()
+            .filter_map(|point| {
+                Some(PricePoint {
+                    // The timestamps are in milliseconds
+                    timestamp: point[0].as_i64()? / 1000,
+                    price: point[1].as_f64()?,
+                })
+            })
+            .collect::<Vec<_>>();
+        if prices.is_empty() {
+            return Err(SolwalrsError::Other(format!(
+                "There is no price history of `{asset}`"
+            )));
+        }
+        Ok(Self {
+            symbol: asset.symbol(),
+            range,
+            currency: currency.clone(),
+            prices,
+            timestamp: utils::get_timestamp(),
+        })
+    }
+
+    /// Returns the price history of the asset in the given range and currency.
+    /// The cached history is used if it's fresh, or if the request fails
+    pub fn get(
+        asset: &Asset,
+        range: HistoryRange,
+        currency: &Currency,
+        args: &AppArgs,
+    ) -> SolwalrsResult<Self> {
+        let cached = Self::load(asset, range, currency, args);
+        if let Some(history) = cached
+            .as_ref()
+            .filter(|history| history.timestamp + range.ttl() > utils::get_timestamp())
+        {
+            return Ok(history.clone());
+        }
+        match Self::send_request(asset, range, currency, args) {
+            Ok(history) => {
+                history.save(asset, args)?;
+                Ok(history)
+            }
+            Err(err) => match cached {
+                Some(history) => {
+                    crate::warn!(
+                        args,
+                        "Using the stale price history, the request failed: {err}"
+                    );
+                    Ok(history)
+                }
+                None => Err(err),
+            },
+        }
+    }
+
+    /// Returns the price of the asset in the currency at the given time, from the shortest range that contains it.
+    /// Returns `None` if it's older than a year, useful to value the past transactions
+    pub fn price_at(
+        asset: &Asset,
+        timestamp: i64,
+        currency: &Currency,
+        args: &AppArgs,
+    ) -> SolwalrsResult<Option<f64>> {
+        let age = (utils::get_timestamp() as i64)
+            .saturating_sub(timestamp)
+            .max(0) as u64;
+        match HistoryRange::containing(age) {
+            Some(range) => Ok(Self::get(asset, range, currency, args)?.nearest(timestamp)),
+            None => Ok(None),
+        }
+    }
+
+    /// Returns the price nearest to the given time, `None` if the time is out of the history
+    pub fn nearest(&self, timestamp: i64) -> Option<f64> {
+        let first = self.prices.first()?;
+        let last = self.prices.last()?;
+        // Allow a step of the history before the first price and after the last one
+        let step = (last.timestamp - first.timestamp) / (self.prices.len() as i64).max(2);
+        if timestamp < first.timestamp - step || timestamp > last.timestamp + step {
+            return None;
+        }
+        self.prices
+            .iter()
+            .min_by_key(|point| (point.timestamp - timestamp).abs())
+            .map(|point| point.price)
+    }
+
+    /// Returns the lowest and the highest prices
+    pub fn bounds(&self) -> (f64, f64) {
+        self.prices
+            .iter()
+            .fold((f64::INFINITY, f64::NEG_INFINITY), |(low, high), point| {
+                (low.min(point.price), high.max(point.price))
+            })
+    }
+
+    /// Returns the price change from the first price to the last one, in percent
+    pub fn change(&self) -> Option<f64> {
+        let first = self.prices.first()?.price;
+        let last = self.prices.last()?.price;
+        (first != 0.0).then(|| (last - first) / first * 100.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn history() -> PriceHistory {
+        PriceHistory {
+            symbol: "SOL".to_owned(),
+            range: HistoryRange::Day,
+            currency: Currency::default(),
+            prices: (0..5)
+                .map(|idx| PricePoint {
+                    timestamp: 1_000 + idx * 300,
+                    price: [10.0, 12.0, 9.0, 11.0, 15.0][idx as usize],
+                })
+                .collect(),
+            timestamp: 2_000,
+        }
+    }
+
+    #[test]
+    fn nearest_price() {
+        let history = history();
+        assert_eq!(history.nearest(1_000), Some(10.0));
+        assert_eq!(history.nearest(1_400), Some(12.0));
+        assert_eq!(history.nearest(2_250), Some(15.0));
+        assert_eq!(history.nearest(2_500), None);
+        assert_eq!(history.nearest(700), None);
+        assert_eq!(history.bounds(), (9.0, 15.0));
+        assert_eq!(history.change(), Some(50.0));
+    }
+
+    #[test]
+    fn history_ranges() {
+        assert_eq!(HistoryRange::containing(3_600), Some(HistoryRange::Day));
+        assert_eq!(
+            HistoryRange::containing(3 * 86_400),
+            Some(HistoryRange::Week)
+        );
+        assert_eq!(
+            HistoryRange::containing(200 * 86_400),
+            Some(HistoryRange::Year)
+        );
+        assert_eq!(HistoryRange::containing(400 * 86_400), None);
+        let json = serde_json::to_string(&history()).unwrap();
+        assert!(json.contains(r#""range":"24h""#));
+        assert!(json.contains(r#""currency":"USD""#));
+        assert_eq!(PriceHistory::parse(json.as_bytes()), Some(history()));
+        let empty = PriceHistory {
+            prices: Vec::new(),
+            ..history()
+        };
+        let json = serde_json::to_string(&empty).unwrap();
+        assert_eq!(PriceHistory::parse(json.as_bytes()), None);
+    }
+}
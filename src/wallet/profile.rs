@@ -0,0 +1,222 @@
+// Solwalrs, A simple and easy to use CLI Solana wallet
+// Copyright (C) 2022  Solwalrs contributors <https://github.com/TheAwiteb/solwalrs/graphs/contributors>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/gpl-3.0.html>.
+
+use std::{fs::File, io::BufReader, path::PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use super::{
+    utils::{app_data_dir, write_atomically},
+    Currency, PriceProvider,
+};
+use crate::{
+    app::AppArgs,
+    errors::{Error as SolwalrsError, Result as SolwalrsResult},
+};
+
+/// The name of the default profile, its wallet file is the `solwalrs.json` file
+pub const DEFAULT_PROFILE: &str = "default";
+/// The default RPC URL, used if the profile doesn't have a cluster
+pub const DEFAULT_RPC: &str = "https://api.mainnet-beta.solana.com";
+
+/// A wallet profile, each profile has its own wallet file (and password) and cluster
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Profile {
+    /// The name of the profile
+    pub name: String,
+    /// The RPC endpoints of the profile
+    pub cluster: Vec<url::Url>,
+    /// The price sources of the profile in priority order, empty for the default order
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub price_sources: Vec<PriceProvider>,
+    /// The fiat currency of the profile, `None` for USD
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub currency: Option<Currency>,
+}
+
+/// The profiles file structure
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Profiles {
+    /// The name of the active profile, `None` for the default profile
+    pub active: Option<String>,
+    /// The profiles
+    #[serde(default)]
+    pub profiles: Vec<Profile>,
+}
+
+/// Returns the profiles file path
+pub fn profiles_file_path() -> SolwalrsResult<PathBuf> {
+    app_data_dir().map(|data| data.join("profiles.json"))
+}
+
+/// Returns the wallet file path of the given profile
+pub fn profile_file_path(name: &str) -> SolwalrsResult<PathBuf> {
+    if name == DEFAULT_PROFILE {
+        return app_data_dir().map(|data| data.join("solwalrs.json"));
+    }
+    let wallets_dir = app_data_dir()?.join("wallets");
+    std::fs::create_dir_all(&wallets_dir).map_err(|err| {
+        SolwalrsError::AppDataDir(format!("Failed to create the wallets directory: {err}"))
+    })?;
+    Ok(wallets_dir.join(format!("{name}.json")))
+}
+
+/// Returns the default cluster, the mainnet-beta endpoint
+pub fn default_cluster() -> Vec<url::Url> {
+    // SAFETY: The default RPC URL is valid
+    vec![DEFAULT_RPC.parse().unwrap()]
+}
+
+impl Profile {
+    /// Create a new profile, will return `Error::InvalidProfileName` if the name is invalid
+    pub fn new(name: &str, cluster: Vec<url::Url>) -> SolwalrsResult<Self> {
+        if name.is_empty()
+            || !name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+        {
+            return Err(SolwalrsError::InvalidProfileName(name.to_owned()));
+        }
+        Ok(Self {
+            name: name.to_owned(),
+            cluster,
+            price_sources: Vec::new(),
+            currency: None,
+        })
+    }
+}
+
+impl Profiles {
+    /// Load the profiles from the profiles file, or create a new one if it doesn't exist
+    pub fn load(args: &AppArgs) -> SolwalrsResult<Self> {
+        let profiles_file = profiles_file_path()?;
+        if !profiles_file.exists() {
+            crate::info!(args, "There is no profiles file, using the default profile");
+            return Ok(Self::default());
+        }
+        crate::info!(
+            args,
+            "Loading the profiles from `{}`",
+            profiles_file.display()
+        );
+        let file = File::open(&profiles_file)
+            .map_err(|err| SolwalrsError::IO(format!("Failed to open profiles file: {err}")))?;
+        serde_json::from_reader(BufReader::new(file))
+            .map_err(|err| SolwalrsError::IO(format!("Failed to load profiles file: {err}")))
+    }
+
+    /// Save the profiles to the profiles file
+    pub fn save(&self, args: &AppArgs) -> SolwalrsResult<()> {
+        crate::info!(args, "Saving the profiles {self:?}");
+        let contents = serde_json::to_vec_pretty(self)
+            .map_err(|err| SolwalrsError::IO(format!("Failed to save profiles file: {err}")))?;
+        // A crash while writing shouldn't leave the profiles file empty
+        write_atomically(&profiles_file_path()?, &contents)
+    }
+
+    /// Returns the name of the active profile
+    pub fn active_name(&self) -> &str {
+        self.active.as_deref().unwrap_or(DEFAULT_PROFILE)
+    }
+
+    /// Get a profile by its name, the default profile always exists.
+    /// Will return `Error::ProfileNotFound` if the profile doesn't exist
+    pub fn get(&self, name: &str) -> SolwalrsResult<Profile> {
+        self.profiles
+            .iter()
+            .find(|profile| profile.name == name)
+            .cloned()
+            .or_else(|| {
+                (name == DEFAULT_PROFILE).then(|| Profile {
+                    name: DEFAULT_PROFILE.to_owned(),
+                    cluster: default_cluster(),
+                    price_sources: Vec::new(),
+                    currency: None,
+                })
+            })
+            .ok_or_else(|| SolwalrsError::ProfileNotFound(name.to_owned()))
+    }
+
+    /// Add a new profile, will return `Error::DuplicateProfile` if the profile already exists
+    pub fn add(&mut self, profile: Profile) -> SolwalrsResult<()> {
+        if self.get(&profile.name).is_ok() {
+            return Err(SolwalrsError::DuplicateProfile(profile.name));
+        }
+        self.profiles.push(profile);
+        Ok(())
+    }
+
+    /// Replace the profile with the given one, the default profile is added if it isn't stored yet.
+    /// Will return `Error::ProfileNotFound` if the profile doesn't exist
+    pub fn update(&mut self, profile: Profile) -> SolwalrsResult<()> {
+        match self.profiles.iter_mut().find(|p| p.name == profile.name) {
+            Some(stored) => *stored = profile,
+            None if profile.name == DEFAULT_PROFILE => self.profiles.push(profile),
+            None => return Err(SolwalrsError::ProfileNotFound(profile.name)),
+        }
+        Ok(())
+    }
+
+    /// Remove a profile, the active profile will be the default profile if it's removed.
+    /// Will return `Error::ProfileNotFound` if the profile doesn't exist
+    pub fn remove(&mut self, name: &str) -> SolwalrsResult<Profile> {
+        let index = self
+            .profiles
+            .iter()
+            .position(|profile| profile.name == name)
+            .ok_or_else(|| SolwalrsError::ProfileNotFound(name.to_owned()))?;
+        if self.active.as_deref() == Some(name) {
+            self.active = None;
+        }
+        Ok(self.profiles.remove(index))
+    }
+
+    /// Set the active profile, will return `Error::ProfileNotFound` if the profile doesn't exist
+    pub fn set_active(&mut self, name: &str) -> SolwalrsResult<()> {
+        self.get(name)?;
+        self.active = (name != DEFAULT_PROFILE).then(|| name.to_owned());
+        Ok(())
+    }
+
+    /// Apply the selected profile (`--wallet` or the active profile) to the app args,
+    /// sets the app file to the profile wallet file, the RPC endpoints to the profile
+    /// cluster if the `--rpc` flag is not set, and the price sources and the currency if they're not set.
+    ///
+    /// If `--app-file` is set, the profile will be ignored.
+    pub fn apply(&self, args: &mut AppArgs) -> SolwalrsResult<()> {
+        if args.app_file.is_some() {
+            crate::info!(args, "The `--app-file` is set, ignoring the profiles");
+            if args.rpc.is_empty() {
+                args.rpc = default_cluster();
+            }
+            return Ok(());
+        }
+        let profile = self.get(args.wallet.as_deref().unwrap_or(self.active_name()))?;
+        crate::info!(args, "The selected profile is {profile:?}");
+        args.app_file = Some(profile_file_path(&profile.name)?.display().to_string());
+        if args.rpc.is_empty() {
+            args.rpc = profile.cluster;
+        }
+        if args.price_sources.is_empty() {
+            args.price_sources = profile.price_sources;
+        }
+        if args.currency.is_none() {
+            args.currency = profile.currency;
+        }
+        args.profile = Some(profile.name);
+        Ok(())
+    }
+}
@@ -0,0 +1,203 @@
+// Solwalrs, A simple and easy to use CLI Solana wallet
+// Copyright (C) 2022  Solwalrs contributors <https://github.com/TheAwiteb/solwalrs/graphs/contributors>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/gpl-3.0.html>.
+
+use std::{sync::OnceLock, time::Duration};
+
+use solana_client::{
+    client_error::{ClientError, ClientErrorKind, Result as ClientResult},
+    rpc_client::RpcClient,
+    rpc_request::RpcError,
+};
+
+use crate::app::AppArgs;
+
+/// The timeout of each RPC request
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+/// The maximum number of slots an endpoint can be behind the others before it's considered lagging
+const MAX_SLOT_LAG: u64 = 150;
+/// The `Node is unhealthy` RPC error code
+const NODE_UNHEALTHY_CODE: i64 = -32005;
+
+/// The RPC endpoints ordered by their health, checked once per run
+static ORDERED_ENDPOINTS: OnceLock<Vec<String>> = OnceLock::new();
+
+/// RPC client with multiple endpoints, will fail over to the next endpoint
+/// if the current one is rate-limited, down or timed out.
+pub struct Rpc<'a> {
+    /// The RPC clients, ordered by their health
+    clients: Vec<RpcClient>,
+    /// The app args, used for logging
+    args: &'a AppArgs,
+}
+
+/// Returns the RPC endpoints, the URLs of the `--rpc` flags
+pub fn rpc_endpoints(args: &AppArgs) -> Vec<String> {
+    args.rpc
+        .iter()
+        .map(|url| url.to_string().trim_end_matches('/').to_owned())
+        .collect()
+}
+
+/// Order the endpoints by their health, the healthy and non-lagging endpoints come first
+fn order_endpoints(endpoints: Vec<String>, args: &AppArgs) -> Vec<String> {
+    if endpoints.len() < 2 {
+        return endpoints;
+    }
+    crate::info!(
+        args,
+        "Checking the health of the RPC endpoints {endpoints:?}"
+    );
+    let slots: Vec<_> = endpoints
+        .iter()
+        .map(|endpoint| {
+            let client = RpcClient::new_with_timeout(endpoint, REQUEST_TIMEOUT);
+            let slot = client.get_health().and_then(|_| client.get_slot());
+            crate::info_or_warn!(args, slot, "`{endpoint}` is healthy, its slot is {slot:?}"; "`{endpoint}` is unhealthy: {slot:?}");
+            slot.ok()
+        })
+        .collect();
+    let ordered = rank_endpoints(endpoints, slots);
+    crate::info!(
+        args,
+        "The RPC endpoints ordered by their health {ordered:?}"
+    );
+    ordered
+}
+
+/// Order the endpoints by their slots, `None` if the endpoint is unhealthy. The non-lagging endpoints
+/// come first, then the lagging ones, then the unhealthy ones
+fn rank_endpoints(endpoints: Vec<String>, slots: Vec<Option<u64>>) -> Vec<String> {
+    let max_slot = slots.iter().flatten().max().copied().unwrap_or_default();
+    let mut ordered: Vec<_> = endpoints.into_iter().zip(slots).collect();
+    // Stable sort, so the order of the `--rpc` flags is kept for the endpoints of the same rank
+    ordered.sort_by_key(|(_, slot)| match slot {
+        Some(slot) if slot + MAX_SLOT_LAG >= max_slot => 0,
+        Some(_) => 1,
+        None => 2,
+    });
+    ordered.into_iter().map(|(endpoint, _)| endpoint).collect()
+}
+
+/// Whether the error is caused by the endpoint (rate-limited, server error, timeout or unhealthy),
+/// so the request should be retried on the next endpoint
+fn is_endpoint_error(err: &ClientError) -> bool {
+    match err.kind() {
+        ClientErrorKind::Reqwest(err) => {
+            err.is_timeout()
+                || err.is_connect()
+                || err
+                    .status()
+                    .is_some_and(|status| status.as_u16() == 429 || status.is_server_error())
+        }
+        ClientErrorKind::Io(_) => true,
+        ClientErrorKind::RpcError(RpcError::RpcResponseError { code, .. }) => {
+            *code == NODE_UNHEALTHY_CODE
+        }
+        _ => false,
+    }
+}
+
+impl<'a> Rpc<'a> {
+    /// Create a new RPC client with the `--rpc` endpoints
+    pub fn new(args: &'a AppArgs) -> Self {
+        let endpoints =
+            ORDERED_ENDPOINTS.get_or_init(|| order_endpoints(rpc_endpoints(args), args));
+        Self {
+            clients: endpoints
+                .iter()
+                .map(|endpoint| RpcClient::new_with_timeout(endpoint, REQUEST_TIMEOUT))
+                .collect(),
+            args,
+        }
+    }
+
+    /// Call the given function with the RPC client of the endpoints, one by one,
+    /// until it succeeds or fails with an error that isn't caused by the endpoint.
+    pub fn call<T>(&self, f: impl Fn(&RpcClient) -> ClientResult<T>) -> ClientResult<T> {
+        let mut last_error = None;
+        for client in &self.clients {
+            match f(client) {
+                Ok(result) => {
+                    crate::info!(self.args, "The RPC call served by `{}`", client.url());
+                    return Ok(result);
+                }
+                Err(err) if is_endpoint_error(&err) => {
+                    crate::warn!(
+                        self.args,
+                        "The RPC call failed on `{}`, trying the next endpoint: {err}",
+                        client.url()
+                    );
+                    last_error = Some(err);
+                }
+                Err(err) => {
+                    crate::info!(
+                        self.args,
+                        "The RPC call failed on `{}`: {err}",
+                        client.url()
+                    );
+                    return Err(err);
+                }
+            }
+        }
+        Err(last_error
+            .unwrap_or_else(|| ClientErrorKind::Custom("No RPC endpoints".to_owned()).into()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use solana_client::rpc_request::RpcResponseErrorData;
+
+    use super::*;
+
+    #[test]
+    fn endpoints_ranking() {
+        let endpoints: Vec<_> = ["a", "b", "c", "d", "e"].map(str::to_owned).into();
+        let ranked = rank_endpoints(
+            endpoints,
+            vec![
+                None,
+                Some(1_000),
+                Some(1_000 - MAX_SLOT_LAG - 1),
+                Some(1_000 - MAX_SLOT_LAG),
+                None,
+            ],
+        );
+        assert_eq!(ranked, ["b", "d", "c", "a", "e"]);
+        let ranked = rank_endpoints(vec!["a".to_owned(), "b".to_owned()], vec![None, None]);
+        assert_eq!(ranked, ["a", "b"]);
+    }
+
+    #[test]
+    fn endpoint_errors() {
+        let response_error = |code| {
+            ClientError::from(RpcError::RpcResponseError {
+                code,
+                message: String::new(),
+                data: RpcResponseErrorData::Empty,
+            })
+        };
+        assert!(is_endpoint_error(&response_error(NODE_UNHEALTHY_CODE)));
+        // e.g. an invalid transaction, the other endpoints will fail the same
+        assert!(!is_endpoint_error(&response_error(-32602)));
+        assert!(is_endpoint_error(&ClientError::from(std::io::Error::from(
+            std::io::ErrorKind::ConnectionReset
+        ))));
+        assert!(!is_endpoint_error(&ClientError::from(
+            ClientErrorKind::Custom("Invalid response".to_owned())
+        )));
+    }
+}
@@ -0,0 +1,672 @@
+// Solwalrs, A simple and easy to use CLI Solana wallet
+// Copyright (C) 2022  Solwalrs contributors <https://github.com/TheAwiteb/solwalrs/graphs/contributors>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/gpl-3.0.html>.
+
+use std::{
+    collections::HashMap,
+    str::FromStr,
+    time::{Duration, Instant},
+};
+
+use clap::ValueEnum;
+use serde::Deserialize;
+use solana_account_decoder::UiAccountEncoding;
+use solana_client::{
+    rpc_config::{
+        RpcSendTransactionConfig, RpcSimulateTransactionAccountsConfig,
+        RpcSimulateTransactionConfig,
+    },
+    rpc_request::RpcRequest,
+};
+use solana_sdk::{
+    account::Account,
+    commitment_config::CommitmentConfig,
+    compute_budget::{self, ComputeBudgetInstruction},
+    instruction::Instruction,
+    native_token::lamports_to_sol,
+    program_pack::Pack,
+    pubkey::Pubkey,
+    sanitize::Sanitize,
+    signature::{Keypair, Signature},
+    transaction::{Transaction, TransactionError},
+};
+
+use super::{KeyPair, Rpc, Wallet};
+use crate::{
+    app::AppArgs,
+    errors::{Error as SolwalrsError, Result as SolwalrsResult},
+    utils,
+};
+
+/// The default compute unit limit of each instruction, used when `--compute-units` isn't set
+const DEFAULT_INSTRUCTION_COMPUTE_UNITS: u64 = 200_000;
+/// The maximum compute units of a transaction, the runtime caps the compute unit limit to it
+const MAX_TRANSACTION_COMPUTE_UNITS: u64 = 1_400_000;
+
+/// The interval between checking the transaction status and rebroadcasting it
+const CONFIRMATION_INTERVAL: Duration = Duration::from_secs(2);
+
+/// The commitment level to wait for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Commitment {
+    /// The transaction is processed by the connected node
+    Processed,
+    /// The transaction is voted on by supermajority of the cluster
+    Confirmed,
+    /// The block of the transaction is finalized
+    Finalized,
+}
+
+impl Commitment {
+    /// Returns the commitment config of the commitment level
+    pub fn config(&self) -> CommitmentConfig {
+        match self {
+            Self::Processed => CommitmentConfig::processed(),
+            Self::Confirmed => CommitmentConfig::confirmed(),
+            Self::Finalized => CommitmentConfig::finalized(),
+        }
+    }
+}
+
+/// The priority fee of the transactions, in micro-lamports per compute unit
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PriorityFee {
+    /// Derive the priority fee from the recent prioritization fees of the written accounts
+    Auto,
+    /// A fixed priority fee, in micro-lamports per compute unit
+    MicroLamports(u64),
+}
+
+impl FromStr for PriorityFee {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.eq_ignore_ascii_case("auto") {
+            Ok(Self::Auto)
+        } else {
+            s.parse().map(Self::MicroLamports).map_err(|_| {
+                format!("Invalid priority fee `{s}`, it must be `auto` or micro-lamports")
+            })
+        }
+    }
+}
+
+/// A recent prioritization fee, returned by `getRecentPrioritizationFees`
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RecentPrioritizationFee {
+    /// The prioritization fee, in micro-lamports per compute unit
+    prioritization_fee: u64,
+}
+
+/// Returns the priority fee at the given percentile of the recent prioritization fees of the given accounts
+fn recent_priority_fee(
+    rpc: &Rpc,
+    accounts: &[Pubkey],
+    percentile: u8,
+    args: &AppArgs,
+) -> SolwalrsResult<u64> {
+    crate::info!(
+        args,
+        "Getting the recent prioritization fees of {accounts:?}, the percentile is {percentile}"
+    );
+    let accounts: Vec<_> = accounts.iter().map(Pubkey::to_string).collect();
+    let mut fees: Vec<u64> = rpc
+        .call(|client| {
+            client.send::<Vec<RecentPrioritizationFee>>(
+                RpcRequest::Custom {
+                    method: "getRecentPrioritizationFees",
+                },
+                serde_json::json!([accounts]),
+            )
+        })
+        .map_err(|err| {
+            SolwalrsError::RpcError(format!(
+                "Error while getting the recent prioritization fees: {err}"
+            ))
+        })?
+        .into_iter()
+        .map(|fee| fee.prioritization_fee)
+        .collect();
+    fees.sort_unstable();
+    let fee = fees
+        .get((fees.len().saturating_sub(1) * percentile.min(100) as usize) / 100)
+        .copied()
+        .unwrap_or_default();
+    crate::info!(args, "The priority fee is {fee} micro-lamports");
+    Ok(fee)
+}
+
+/// Returns the compute budget instructions of the given instructions, with the compute unit price (micro-lamports).
+/// The compute unit price will be derived from the recent prioritization fees if `--priority-fee auto` is set
+fn compute_budget_instructions(
+    rpc: &Rpc,
+    instructions: &[Instruction],
+    args: &AppArgs,
+) -> SolwalrsResult<(Vec<Instruction>, u64)> {
+    let mut budget_instructions = Vec::new();
+    if let Some(units) = args.compute_units {
+        budget_instructions.push(ComputeBudgetInstruction::set_compute_unit_limit(units));
+    }
+    let price = match args.priority_fee {
+        Some(PriorityFee::MicroLamports(price)) => price,
+        Some(PriorityFee::Auto) => {
+            let mut writable_accounts: Vec<_> = instructions
+                .iter()
+                .flat_map(|instruction| &instruction.accounts)
+                .filter(|account| account.is_writable)
+                .map(|account| account.pubkey)
+                .collect();
+            writable_accounts.sort_unstable();
+            writable_accounts.dedup();
+            recent_priority_fee(rpc, &writable_accounts, args.priority_fee_percentile, args)?
+        }
+        None => 0,
+    };
+    if price != 0 {
+        budget_instructions.push(ComputeBudgetInstruction::set_compute_unit_price(price));
+    }
+    Ok((budget_instructions, price))
+}
+
+/// The expected token change of a token account
+#[derive(Debug)]
+pub struct TokenChange {
+    /// The mint of the token
+    pub mint: Pubkey,
+    /// The owner of the token account
+    pub owner: Pubkey,
+    /// The change of the token amount, in the smallest unit of the token
+    pub amount: i128,
+    /// The decimals of the token
+    pub decimals: u8,
+}
+
+/// The expected change of an account after sending the transaction
+#[derive(Debug)]
+pub struct AccountChange {
+    /// The address of the account
+    pub address: Pubkey,
+    /// The change of the SOL balance, in lamports
+    pub lamports: i128,
+    /// The token change, if the account is a token account
+    pub token: Option<TokenChange>,
+}
+
+/// The result of the transaction simulation
+#[derive(Debug)]
+pub struct Simulation {
+    /// The fee of the transaction, in lamports
+    pub fee: u64,
+    /// The error of the transaction, if it will fail
+    pub err: Option<TransactionError>,
+    /// The program logs
+    pub logs: Vec<String>,
+    /// The compute units consumed by the transaction
+    pub units_consumed: Option<u64>,
+    /// The expected changes of the writable accounts
+    pub changes: Vec<AccountChange>,
+}
+
+/// Returns the token account state, if the account is a SPL token account
+fn token_account(account: &Account) -> Option<spl_token::state::Account> {
+    (account.owner == spl_token::id())
+        .then(|| spl_token::state::Account::unpack(&account.data).ok())
+        .flatten()
+}
+
+/// Returns the decimals of the given mints
+fn mints_decimals(rpc: &Rpc, mints: &[Pubkey]) -> SolwalrsResult<HashMap<Pubkey, u8>> {
+    let accounts = rpc
+        .call(|client| client.get_multiple_accounts(mints))
+        .map_err(|err| SolwalrsError::RpcError(format!("Error while getting the mints: {err}")))?;
+    Ok(mints
+        .iter()
+        .zip(accounts)
+        .filter_map(|(mint, account)| {
+            let mint_state = spl_token::state::Mint::unpack(&account?.data).ok()?;
+            Some((*mint, mint_state.decimals))
+        })
+        .collect())
+}
+
+impl Simulation {
+    /// Simulate the given transaction, and calculate the expected changes of its writable accounts
+    pub fn simulate(rpc: &Rpc, transaction: &Transaction, args: &AppArgs) -> SolwalrsResult<Self> {
+        crate::info!(args, "Simulating the transaction {transaction:?}");
+        let message = &transaction.message;
+        let writable_accounts: Vec<_> = message
+            .account_keys
+            .iter()
+            .enumerate()
+            .filter(|(idx, _)| message.is_writable(*idx))
+            .map(|(_, address)| *address)
+            .collect();
+        let pre_accounts = rpc
+            .call(|client| client.get_multiple_accounts(&writable_accounts))
+            .map_err(|err| {
+                SolwalrsError::RpcError(format!("Error while getting the accounts: {err}"))
+            })?;
+        let fee = rpc
+            .call(|client| client.get_fee_for_message(message))
+            .map_err(|err| {
+                SolwalrsError::RpcError(format!("Error while getting the transaction fee: {err}"))
+            })?;
+        let result = rpc
+            .call(|client| {
+                client.simulate_transaction_with_config(
+                    transaction,
+                    RpcSimulateTransactionConfig {
+                        sig_verify: false,
+                        replace_recent_blockhash: true,
+                        accounts: Some(RpcSimulateTransactionAccountsConfig {
+                            encoding: Some(UiAccountEncoding::Base64),
+                            addresses: writable_accounts.iter().map(Pubkey::to_string).collect(),
+                        }),
+                        ..Default::default()
+                    },
+                )
+            })
+            .map_err(|err| {
+                SolwalrsError::RpcError(format!("Error while simulating the transaction: {err}"))
+            })?
+            .value;
+        crate::info!(args, "The simulation result is {result:?}");
+        let post_accounts: Vec<Option<Account>> = result
+            .accounts
+            .unwrap_or_default()
+            .into_iter()
+            .map(|account| account.and_then(|account| account.decode()))
+            .collect();
+
+        let mints: Vec<_> = pre_accounts
+            .iter()
+            .chain(post_accounts.iter())
+            .flatten()
+            .filter_map(token_account)
+            .map(|token_account| token_account.mint)
+            .collect();
+        let decimals = if mints.is_empty() {
+            HashMap::new()
+        } else {
+            mints_decimals(rpc, &mints)?
+        };
+
+        let changes = writable_accounts
+            .into_iter()
+            .enumerate()
+            .map(|(idx, address)| {
+                let pre = pre_accounts.get(idx).and_then(Option::as_ref);
+                let post = post_accounts.get(idx).and_then(Option::as_ref);
+                let lamports = |account: Option<&Account>| {
+                    account
+                        .map(|account| account.lamports as i128)
+                        .unwrap_or_default()
+                };
+                let pre_token = pre.and_then(token_account);
+                let post_token = post.and_then(token_account);
+                let token = post_token.as_ref().or(pre_token.as_ref()).map(|state| {
+                    let amount = |state: Option<&spl_token::state::Account>| {
+                        state.map(|state| state.amount as i128).unwrap_or_default()
+                    };
+                    TokenChange {
+                        mint: state.mint,
+                        owner: state.owner,
+                        amount: amount(post_token.as_ref()) - amount(pre_token.as_ref()),
+                        decimals: decimals.get(&state.mint).copied().unwrap_or_default(),
+                    }
+                });
+                AccountChange {
+                    address,
+                    lamports: lamports(post) - lamports(pre),
+                    token,
+                }
+            })
+            .collect();
+
+        Ok(Self {
+            fee,
+            err: result.err,
+            logs: result.logs.unwrap_or_default(),
+            units_consumed: result.units_consumed,
+            changes,
+        })
+    }
+
+    /// Print the summary of the simulation, the addresses will be annotated with the keypair names
+    pub fn print_summary(&self, wallet: &Wallet) {
+        println!("Expected changes:");
+        for change in &self.changes {
+            println!("  {}", wallet.annotate_address(&change.address.to_string()));
+            if change.lamports != 0 {
+                println!(
+                    "      SOL: {:+}",
+                    change.lamports as f64 / solana_sdk::native_token::LAMPORTS_PER_SOL as f64
+                );
+            }
+            if let Some(token) = change.token.as_ref().filter(|token| token.amount != 0) {
+                println!(
+                    "      Token {} (owner {}): {:+}",
+                    token.mint,
+                    wallet.annotate_address(&token.owner.to_string()),
+                    token.amount as f64 / 10f64.powi(token.decimals as i32)
+                );
+            }
+        }
+        println!(
+            "Fee: {} SOL ({} lamports)",
+            lamports_to_sol(self.fee),
+            self.fee
+        );
+        if let Some(units) = self.units_consumed {
+            println!("Compute units: {units}");
+        }
+        if let Some(err) = &self.err {
+            println!("Program error: {err}");
+        }
+        if !self.logs.is_empty() {
+            println!("Logs:");
+            for log in &self.logs {
+                println!("  {log}");
+            }
+        }
+    }
+}
+
+/// Send the given instructions in a transaction, the fee payer will be the first signer.
+///
+/// The transaction will be simulated first, then the expected changes will be shown
+/// and the user have to confirm sending it (unless the `--yes` flag is set).
+pub fn send_instructions(
+    instructions: &[Instruction],
+    signers: &[&KeyPair],
+    wallet: &Wallet,
+    args: &AppArgs,
+) -> SolwalrsResult<Signature> {
+    let payer = signers
+        .first()
+        .ok_or_else(|| SolwalrsError::Other("The transaction needs a fee payer".to_owned()))?;
+    crate::info!(args, "Sending {instructions:?}, the fee payer is {payer:?}");
+    let rpc = Rpc::new(args);
+    let (mut all_instructions, priority_fee) =
+        compute_budget_instructions(&rpc, instructions, args)?;
+    all_instructions.extend_from_slice(instructions);
+    let keypairs = signers
+        .iter()
+        .map(|signer| signer.signer())
+        .collect::<SolwalrsResult<Vec<Keypair>>>()?;
+    let (blockhash, last_valid_block_height) = rpc
+        .call(|client| client.get_latest_blockhash_with_commitment(args.commitment.config()))
+        .map_err(|err| {
+            SolwalrsError::RpcError(format!("Error while getting the latest blockhash: {err}"))
+        })?;
+    let transaction = Transaction::new_signed_with_payer(
+        &all_instructions,
+        Some(&payer.pubkey()),
+        &keypairs.iter().collect::<Vec<_>>(),
+        blockhash,
+    );
+
+    let simulation = Simulation::simulate(&rpc, &transaction, args)?;
+    simulation.print_summary(wallet);
+    if priority_fee != 0 {
+        // The compute unit limit is the `--compute-units` or the default limit of the instructions
+        let units = args.compute_units.map(u64::from).unwrap_or_else(|| {
+            instructions
+                .iter()
+                .filter(|instruction| instruction.program_id != compute_budget::id())
+                .count() as u64
+                * DEFAULT_INSTRUCTION_COMPUTE_UNITS
+        });
+        let units = units.min(MAX_TRANSACTION_COMPUTE_UNITS);
+        let max_fee = (priority_fee as u128 * units as u128).div_ceil(1_000_000);
+        println!(
+            "Priority fee: {priority_fee} micro-lamports per compute unit, up to {} SOL ({max_fee} lamports) for {units} compute units",
+            max_fee as f64 / solana_sdk::native_token::LAMPORTS_PER_SOL as f64
+        );
+    }
+    confirm_and_send(
+        &rpc,
+        &transaction,
+        simulation,
+        Some(last_valid_block_height),
+        args,
+    )
+}
+
+/// Make sure the transaction that's created by someone else is well formed, signing a malformed one would panic
+pub fn sanitize_transaction(transaction: &Transaction) -> SolwalrsResult<()> {
+    transaction
+        .sanitize()
+        .map_err(|err| SolwalrsError::Other(format!("Invalid transaction: {err}")))?;
+    let required = transaction.message.header.num_required_signatures as usize;
+    if transaction.signatures.len() != required {
+        return Err(SolwalrsError::Other(format!(
+            "Invalid transaction: it has {} signatures, but {required} are required",
+            transaction.signatures.len()
+        )));
+    }
+    Ok(())
+}
+
+/// Sign a transaction that's created by someone else, e.g. by a Solana Pay merchant, with the keypair.
+///
+/// The other signatures must be already set. If the transaction has no signatures, its blockhash
+/// will be replaced with the latest one. The transaction will be simulated and sent the same as `send_instructions`.
+pub fn sign_and_send(
+    mut transaction: Transaction,
+    keypair: &KeyPair,
+    wallet: &Wallet,
+    args: &AppArgs,
+) -> SolwalrsResult<Signature> {
+    crate::info!(args, "Signing {transaction:?} with `{}`", keypair.name);
+    sanitize_transaction(&transaction)?;
+    let rpc = Rpc::new(args);
+    let is_signer = transaction
+        .get_signing_keypair_positions(&[keypair.pubkey()])
+        .map_err(|err| SolwalrsError::Other(format!("Invalid transaction: {err}")))?
+        .first()
+        .is_some_and(Option::is_some);
+    if !is_signer {
+        return Err(SolwalrsError::Other(format!(
+            "The keypair `{}` isn't a signer of the transaction",
+            keypair.name
+        )));
+    }
+    // The last valid block height is only known if the blockhash is replaced with the latest one
+    let mut last_valid_block_height = None;
+    if transaction
+        .signatures
+        .iter()
+        .all(|signature| signature == &Signature::default())
+    {
+        let (blockhash, last_valid) = rpc
+            .call(|client| client.get_latest_blockhash_with_commitment(args.commitment.config()))
+            .map_err(|err| {
+                SolwalrsError::RpcError(format!("Error while getting the latest blockhash: {err}"))
+            })?;
+        transaction.message.recent_blockhash = blockhash;
+        last_valid_block_height = Some(last_valid);
+    }
+    let recent_blockhash = transaction.message.recent_blockhash;
+    transaction
+        .try_partial_sign(&[&keypair.signer()?], recent_blockhash)
+        .map_err(|err| SolwalrsError::Other(format!("Failed to sign the transaction: {err}")))?;
+    if !transaction.is_signed() {
+        return Err(SolwalrsError::Other(
+            "The transaction needs the signatures of other signers".to_owned(),
+        ));
+    }
+
+    let simulation = Simulation::simulate(&rpc, &transaction, args)?;
+    simulation.print_summary(wallet);
+    confirm_and_send(
+        &rpc,
+        &transaction,
+        simulation,
+        last_valid_block_height,
+        args,
+    )
+}
+
+/// Send the simulated transaction after the user confirms it, will return an error if the simulation failed
+fn confirm_and_send(
+    rpc: &Rpc,
+    transaction: &Transaction,
+    simulation: Simulation,
+    last_valid_block_height: Option<u64>,
+    args: &AppArgs,
+) -> SolwalrsResult<Signature> {
+    if let Some(err) = simulation.err {
+        return Err(SolwalrsError::SimulationFailed(err.to_string()));
+    }
+    if !utils::confirm("Send the transaction?", args)? {
+        return Err(SolwalrsError::Cancelled);
+    }
+
+    send_and_confirm(rpc, transaction, last_valid_block_height, args)
+}
+
+/// Returns the status of the given signature, `true` if it's reached the `--commitment` level.
+/// Will return `Error::TransactionFailed` if the transaction failed.
+fn signature_status(rpc: &Rpc, signature: &Signature, args: &AppArgs) -> SolwalrsResult<bool> {
+    let status = rpc
+        .call(|client| client.get_signature_statuses(&[*signature]))
+        .map_err(|err| {
+            SolwalrsError::RpcError(format!(
+                "Error while getting the status of the signature `{signature}`: {err}"
+            ))
+        })?
+        .value
+        .into_iter()
+        .next()
+        .flatten();
+    crate::info!(args, "The status of `{signature}` is {status:?}");
+    match status {
+        Some(status) => match status.err {
+            Some(err) => Err(SolwalrsError::TransactionFailed(format!(
+                "The transaction `{signature}` failed: {err}"
+            ))),
+            None => Ok(status.satisfies_commitment(args.commitment.config())),
+        },
+        None => Ok(false),
+    }
+}
+
+/// Returns whether the signature reached the `--commitment` level, like `signature_status`.
+/// The RPC errors are logged and `false` is returned, the transaction may land anyway so the next check may succeed
+fn is_confirmed(rpc: &Rpc, signature: &Signature, args: &AppArgs) -> SolwalrsResult<bool> {
+    match signature_status(rpc, signature, args) {
+        Err(SolwalrsError::RpcError(err)) => {
+            crate::warn!(args, "{err}");
+            Ok(false)
+        }
+        status => status,
+    }
+}
+
+/// Send the signed transaction and wait until it reaches the `--commitment` level.
+///
+/// The transaction will be rebroadcasted until it's confirmed, or until its blockhash expires
+/// (returns `Error::TransactionExpired`), or until the `--timeout` passes (returns `Error::Timeout`).
+/// The blockhash expires when the block height passes `last_valid_block_height`, or if it's `None`
+/// (the blockhash isn't the latest one) when the RPC reports the blockhash as invalid.
+pub fn send_and_confirm(
+    rpc: &Rpc,
+    transaction: &Transaction,
+    last_valid_block_height: Option<u64>,
+    args: &AppArgs,
+) -> SolwalrsResult<Signature> {
+    let signature = transaction.signatures[0];
+    let started = Instant::now();
+    let timeout = Duration::from_secs(args.timeout);
+    loop {
+        crate::info!(args, "Broadcasting the transaction `{signature}`");
+        // The transaction is already simulated, so we can skip the preflight
+        if let Err(err) = rpc.call(|client| {
+            client.send_transaction_with_config(
+                transaction,
+                RpcSendTransactionConfig {
+                    skip_preflight: true,
+                    max_retries: Some(0),
+                    ..Default::default()
+                },
+            )
+        }) {
+            crate::warn!(
+                args,
+                "Failed to broadcast the transaction `{signature}`: {err}"
+            );
+        }
+        std::thread::sleep(CONFIRMATION_INTERVAL);
+
+        if is_confirmed(rpc, &signature, args)? {
+            crate::info!(args, "The transaction `{signature}` is confirmed");
+            return Ok(signature);
+        }
+        let expired = match last_valid_block_height {
+            Some(last_valid_block_height) => rpc
+                .call(|client| {
+                    client.get_block_height_with_commitment(CommitmentConfig::confirmed())
+                })
+                .map(|block_height| block_height > last_valid_block_height),
+            None => rpc
+                .call(|client| {
+                    client.is_blockhash_valid(
+                        &transaction.message.recent_blockhash,
+                        CommitmentConfig::confirmed(),
+                    )
+                })
+                .map(|is_valid| !is_valid),
+        };
+        match expired {
+            Ok(true) => {
+                // The transaction may be confirmed right before the blockhash expired
+                if is_confirmed(rpc, &signature, args)? {
+                    return Ok(signature);
+                }
+                return Err(SolwalrsError::TransactionExpired(signature.to_string()));
+            }
+            Ok(false) => {}
+            Err(err) => {
+                crate::warn!(args, "Error while checking the blockhash expiry: {err}");
+            }
+        }
+        if started.elapsed() >= timeout {
+            return Err(SolwalrsError::Timeout(signature.to_string()));
+        }
+    }
+}
+
+/// Wait until the given signature reaches the `--commitment` level, without rebroadcasting it.
+/// Will return `Error::Timeout` if the `--timeout` passes before that.
+pub fn confirm_signature(args: &AppArgs, signature: &str) -> SolwalrsResult<()> {
+    crate::info!(args, "Confirming the signature `{signature}`");
+    let rpc = Rpc::new(args);
+    let signature: Signature = signature
+        .parse()
+        .map_err(|_| SolwalrsError::Other(format!("Invalid signature `{signature}`")))?;
+    let started = Instant::now();
+    let timeout = Duration::from_secs(args.timeout);
+    while !is_confirmed(&rpc, &signature, args)? {
+        if started.elapsed() >= timeout {
+            return Err(SolwalrsError::Timeout(signature.to_string()));
+        }
+        crate::info!(args, "Waiting for the signature to be confirmed...");
+        std::thread::sleep(CONFIRMATION_INTERVAL);
+    }
+    crate::info!(args, "Signature `{signature}` confirmed successfully");
+    Ok(())
+}
@@ -0,0 +1,250 @@
+// Solwalrs, A simple and easy to use CLI Solana wallet
+// Copyright (C) 2022  Solwalrs contributors <https://github.com/TheAwiteb/solwalrs/graphs/contributors>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/gpl-3.0.html>.
+
+//! Shamir's secret sharing of a keypair or the wallet password.
+//!
+//! The secret is prefixed with its kind and suffixed with a checksum before it's split, so the
+//! recovered secret is checked. Every share has its own checksum too, to catch the typos when
+//! it's entered by hand. A share is encoded as `solwalrs-share-<threshold>-<base58>`
+
+use std::convert::TryFrom;
+
+use base58::{FromBase58, ToBase58};
+use sha2::{Digest, Sha256};
+use sharks::{Share, Sharks};
+use zeroize::Zeroizing;
+
+use crate::errors::{Error as SolwalrsError, Result as SolwalrsResult};
+
+/// The prefix of the encoded shares
+const SHARE_PREFIX: &str = "solwalrs-share-";
+
+/// The length of the checksums of the secret and the shares
+const CHECKSUM_LEN: usize = 4;
+
+/// The kind of the shared secret
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SecretKind {
+    /// The private key of a keypair, 64 bytes (secret key and public key)
+    KeyPair,
+    /// The wallet password, with the wallet file it restores the whole wallet
+    Password,
+}
+
+/// A share of a secret, with the number of the shares needed to recover it
+pub struct SecretShare {
+    /// The number of the shares needed to recover the secret
+    pub threshold: u8,
+    /// The share itself
+    share: Share,
+}
+
+impl SecretKind {
+    fn tag(self) -> u8 {
+        match self {
+            SecretKind::KeyPair => 0,
+            SecretKind::Password => 1,
+        }
+    }
+
+    fn from_tag(tag: u8) -> Option<Self> {
+        match tag {
+            0 => Some(SecretKind::KeyPair),
+            1 => Some(SecretKind::Password),
+            _ => None,
+        }
+    }
+}
+
+impl std::fmt::Display for SecretKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SecretKind::KeyPair => write!(f, "keypair"),
+            SecretKind::Password => write!(f, "wallet password"),
+        }
+    }
+}
+
+/// Returns the checksum of the given bytes, the first bytes of its SHA-256 hash
+fn checksum(bytes: &[u8]) -> [u8; CHECKSUM_LEN] {
+    let mut checksum = [0; CHECKSUM_LEN];
+    checksum.copy_from_slice(&Sha256::digest(bytes)[..CHECKSUM_LEN]);
+    checksum
+}
+
+impl SecretShare {
+    /// Encode the share, `solwalrs-share-<threshold>-<base58 of the share and its checksum>`
+    pub fn encode(&self) -> Zeroizing<String> {
+        let mut bytes = Zeroizing::new(Vec::from(&self.share));
+        let checksum = checksum(&bytes);
+        bytes.extend_from_slice(&checksum);
+        Zeroizing::new(format!(
+            "{SHARE_PREFIX}{}-{}",
+            self.threshold,
+            bytes.to_base58()
+        ))
+    }
+
+    /// Decode the share, will return an error if it's not a valid share or its checksum doesn't match
+    pub fn decode(input: &str) -> SolwalrsResult<Self> {
+        let invalid = |reason: &str| SolwalrsError::Other(format!("Invalid share: {reason}"));
+        let (threshold, encoded) = input
+            .trim()
+            .strip_prefix(SHARE_PREFIX)
+            .and_then(|share| share.split_once('-'))
+            .ok_or_else(|| invalid("it doesn't start with `solwalrs-share-`"))?;
+        let threshold = threshold
+            .parse()
+            .map_err(|_| invalid("the threshold is not a number"))?;
+        let bytes = Zeroizing::new(
+            encoded
+                .from_base58()
+                .map_err(|_| invalid("it's not base58 encoded"))?,
+        );
+        if bytes.len() <= CHECKSUM_LEN {
+            return Err(invalid("it's too short"));
+        }
+        let (share, share_checksum) = bytes.split_at(bytes.len() - CHECKSUM_LEN);
+        if checksum(share) != share_checksum {
+            return Err(invalid("the checksum doesn't match, check it for typos"));
+        }
+        Ok(Self {
+            threshold,
+            share: Share::try_from(share).map_err(invalid)?,
+        })
+    }
+
+    /// The index of the share, starts from 1
+    pub fn index(&self) -> u8 {
+        self.share.x.0
+    }
+}
+
+/// Split the secret into `shares` shares, any `threshold` of them recover it
+pub fn split_secret(
+    kind: SecretKind,
+    secret: &[u8],
+    threshold: u8,
+    shares: u8,
+) -> SolwalrsResult<Vec<SecretShare>> {
+    if threshold < 2 || threshold > shares {
+        return Err(SolwalrsError::Other(format!(
+            "The threshold must be between 2 and the number of the shares ({shares})"
+        )));
+    }
+    let mut payload = Zeroizing::new(Vec::with_capacity(secret.len() + 1 + CHECKSUM_LEN));
+    payload.push(kind.tag());
+    payload.extend_from_slice(secret);
+    let checksum = checksum(&payload);
+    payload.extend_from_slice(&checksum);
+    Ok(Sharks(threshold)
+        .dealer(&payload)
+        .take(shares as usize)
+        .map(|share| SecretShare { threshold, share })
+        .collect())
+}
+
+/// Recover the secret from the shares, will return an error if there aren't enough shares,
+/// or the shares are not of the same secret
+pub fn recover_secret(shares: &[SecretShare]) -> SolwalrsResult<(SecretKind, Zeroizing<Vec<u8>>)> {
+    let threshold = shares.first().map(|share| share.threshold).unwrap_or(2);
+    if shares.iter().any(|share| share.threshold != threshold) {
+        return Err(SolwalrsError::Other(
+            "The shares have different thresholds, they are not of the same secret".to_owned(),
+        ));
+    }
+    let payload = Zeroizing::new(
+        Sharks(threshold)
+            .recover(shares.iter().map(|share| &share.share))
+            .map_err(|err| SolwalrsError::Other(format!("Failed to recover the secret: {err}")))?,
+    );
+    let mismatch = || {
+        SolwalrsError::Other(
+            "The shares are not of the same secret, or they are modified".to_owned(),
+        )
+    };
+    if payload.len() <= CHECKSUM_LEN + 1 {
+        return Err(mismatch());
+    }
+    let (secret, secret_checksum) = payload.split_at(payload.len() - CHECKSUM_LEN);
+    if checksum(secret) != secret_checksum {
+        return Err(mismatch());
+    }
+    let kind = SecretKind::from_tag(secret[0]).ok_or_else(mismatch)?;
+    Ok((kind, Zeroizing::new(secret[1..].to_vec())))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn encoded_shares(threshold: u8, shares: u8) -> Vec<Zeroizing<String>> {
+        split_secret(SecretKind::KeyPair, &[7; 64], threshold, shares)
+            .unwrap()
+            .iter()
+            .map(SecretShare::encode)
+            .collect()
+    }
+
+    fn recover(shares: &[&Zeroizing<String>]) -> SolwalrsResult<(SecretKind, Zeroizing<Vec<u8>>)> {
+        let shares = shares
+            .iter()
+            .map(|share| SecretShare::decode(share))
+            .collect::<SolwalrsResult<Vec<_>>>()?;
+        recover_secret(&shares)
+    }
+
+    #[test]
+    fn any_threshold_shares_recover_the_secret() {
+        let shares = encoded_shares(3, 5);
+        assert!(shares[0].starts_with("solwalrs-share-3-"));
+        for picked in [[0, 1, 2], [4, 2, 0], [1, 3, 4]] {
+            let (kind, secret) = recover(&picked.map(|idx| &shares[idx])).unwrap();
+            assert_eq!(kind, SecretKind::KeyPair);
+            assert_eq!(*secret, [7; 64]);
+        }
+    }
+
+    #[test]
+    fn not_enough_shares() {
+        let shares = encoded_shares(3, 5);
+        assert!(recover(&[&shares[0], &shares[1]]).is_err());
+        // The same share twice isn't counted twice
+        assert!(recover(&[&shares[0], &shares[1], &shares[1]]).is_err());
+    }
+
+    #[test]
+    fn shares_of_different_secrets() {
+        let (first, second) = (encoded_shares(2, 3), encoded_shares(2, 3));
+        assert!(recover(&[&first[0], &second[1]]).is_err());
+    }
+
+    #[test]
+    fn share_typo_is_detected() {
+        let share = encoded_shares(2, 2).remove(0);
+        let mut typo = share.to_string();
+        let last = typo.pop().unwrap();
+        typo.push(if last == 'a' { 'b' } else { 'a' });
+        assert!(SecretShare::decode(&typo).is_err());
+        assert!(SecretShare::decode(&share).is_ok());
+    }
+
+    #[test]
+    fn invalid_threshold() {
+        assert!(split_secret(SecretKind::Password, &[1; 32], 1, 5).is_err());
+        assert!(split_secret(SecretKind::Password, &[1; 32], 6, 5).is_err());
+    }
+}
@@ -0,0 +1,732 @@
+// Solwalrs, A simple and easy to use CLI Solana wallet
+// Copyright (C) 2022  Solwalrs contributors <https://github.com/TheAwiteb/solwalrs/graphs/contributors>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/gpl-3.0.html>.
+
+//! Solana Pay transfer requests, `solana:<recipient>?amount=<amount>&spl-token=<mint>&...`,
+//! and transaction requests, `solana:<https link>`
+//!
+//! See the specification <https://docs.solanapay.com/spec>
+
+use serde::Deserialize;
+use solana_client::rpc_client::GetConfirmedSignaturesForAddress2Config;
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    program_pack::Pack,
+    pubkey::Pubkey,
+    signature::Signature,
+    system_instruction,
+    transaction::Transaction,
+};
+use solana_transaction_status::UiTransactionTokenBalance;
+use spl_associated_token_account::get_associated_token_address;
+use url::{form_urlencoded, Url};
+
+use super::{sanitize_transaction, Rpc, Tokens, TransactionDetails};
+use crate::{
+    app::AppArgs,
+    errors::{Error as SolwalrsError, Result as SolwalrsResult},
+};
+
+/// The URI scheme of Solana Pay
+pub const SOLANA_PAY_SCHEME: &str = "solana";
+
+/// The number of the decimals of SOL
+pub const SOL_DECIMALS: usize = 9;
+
+/// A Solana Pay transfer request
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TransferRequest {
+    /// The recipient of the transfer, a system account (not a token account)
+    pub recipient: Pubkey,
+    /// The amount to transfer, in SOL or in tokens (not lamports), the payer enters it if it's `None`
+    pub amount: Option<String>,
+    /// The mint of the SPL token to transfer, SOL if it's `None`
+    pub spl_token: Option<Pubkey>,
+    /// The reference keys, they're added to the transfer instruction to find the transaction
+    pub references: Vec<Pubkey>,
+    /// The source of the request, e.g. the store name
+    pub label: Option<String>,
+    /// What the request is for, e.g. the item name
+    pub message: Option<String>,
+    /// The memo to add to the transaction
+    pub memo: Option<String>,
+}
+
+/// A Solana Pay transaction request, the transaction to sign is requested from the link
+#[derive(Debug, Clone, PartialEq)]
+pub struct TransactionRequest {
+    /// The HTTPS link of the merchant
+    pub link: Url,
+}
+
+/// A Solana Pay request, parsed from a `solana:` URI
+#[derive(Debug, Clone, PartialEq)]
+pub enum PaymentRequest {
+    /// Transfer SOL or SPL tokens to the recipient
+    Transfer(TransferRequest),
+    /// Sign the transaction of the merchant
+    Transaction(TransactionRequest),
+}
+
+/// The label and the icon of the merchant, the response of the `GET` request of the transaction request
+#[derive(Debug, Deserialize)]
+pub struct MerchantInfo {
+    /// The merchant name
+    pub label: String,
+    /// The URL of the merchant icon
+    pub icon: String,
+}
+
+/// The response of the `POST` request of the transaction request
+#[derive(Debug, Deserialize)]
+struct TransactionResponse {
+    /// The base64 encoded transaction
+    transaction: String,
+    /// What the transaction is for, e.g. the item name
+    message: Option<String>,
+}
+
+/// Split the decimal amount to its integer and fraction digits, `None` if it isn't a non-negative decimal number
+fn split_amount(amount: &str) -> Option<(&str, &str)> {
+    let (integer, fraction) = amount.split_once('.').unwrap_or((amount, ""));
+    (!integer.is_empty()
+        && integer
+            .chars()
+            .chain(fraction.chars())
+            .all(|c| c.is_ascii_digit()))
+    .then_some((integer, fraction))
+}
+
+/// Set the query parameter, will return an error if it's already set
+fn set_once<T>(field: &mut Option<T>, value: T, key: &str) -> SolwalrsResult<()> {
+    if field.replace(value).is_some() {
+        return Err(SolwalrsError::Other(format!(
+            "Invalid Solana Pay URI, the `{key}` parameter is repeated"
+        )));
+    }
+    Ok(())
+}
+
+/// Format the amount as a decimal number, with at most `decimals` decimals and without the trailing zeros.
+/// Will return an error if the amount isn't positive
+pub fn format_amount(amount: f64, decimals: usize) -> SolwalrsResult<String> {
+    if !amount.is_finite() || amount <= 0.0 {
+        return Err(SolwalrsError::Other(format!(
+            "The amount must be positive, got `{amount}`"
+        )));
+    }
+    let formatted = format!("{amount:.decimals$}");
+    let formatted = if formatted.contains('.') {
+        formatted.trim_end_matches('0').trim_end_matches('.')
+    } else {
+        &formatted
+    };
+    if formatted == "0" {
+        return Err(SolwalrsError::Other(format!(
+            "The amount `{amount}` is less than the smallest unit, it has {decimals} decimals"
+        )));
+    }
+    Ok(formatted.to_owned())
+}
+
+/// Parse the decimal amount to the smallest units, e.g. `0.01` SOL to `10000000` lamports.
+/// Will return an error if the amount has more than `decimals` decimals
+pub fn amount_to_units(amount: &str, decimals: usize) -> SolwalrsResult<u64> {
+    let invalid = || {
+        SolwalrsError::Other(format!(
+            "Invalid amount `{amount}`, it must be a decimal number with at most {decimals} decimals"
+        ))
+    };
+    let (integer, fraction) = split_amount(amount)
+        .filter(|(_, fraction)| fraction.len() <= decimals)
+        .ok_or_else(invalid)?;
+    format!("{integer}{fraction:0<decimals$}")
+        .parse()
+        .map_err(|_| invalid())
+}
+
+/// Returns the signature of the oldest transaction that references the given key, `None` if there is no one yet
+pub fn find_reference(reference: &Pubkey, args: &AppArgs) -> SolwalrsResult<Option<Signature>> {
+    crate::info!(
+        args,
+        "Searching for the transactions of the reference `{reference}`"
+    );
+    let signatures = Rpc::new(args)
+        .call(|client| {
+            client.get_signatures_for_address_with_config(
+                reference,
+                GetConfirmedSignaturesForAddress2Config {
+                    commitment: Some(args.commitment.config()),
+                    ..Default::default()
+                },
+            )
+        })
+        .map_err(|err| {
+            SolwalrsError::RpcError(format!(
+                "Error while getting the signatures of the reference `{reference}`: {err}"
+            ))
+        })?;
+    crate::info!(args, "The signatures of the reference are {signatures:?}");
+    // The signatures are ordered from the newest to the oldest
+    signatures
+        .last()
+        .map(|status| {
+            status.signature.parse().map_err(|_| {
+                SolwalrsError::RpcError(format!("Invalid signature `{}`", status.signature))
+            })
+        })
+        .transpose()
+}
+
+/// Returns the token amount of the given owner and mint, in the smallest units
+fn token_amount(balances: &[UiTransactionTokenBalance], owner: &str, mint: &str) -> u64 {
+    balances
+        .iter()
+        .filter(|balance| {
+            balance.mint == mint
+                && Option::<&String>::from(balance.owner.as_ref()) == Some(&owner.to_owned())
+        })
+        .filter_map(|balance| balance.ui_token_amount.amount.parse::<u64>().ok())
+        .sum()
+}
+
+impl PaymentRequest {
+    /// Parse the `solana:` URI, the link of the transaction request can be URL-encoded
+    pub fn parse(uri: &str) -> SolwalrsResult<Self> {
+        let invalid = |reason: &str| {
+            SolwalrsError::Other(format!("Invalid Solana Pay URI `{uri}`, {reason}"))
+        };
+        let (_, path) = uri
+            .trim()
+            .split_once(':')
+            .filter(|(scheme, _)| scheme.eq_ignore_ascii_case(SOLANA_PAY_SCHEME))
+            .ok_or_else(|| invalid("it must start with `solana:`"))?;
+
+        if path.to_ascii_lowercase().starts_with("https") {
+            let link = if path.to_ascii_lowercase().starts_with("https%3a") {
+                form_urlencoded::parse(format!("link={path}").as_bytes())
+                    .map(|(_, link)| link.into_owned())
+                    .next()
+                    .unwrap_or_default()
+            } else {
+                path.to_owned()
+            };
+            let link = Url::parse(&link)
+                .ok()
+                .filter(|link| link.scheme() == "https")
+                .ok_or_else(|| invalid("the link isn't a valid HTTPS URL"))?;
+            return Ok(Self::Transaction(TransactionRequest { link }));
+        }
+
+        let (recipient, query) = path.split_once('?').unwrap_or((path, ""));
+        let mut request = TransferRequest {
+            recipient: recipient
+                .parse()
+                .map_err(|_| invalid("the recipient isn't a valid address"))?,
+            ..Default::default()
+        };
+        let parse_pubkey = |value: &str| {
+            value
+                .parse::<Pubkey>()
+                .map_err(|_| invalid(&format!("`{value}` isn't a valid address")))
+        };
+        for (key, value) in form_urlencoded::parse(query.as_bytes()) {
+            let value = value.into_owned();
+            match key.as_ref() {
+                "amount" => {
+                    if split_amount(&value).is_none() {
+                        return Err(invalid(&format!("`{value}` isn't a valid amount")));
+                    }
+                    set_once(&mut request.amount, value, "amount")?
+                }
+                "spl-token" => {
+                    set_once(&mut request.spl_token, parse_pubkey(&value)?, "spl-token")?
+                }
+                "reference" => request.references.push(parse_pubkey(&value)?),
+                "label" => set_once(&mut request.label, value, "label")?,
+                "message" => set_once(&mut request.message, value, "message")?,
+                "memo" => set_once(&mut request.memo, value, "memo")?,
+                // The unknown parameters are ignored, they may be added in the future versions
+                _ => {}
+            }
+        }
+        Ok(Self::Transfer(request))
+    }
+}
+
+impl TransactionRequest {
+    /// Send the requests to the given endpoint instead of the origin of the link, the path and the query are kept
+    pub fn set_endpoint(&mut self, endpoint: &Url) -> SolwalrsResult<()> {
+        let invalid = || SolwalrsError::Other(format!("Invalid endpoint `{endpoint}`"));
+        self.link
+            .set_scheme(endpoint.scheme())
+            .map_err(|_| invalid())?;
+        self.link
+            .set_host(endpoint.host_str())
+            .map_err(|_| invalid())?;
+        self.link.set_port(endpoint.port()).map_err(|_| invalid())?;
+        Ok(())
+    }
+
+    /// Get the label and the icon of the merchant
+    pub fn merchant(&self, args: &AppArgs) -> SolwalrsResult<MerchantInfo> {
+        crate::info!(args, "Getting the merchant info from `{}`", self.link);
+        let info = reqwest::blocking::get(self.link.clone())
+            .and_then(|response| response.error_for_status())
+            .map_err(|err| SolwalrsError::RequestError(err.to_string()))?
+            .json::<MerchantInfo>()
+            .map_err(|err| {
+                SolwalrsError::Other(format!("Failed to parse the merchant info: {err}"))
+            })?;
+        crate::info!(args, "The merchant info is {info:?}");
+        Ok(info)
+    }
+
+    /// Request the transaction for the given account to sign, with the message of the merchant
+    pub fn transaction(
+        &self,
+        account: &Pubkey,
+        args: &AppArgs,
+    ) -> SolwalrsResult<(Transaction, Option<String>)> {
+        crate::info!(
+            args,
+            "Requesting the transaction of `{account}` from `{}`",
+            self.link
+        );
+        let response = reqwest::blocking::Client::new()
+            .post(self.link.clone())
+            .json(&serde_json::json!({ "account": account.to_string() }))
+            .send()
+            .and_then(|response| response.error_for_status())
+            .map_err(|err| SolwalrsError::RequestError(err.to_string()))?
+            .json::<TransactionResponse>()
+            .map_err(|err| {
+                SolwalrsError::Other(format!("Failed to parse the transaction response: {err}"))
+            })?;
+        crate::info!(args, "The transaction response is {response:?}");
+        Ok((decode_transaction(&response.transaction)?, response.message))
+    }
+}
+
+/// Decode the base64 encoded transaction of the merchant, it's sanitized before it's signed
+fn decode_transaction(encoded: &str) -> SolwalrsResult<Transaction> {
+    let transaction = base64::decode(encoded)
+        .ok()
+        .and_then(|bytes| bincode::deserialize::<Transaction>(&bytes).ok())
+        .ok_or_else(|| {
+            SolwalrsError::Other("The merchant returned an invalid transaction".to_owned())
+        })?;
+    sanitize_transaction(&transaction).map_err(|err| {
+        SolwalrsError::Other(format!(
+            "The merchant returned an invalid transaction: {err}"
+        ))
+    })?;
+    Ok(transaction)
+}
+
+impl TransferRequest {
+    /// Returns the number of the decimals of the transferred token, the mint is fetched if it isn't supported
+    pub fn decimals(&self, args: &AppArgs) -> SolwalrsResult<usize> {
+        let Some(mint) = &self.spl_token else {
+            return Ok(SOL_DECIMALS);
+        };
+        if let Some(token) = Tokens::from_mint(mint) {
+            return Ok(token.decimals());
+        }
+        crate::info!(args, "Getting the decimals of the mint `{mint}`");
+        let account = Rpc::new(args)
+            .call(|client| client.get_account(mint))
+            .map_err(|err| {
+                SolwalrsError::RpcError(format!("Error while getting the mint `{mint}`: {err}"))
+            })?;
+        spl_token::state::Mint::unpack(&account.data)
+            .map(|mint| mint.decimals as usize)
+            .map_err(|_| SolwalrsError::Other(format!("`{mint}` isn't a SPL token mint")))
+    }
+
+    /// Returns the instructions that pay the request from the payer, the memo instruction then the transfer
+    /// instruction with the references. `amount` is in the smallest units (lamports for SOL)
+    pub fn instructions(
+        &self,
+        payer: &Pubkey,
+        amount: u64,
+        decimals: usize,
+    ) -> SolwalrsResult<Vec<Instruction>> {
+        let mut instructions = Vec::new();
+        if let Some(memo) = &self.memo {
+            instructions.push(spl_memo::build_memo(memo.as_bytes(), &[]));
+        }
+        let mut transfer = match &self.spl_token {
+            Some(mint) => spl_token::instruction::transfer_checked(
+                &spl_token::id(),
+                &get_associated_token_address(payer, mint),
+                mint,
+                &get_associated_token_address(&self.recipient, mint),
+                payer,
+                &[],
+                amount,
+                decimals as u8,
+            )
+            .map_err(|err| {
+                SolwalrsError::Other(format!("Failed to create the transfer instruction: {err}"))
+            })?,
+            None => system_instruction::transfer(payer, &self.recipient, amount),
+        };
+        transfer.accounts.extend(
+            self.references
+                .iter()
+                .map(|reference| AccountMeta::new_readonly(*reference, false)),
+        );
+        instructions.push(transfer);
+        Ok(instructions)
+    }
+
+    /// Validate that the given transaction pays the request, `decimals` is the decimals of the transferred token.
+    ///
+    /// The transaction must be succeeded, contain all the references and increase the balance
+    /// of the recipient (or its token balance) by at least the requested amount.
+    /// Will return `Error::PaymentMismatch` if it doesn't
+    pub fn validate_transfer(
+        &self,
+        details: &TransactionDetails,
+        decimals: usize,
+    ) -> SolwalrsResult<()> {
+        let signature = details
+            .signature()
+            .map(Signature::to_string)
+            .unwrap_or_default();
+        let mismatch = |reason: String| SolwalrsError::PaymentMismatch(signature.clone(), reason);
+        let meta = details
+            .meta
+            .as_ref()
+            .ok_or_else(|| mismatch("the transaction status is unknown".to_owned()))?;
+        if let Some(err) = &meta.err {
+            return Err(mismatch(format!("the transaction failed: {err}")));
+        }
+        let account_keys = details.account_keys();
+        if let Some(reference) = self
+            .references
+            .iter()
+            .find(|reference| !account_keys.iter().any(|key| key == *reference))
+        {
+            return Err(mismatch(format!(
+                "the reference `{reference}` isn't in the transaction"
+            )));
+        }
+
+        let expected = self
+            .amount
+            .as_deref()
+            .map(|amount| amount_to_units(amount, decimals))
+            .transpose()?
+            .unwrap_or_default();
+        let received = match &self.spl_token {
+            Some(mint) => {
+                let recipient = self.recipient.to_string();
+                let mint = mint.to_string();
+                let pre_balances: Vec<_> =
+                    Option::from(meta.pre_token_balances.clone()).unwrap_or_default();
+                let post_balances: Vec<_> =
+                    Option::from(meta.post_token_balances.clone()).unwrap_or_default();
+                token_amount(&post_balances, &recipient, &mint).saturating_sub(token_amount(
+                    &pre_balances,
+                    &recipient,
+                    &mint,
+                ))
+            }
+            None => {
+                let index = account_keys
+                    .iter()
+                    .position(|key| key == &self.recipient)
+                    .ok_or_else(|| {
+                        mismatch(format!(
+                            "the recipient `{}` isn't in the transaction",
+                            self.recipient
+                        ))
+                    })?;
+                let pre = meta.pre_balances.get(index).copied().unwrap_or_default();
+                let post = meta.post_balances.get(index).copied().unwrap_or_default();
+                post.saturating_sub(pre)
+            }
+        };
+        if received < expected {
+            return Err(mismatch(format!(
+                "the recipient received {received} units, {expected} units are requested"
+            )));
+        }
+        Ok(())
+    }
+
+    /// Returns the query parameters of the transfer request, in the order of the specification
+    fn query_pairs(&self) -> Vec<(&'static str, String)> {
+        let mut pairs = Vec::new();
+        if let Some(amount) = &self.amount {
+            pairs.push(("amount", amount.clone()));
+        }
+        if let Some(spl_token) = &self.spl_token {
+            pairs.push(("spl-token", spl_token.to_string()));
+        }
+        for reference in &self.references {
+            pairs.push(("reference", reference.to_string()));
+        }
+        for (key, value) in [
+            ("label", &self.label),
+            ("message", &self.message),
+            ("memo", &self.memo),
+        ] {
+            if let Some(value) = value {
+                pairs.push((key, value.clone()));
+            }
+        }
+        pairs
+    }
+
+    /// Returns the `solana:` URI of the transfer request
+    pub fn to_uri(&self) -> Url {
+        // SAFETY: the scheme and the base58 address are valid URL parts
+        let mut uri = Url::parse(&format!("{SOLANA_PAY_SCHEME}:{}", self.recipient)).unwrap();
+        let query = self
+            .query_pairs()
+            .into_iter()
+            .map(|(key, value)| {
+                // The spaces are encoded as `%20`, the literal `+` is encoded as `%2B`
+                let value: String = form_urlencoded::byte_serialize(value.as_bytes()).collect();
+                format!("{key}={}", value.replace('+', "%20"))
+            })
+            .collect::<Vec<_>>()
+            .join("&");
+        if !query.is_empty() {
+            uri.set_query(Some(&query));
+        }
+        uri
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn merchant_transaction() {
+        let payer = Pubkey::new_unique();
+        let instruction = system_instruction::transfer(&payer, &Pubkey::new_unique(), 1_000);
+        let transaction = Transaction::new_unsigned(solana_sdk::message::Message::new(
+            &[instruction],
+            Some(&payer),
+        ));
+        let encode =
+            |transaction: &Transaction| base64::encode(bincode::serialize(transaction).unwrap());
+        assert!(decode_transaction(&encode(&transaction)).is_ok());
+        // A signature is missing, signing it would index out of bounds
+        let mut missing = transaction.clone();
+        missing.signatures.clear();
+        assert!(decode_transaction(&encode(&missing)).is_err());
+        let mut extra = transaction;
+        extra.signatures.push(Signature::default());
+        assert!(decode_transaction(&encode(&extra)).is_err());
+        assert!(decode_transaction("not base64!").is_err());
+    }
+
+    #[test]
+    fn amount_format() {
+        assert_eq!(format_amount(1.0, SOL_DECIMALS).unwrap(), "1");
+        assert_eq!(format_amount(0.01, SOL_DECIMALS).unwrap(), "0.01");
+        assert_eq!(format_amount(1e-7, SOL_DECIMALS).unwrap(), "0.0000001");
+        assert_eq!(format_amount(12.3456789, 6).unwrap(), "12.345679");
+        assert!(format_amount(0.0, SOL_DECIMALS).is_err());
+        assert!(format_amount(-1.0, SOL_DECIMALS).is_err());
+        assert!(format_amount(1e-7, 6).is_err());
+    }
+
+    #[test]
+    fn amount_to_smallest_units() {
+        assert_eq!(amount_to_units("1", SOL_DECIMALS).unwrap(), 1_000_000_000);
+        assert_eq!(amount_to_units("0.01", SOL_DECIMALS).unwrap(), 10_000_000);
+        assert_eq!(amount_to_units("12.345679", 6).unwrap(), 12_345_679);
+        assert_eq!(amount_to_units("0.000000001", SOL_DECIMALS).unwrap(), 1);
+        assert!(amount_to_units("0.0000001", 6).is_err());
+        assert!(amount_to_units(".5", SOL_DECIMALS).is_err());
+        assert!(amount_to_units("-1", SOL_DECIMALS).is_err());
+        assert!(amount_to_units("1e3", SOL_DECIMALS).is_err());
+    }
+
+    #[test]
+    fn transfer_request_uri() {
+        let recipient: Pubkey = "mvines9iiHiQTysrwkJjGf2gb9Ex9jXJX8ns3qwf2kN"
+            .parse()
+            .unwrap();
+        let reference: Pubkey = "82ZJ7nbGpixjeDCmEhUcmwXYfvurzAgGdtSMuHnUgyny"
+            .parse()
+            .unwrap();
+        assert_eq!(
+            TransferRequest {
+                recipient,
+                ..Default::default()
+            }
+            .to_uri()
+            .as_str(),
+            "solana:mvines9iiHiQTysrwkJjGf2gb9Ex9jXJX8ns3qwf2kN"
+        );
+        let request = TransferRequest {
+            recipient,
+            amount: Some("0.01".to_owned()),
+            spl_token: Some(
+                "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v"
+                    .parse()
+                    .unwrap(),
+            ),
+            references: vec![reference],
+            label: Some("Michael".to_owned()),
+            message: Some("Thanks for all the fish".to_owned()),
+            memo: Some("OrderId12345".to_owned()),
+        };
+        assert_eq!(
+            request.to_uri().as_str(),
+            "solana:mvines9iiHiQTysrwkJjGf2gb9Ex9jXJX8ns3qwf2kN?amount=0.01\
+             &spl-token=EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v\
+             &reference=82ZJ7nbGpixjeDCmEhUcmwXYfvurzAgGdtSMuHnUgyny\
+             &label=Michael&message=Thanks%20for%20all%20the%20fish&memo=OrderId12345"
+        );
+    }
+
+    #[test]
+    fn parse_transfer_request() {
+        let request = TransferRequest {
+            recipient: "mvines9iiHiQTysrwkJjGf2gb9Ex9jXJX8ns3qwf2kN"
+                .parse()
+                .unwrap(),
+            amount: Some("0.01".to_owned()),
+            spl_token: Some(Tokens::Usdc.mint()),
+            references: vec![
+                "82ZJ7nbGpixjeDCmEhUcmwXYfvurzAgGdtSMuHnUgyny"
+                    .parse()
+                    .unwrap(),
+                "7Tf3pF3uaGYWMfXRSKxXdoFFBmpzxyyFG1NUyTczCshP"
+                    .parse()
+                    .unwrap(),
+            ],
+            label: Some("Michael & Co".to_owned()),
+            message: Some("Thanks for all the fish +1".to_owned()),
+            memo: Some("OrderId12345".to_owned()),
+        };
+        assert_eq!(
+            PaymentRequest::parse(request.to_uri().as_str()).unwrap(),
+            PaymentRequest::Transfer(request)
+        );
+        assert_eq!(
+            PaymentRequest::parse(
+                "solana:mvines9iiHiQTysrwkJjGf2gb9Ex9jXJX8ns3qwf2kN?label=Michael&unknown=1"
+            )
+            .unwrap(),
+            PaymentRequest::Transfer(TransferRequest {
+                recipient: "mvines9iiHiQTysrwkJjGf2gb9Ex9jXJX8ns3qwf2kN"
+                    .parse()
+                    .unwrap(),
+                label: Some("Michael".to_owned()),
+                ..Default::default()
+            })
+        );
+        for invalid in [
+            "bitcoin:mvines9iiHiQTysrwkJjGf2gb9Ex9jXJX8ns3qwf2kN",
+            "solana:not-an-address",
+            "solana:mvines9iiHiQTysrwkJjGf2gb9Ex9jXJX8ns3qwf2kN?amount=1e3",
+            "solana:mvines9iiHiQTysrwkJjGf2gb9Ex9jXJX8ns3qwf2kN?amount=1&amount=2",
+            "solana:mvines9iiHiQTysrwkJjGf2gb9Ex9jXJX8ns3qwf2kN?spl-token=usdc",
+        ] {
+            assert!(PaymentRequest::parse(invalid).is_err(), "{invalid}");
+        }
+    }
+
+    #[test]
+    fn parse_transaction_request() {
+        let link: Url = "https://example.com/solana-pay?order=12345"
+            .parse()
+            .unwrap();
+        for uri in [
+            "solana:https://example.com/solana-pay?order=12345",
+            "solana:https%3A%2F%2Fexample.com%2Fsolana-pay%3Forder%3D12345",
+        ] {
+            assert_eq!(
+                PaymentRequest::parse(uri).unwrap(),
+                PaymentRequest::Transaction(TransactionRequest { link: link.clone() })
+            );
+        }
+        assert!(PaymentRequest::parse("solana:https%3A%2F%2F").is_err());
+
+        let mut request = TransactionRequest { link };
+        request
+            .set_endpoint(&"http://localhost:8080".parse().unwrap())
+            .unwrap();
+        assert_eq!(
+            request.link.as_str(),
+            "http://localhost:8080/solana-pay?order=12345"
+        );
+    }
+
+    #[test]
+    fn transfer_instructions() {
+        let payer: Pubkey = "7Tf3pF3uaGYWMfXRSKxXdoFFBmpzxyyFG1NUyTczCshP"
+            .parse()
+            .unwrap();
+        let reference: Pubkey = "82ZJ7nbGpixjeDCmEhUcmwXYfvurzAgGdtSMuHnUgyny"
+            .parse()
+            .unwrap();
+        let mut request = TransferRequest {
+            recipient: "mvines9iiHiQTysrwkJjGf2gb9Ex9jXJX8ns3qwf2kN"
+                .parse()
+                .unwrap(),
+            references: vec![reference],
+            memo: Some("OrderId12345".to_owned()),
+            ..Default::default()
+        };
+
+        let instructions = request.instructions(&payer, 10, SOL_DECIMALS).unwrap();
+        assert_eq!(instructions.len(), 2);
+        assert_eq!(instructions[0].program_id, spl_memo::id());
+        assert_eq!(instructions[0].data, b"OrderId12345");
+        assert_eq!(
+            instructions[1],
+            Instruction {
+                accounts: [
+                    system_instruction::transfer(&payer, &request.recipient, 10).accounts,
+                    vec![AccountMeta::new_readonly(reference, false)],
+                ]
+                .concat(),
+                ..system_instruction::transfer(&payer, &request.recipient, 10)
+            }
+        );
+
+        request.memo = None;
+        request.spl_token = Some(Tokens::Usdc.mint());
+        let instructions = request.instructions(&payer, 10, 6).unwrap();
+        assert_eq!(instructions.len(), 1);
+        let accounts: Vec<_> = instructions[0]
+            .accounts
+            .iter()
+            .map(|account| account.pubkey)
+            .collect();
+        assert_eq!(instructions[0].program_id, spl_token::id());
+        assert_eq!(
+            accounts,
+            [
+                get_associated_token_address(&payer, &Tokens::Usdc.mint()),
+                Tokens::Usdc.mint(),
+                get_associated_token_address(&request.recipient, &Tokens::Usdc.mint()),
+                payer,
+                reference,
+            ]
+        );
+    }
+}
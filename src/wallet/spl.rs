@@ -17,9 +17,10 @@
 use clap::ValueEnum;
 use name_variant::NamedVariant;
 use solana_client::rpc_request::TokenAccountsFilter;
+use solana_sdk::pubkey::Pubkey;
 
 /// The supported spl tokens
-#[derive(Debug, Clone, NamedVariant, ValueEnum)]
+#[derive(Debug, Clone, PartialEq, Eq, NamedVariant, ValueEnum)]
 pub enum Tokens {
     Usdc,
     Usdt,
@@ -31,9 +32,8 @@ impl Tokens {
     pub const fn name(&self) -> &'static str {
         self.variant_name()
     }
-    /// Returns the token mint address
-    pub fn mint_address(&self) -> TokenAccountsFilter {
-        use TokenAccountsFilter::Mint;
+    /// Returns the token mint
+    pub fn mint(&self) -> Pubkey {
         use Tokens::*;
         let mint_address = match &self {
             Usdc => "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v",
@@ -41,7 +41,25 @@ impl Tokens {
             Srm => "SRMuApVNdxXokk5GT7XD5cUUgXMBCoAz2LHeuAoKWRt",
         };
         // SAFETY: The mint addresses are valid
-        Mint(mint_address.parse().unwrap())
+        mint_address.parse().unwrap()
+    }
+
+    /// Returns the supported token of the given mint, `None` if it isn't supported
+    pub fn from_mint(mint: &Pubkey) -> Option<Self> {
+        Self::value_variants()
+            .iter()
+            .find(|token| &token.mint() == mint)
+            .cloned()
+    }
+
+    /// Returns the token mint address filter
+    pub fn mint_address(&self) -> TokenAccountsFilter {
+        TokenAccountsFilter::Mint(self.mint())
+    }
+
+    /// Returns the number of the decimals of the token
+    pub fn decimals(&self) -> usize {
+        6
     }
 
     /// Return the lamports per token
@@ -0,0 +1,450 @@
+// Solwalrs, A simple and easy to use CLI Solana wallet
+// Copyright (C) 2022  Solwalrs contributors <https://github.com/TheAwiteb/solwalrs/graphs/contributors>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/gpl-3.0.html>.
+
+use base58::FromBase58;
+use borsh::BorshDeserialize;
+use serde_json::Value;
+use solana_client::rpc_config::RpcTransactionConfig;
+use solana_sdk::{
+    commitment_config::CommitmentConfig,
+    compute_budget::{self, ComputeBudgetInstruction},
+    instruction::CompiledInstruction,
+    message::{v0::LoadedAddresses, AccountKeys},
+    pubkey::Pubkey,
+    signature::Signature,
+    transaction::VersionedTransaction,
+};
+use solana_transaction_status::{
+    option_serializer::OptionSerializer, parse_instruction, UiInstruction, UiTransactionEncoding,
+    UiTransactionStatusMeta, UiTransactionTokenBalance,
+};
+
+use super::Rpc;
+use crate::{
+    app::AppArgs,
+    errors::{Error as SolwalrsError, Result as SolwalrsResult},
+};
+
+/// A transaction to inspect, fetched from the cluster or decoded locally
+#[derive(Debug)]
+pub struct TransactionDetails {
+    /// The transaction itself
+    pub transaction: VersionedTransaction,
+    /// The status meta of the transaction, `None` if the transaction is decoded locally
+    pub meta: Option<UiTransactionStatusMeta>,
+    /// The slot of the transaction, `None` if the transaction is decoded locally
+    pub slot: Option<u64>,
+    /// The block time of the transaction, `None` if it's unknown
+    pub block_time: Option<i64>,
+    /// The addresses loaded from the address lookup tables
+    loaded_addresses: Option<LoadedAddresses>,
+}
+
+/// A decoded instruction of the transaction
+#[derive(Debug)]
+pub struct DecodedInstruction {
+    /// The program name, or the program id if the program is unknown
+    pub program: String,
+    /// The instruction type, e.g. `transfer`
+    pub instruction_type: String,
+    /// The instruction information, key and value
+    pub info: Vec<(String, String)>,
+}
+
+/// The token balance change of a token account in the transaction
+#[derive(Debug, PartialEq)]
+pub struct TokenBalanceChange {
+    /// The index of the token account in the account keys
+    pub account_index: u8,
+    /// The mint of the token
+    pub mint: String,
+    /// The owner of the token account, if it's known
+    pub owner: Option<String>,
+    /// The balance before the transaction
+    pub pre: f64,
+    /// The balance after the transaction
+    pub post: f64,
+    /// Whether the token account is closed by the transaction, it only has a pre balance
+    pub closed: bool,
+}
+
+impl TransactionDetails {
+    /// Fetch the transaction from the cluster using `getTransaction`
+    pub fn fetch(signature: &Signature, args: &AppArgs) -> SolwalrsResult<Self> {
+        crate::info!(args, "Fetching the transaction `{signature}`");
+        let encoded = Rpc::new(args)
+            .call(|client| {
+                client.get_transaction_with_config(
+                    signature,
+                    RpcTransactionConfig {
+                        encoding: Some(UiTransactionEncoding::Base64),
+                        commitment: Some(CommitmentConfig::confirmed()),
+                        max_supported_transaction_version: Some(0),
+                    },
+                )
+            })
+            .map_err(|err| {
+                SolwalrsError::RpcError(format!(
+                    "Error while getting the transaction `{signature}`: {err}"
+                ))
+            })?;
+        crate::info!(args, "Got the transaction: {encoded:?}");
+        let transaction = encoded.transaction.transaction.decode().ok_or_else(|| {
+            SolwalrsError::Other(format!("Failed to decode the transaction `{signature}`"))
+        })?;
+        let loaded_addresses = encoded
+            .transaction
+            .meta
+            .as_ref()
+            .and_then(|meta| Option::from(meta.loaded_addresses.clone()))
+            .map(
+                |addresses: solana_transaction_status::UiLoadedAddresses| LoadedAddresses {
+                    writable: addresses
+                        .writable
+                        .iter()
+                        .filter_map(|addr| addr.parse().ok())
+                        .collect(),
+                    readonly: addresses
+                        .readonly
+                        .iter()
+                        .filter_map(|addr| addr.parse().ok())
+                        .collect(),
+                },
+            );
+        Ok(Self {
+            transaction,
+            meta: encoded.transaction.meta,
+            slot: Some(encoded.slot),
+            block_time: encoded.block_time,
+            loaded_addresses,
+        })
+    }
+
+    /// Decode a base64 encoded transaction locally, without sending any request
+    pub fn decode(base64_transaction: &str, args: &AppArgs) -> SolwalrsResult<Self> {
+        crate::info!(args, "Decoding the transaction locally");
+        let bytes = base64::decode(base64_transaction.trim())
+            .map_err(|err| SolwalrsError::Other(format!("Failed to decode base64: {err}")))?;
+        let transaction: VersionedTransaction = bincode::deserialize(&bytes)
+            .map_err(|err| SolwalrsError::Other(format!("Invalid transaction: {err}")))?;
+        crate::info!(args, "Transaction decoded successfully: {transaction:?}");
+        Ok(Self {
+            transaction,
+            meta: None,
+            slot: None,
+            block_time: None,
+            loaded_addresses: None,
+        })
+    }
+
+    /// Fetch the transaction if the input is a signature, otherwise decode it as base64 transaction
+    pub fn from_input(input: &str, args: &AppArgs) -> SolwalrsResult<Self> {
+        match input.parse::<Signature>() {
+            Ok(signature) => Self::fetch(&signature, args),
+            Err(_) => Self::decode(input, args),
+        }
+    }
+
+    /// Returns the first signature of the transaction
+    pub fn signature(&self) -> Option<&Signature> {
+        self.transaction.signatures.first()
+    }
+
+    /// Returns the account keys of the transaction, including the loaded addresses
+    pub fn account_keys(&self) -> AccountKeys<'_> {
+        AccountKeys::new(
+            self.transaction.message.static_account_keys(),
+            self.loaded_addresses.as_ref(),
+        )
+    }
+
+    /// Whether the account at the given index is a signer
+    pub fn is_signer(&self, index: usize) -> bool {
+        self.transaction.message.is_signer(index)
+    }
+
+    /// Whether the account at the given index is writable
+    pub fn is_writable(&self, index: usize) -> bool {
+        self.transaction.message.is_maybe_writable(index)
+    }
+
+    /// Returns the token balance changes of the transaction, ordered by the account index.
+    /// Returns an empty vector if the transaction is decoded locally
+    pub fn token_balance_changes(&self) -> Vec<TokenBalanceChange> {
+        match &self.meta {
+            Some(UiTransactionStatusMeta {
+                pre_token_balances: OptionSerializer::Some(pre),
+                post_token_balances: OptionSerializer::Some(post),
+                ..
+            }) => token_balance_changes(pre, post),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Decode the top-level instructions of the transaction
+    pub fn instructions(&self) -> Vec<DecodedInstruction> {
+        let account_keys = self.account_keys();
+        self.transaction
+            .message
+            .instructions()
+            .iter()
+            .map(|instruction| decode_instruction(instruction, &account_keys))
+            .collect()
+    }
+
+    /// Decode the inner instructions of the transaction, the key is the index of the parent instruction.
+    /// Returns an empty vector if the transaction is decoded locally
+    pub fn inner_instructions(&self) -> Vec<(u8, Vec<DecodedInstruction>)> {
+        let account_keys = self.account_keys();
+        self.meta
+            .as_ref()
+            .and_then(|meta| Option::<&Vec<_>>::from(meta.inner_instructions.as_ref()))
+            .map(|inner_instructions| {
+                inner_instructions
+                    .iter()
+                    .map(|inner| {
+                        (
+                            inner.index,
+                            inner
+                                .instructions
+                                .iter()
+                                .filter_map(|instruction| match instruction {
+                                    UiInstruction::Compiled(compiled) => {
+                                        Some(CompiledInstruction {
+                                            program_id_index: compiled.program_id_index,
+                                            accounts: compiled.accounts.clone(),
+                                            data: compiled.data.from_base58().unwrap_or_default(),
+                                        })
+                                    }
+                                    // We request the transaction as base64, so it will never be parsed
+                                    UiInstruction::Parsed(_) => None,
+                                })
+                                .map(|instruction| decode_instruction(&instruction, &account_keys))
+                                .collect(),
+                        )
+                    })
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+}
+
+/// Decode the compute budget instruction, the parser of `solana-transaction-status` doesn't support it
+fn decode_compute_budget(data: &[u8]) -> Option<(String, Vec<(String, String)>)> {
+    use ComputeBudgetInstruction::*;
+    let instruction = ComputeBudgetInstruction::try_from_slice(data).ok()?;
+    Some(match instruction {
+        RequestUnitsDeprecated {
+            units,
+            additional_fee,
+        } => (
+            "requestUnits".to_owned(),
+            vec![
+                ("units".to_owned(), units.to_string()),
+                ("additionalFee".to_owned(), additional_fee.to_string()),
+            ],
+        ),
+        RequestHeapFrame(bytes) => (
+            "requestHeapFrame".to_owned(),
+            vec![("bytes".to_owned(), bytes.to_string())],
+        ),
+        SetComputeUnitLimit(units) => (
+            "setComputeUnitLimit".to_owned(),
+            vec![("units".to_owned(), units.to_string())],
+        ),
+        SetComputeUnitPrice(micro_lamports) => (
+            "setComputeUnitPrice".to_owned(),
+            vec![("microLamports".to_owned(), micro_lamports.to_string())],
+        ),
+    })
+}
+
+/// Flatten the parsed json information into key and value, nested keys are joined with `.`
+fn flatten_info(prefix: &str, value: &Value, info: &mut Vec<(String, String)>) {
+    match value {
+        Value::Object(map) => {
+            for (key, value) in map {
+                let key = if prefix.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{prefix}.{key}")
+                };
+                flatten_info(&key, value, info);
+            }
+        }
+        Value::String(value) => info.push((prefix.to_owned(), value.clone())),
+        Value::Null => {}
+        value => info.push((prefix.to_owned(), value.to_string())),
+    }
+}
+
+/// Decode the given instruction, unknown instructions will be returned with the raw accounts and data
+fn decode_instruction(
+    instruction: &CompiledInstruction,
+    account_keys: &AccountKeys,
+) -> DecodedInstruction {
+    let program_id = account_keys
+        .get(instruction.program_id_index as usize)
+        .copied()
+        .unwrap_or_default();
+
+    if program_id == compute_budget::id() {
+        if let Some((instruction_type, info)) = decode_compute_budget(&instruction.data) {
+            return DecodedInstruction {
+                program: "compute-budget".to_owned(),
+                instruction_type,
+                info,
+            };
+        }
+    }
+
+    if let Ok(parsed) = parse_instruction::parse(&program_id, instruction, account_keys) {
+        let mut info = Vec::new();
+        let instruction_type = match &parsed.parsed {
+            // The memo program returns the memo as a string
+            Value::String(memo) => {
+                info.push(("memo".to_owned(), memo.clone()));
+                "memo".to_owned()
+            }
+            parsed_value => {
+                flatten_info("", &parsed_value["info"], &mut info);
+                parsed_value["type"]
+                    .as_str()
+                    .unwrap_or("unknown")
+                    .to_owned()
+            }
+        };
+        return DecodedInstruction {
+            program: parsed.program,
+            instruction_type,
+            info,
+        };
+    }
+
+    // Unknown program, show the accounts and the raw data
+    let mut info: Vec<_> = instruction
+        .accounts
+        .iter()
+        .enumerate()
+        .map(|(idx, account_idx)| {
+            (
+                format!("account{idx}"),
+                account_keys
+                    .get(*account_idx as usize)
+                    .map(Pubkey::to_string)
+                    .unwrap_or_else(|| format!("<unknown account #{account_idx}>")),
+            )
+        })
+        .collect();
+    info.push(("data".to_owned(), base64::encode(&instruction.data)));
+    DecodedInstruction {
+        program: program_id.to_string(),
+        instruction_type: "unknown".to_owned(),
+        info,
+    }
+}
+
+/// Merge the pre and post token balances, the accounts closed by the transaction only have a pre balance
+fn token_balance_changes(
+    pre_balances: &[UiTransactionTokenBalance],
+    post_balances: &[UiTransactionTokenBalance],
+) -> Vec<TokenBalanceChange> {
+    let mut accounts: Vec<(u8, &str)> = post_balances
+        .iter()
+        .chain(pre_balances)
+        .map(|balance| (balance.account_index, balance.mint.as_str()))
+        .collect();
+    accounts.sort_by_key(|(account_index, _)| *account_index);
+    accounts.dedup();
+    accounts
+        .into_iter()
+        .map(|(account_index, mint)| {
+            let find = |balances: &[UiTransactionTokenBalance]| {
+                balances
+                    .iter()
+                    .find(|balance| balance.account_index == account_index && balance.mint == mint)
+                    .cloned()
+            };
+            let (pre, post) = (find(pre_balances), find(post_balances));
+            let amount = |balance: &Option<UiTransactionTokenBalance>| {
+                balance
+                    .as_ref()
+                    .and_then(|balance| balance.ui_token_amount.ui_amount)
+                    .unwrap_or_default()
+            };
+            TokenBalanceChange {
+                account_index,
+                mint: mint.to_owned(),
+                owner: post
+                    .as_ref()
+                    .or(pre.as_ref())
+                    .and_then(|balance| Option::<&String>::from(balance.owner.as_ref()))
+                    .cloned(),
+                pre: amount(&pre),
+                post: amount(&post),
+                closed: post.is_none(),
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use solana_account_decoder::parse_token::UiTokenAmount;
+
+    use super::*;
+
+    const MINT: &str = "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v";
+
+    fn balance(account_index: u8, ui_amount: f64) -> UiTransactionTokenBalance {
+        UiTransactionTokenBalance {
+            account_index,
+            mint: MINT.to_owned(),
+            ui_token_amount: UiTokenAmount {
+                ui_amount: Some(ui_amount),
+                decimals: 6,
+                amount: ((ui_amount * 1e6) as u64).to_string(),
+                ui_amount_string: ui_amount.to_string(),
+            },
+            owner: OptionSerializer::Some(format!("owner{account_index}")),
+            program_id: OptionSerializer::None,
+        }
+    }
+
+    #[test]
+    fn closed_token_accounts() {
+        let changes = token_balance_changes(&[balance(3, 1.5), balance(1, 2.0)], &[]);
+        assert_eq!(
+            changes
+                .iter()
+                .map(|change| (change.account_index, change.pre, change.post, change.closed))
+                .collect::<Vec<_>>(),
+            [(1, 2.0, 0.0, true), (3, 1.5, 0.0, true)]
+        );
+        assert_eq!(changes[0].owner.as_deref(), Some("owner1"));
+
+        let changes =
+            token_balance_changes(&[balance(1, 2.0)], &[balance(1, 0.5), balance(2, 1.5)]);
+        assert_eq!(
+            changes
+                .iter()
+                .map(|change| (change.account_index, change.pre, change.post, change.closed))
+                .collect::<Vec<_>>(),
+            [(1, 2.0, 0.5, false), (2, 0.0, 1.5, false)]
+        );
+        assert!(token_balance_changes(&[], &[]).is_empty());
+    }
+}
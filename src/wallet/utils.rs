@@ -16,6 +16,7 @@
 
 use std::{
     fs,
+    io::Write,
     path::{Path, PathBuf},
 };
 
@@ -27,9 +28,10 @@ use base58::ToBase58;
 use ed25519_dalek::PublicKey;
 use fernet::Fernet;
 use solana_account_decoder::UiAccountData;
-use solana_client::rpc_client::RpcClient;
+use solana_sdk::pubkey::Pubkey;
+use zeroize::Zeroizing;
 
-use super::Tokens;
+use super::{rpc_endpoints, Revisions, Rpc, Tokens, WalletLock};
 
 /// Returns the project directories
 pub fn project_dirs() -> SolwalrsResult<directories::ProjectDirs> {
@@ -80,6 +82,64 @@ pub fn app_file_path(args: &AppArgs) -> SolwalrsResult<std::path::PathBuf> {
     }
 }
 
+/// Returns the absolute app data file, used as a stable key of the wallet file
+pub fn canonical_app_file_path(args: &AppArgs) -> SolwalrsResult<PathBuf> {
+    let app_file = app_file_path(args)?;
+    // The parent directory exists, `app_file_path` creates it
+    Ok(app_file
+        .parent()
+        .and_then(|parent| parent.canonicalize().ok())
+        .zip(app_file.file_name())
+        .map(|(parent, name)| parent.join(name))
+        .unwrap_or(app_file))
+}
+
+/// Returns a path next to the given file with the extension appended, e.g. `solwalrs.json.bak`
+pub fn sibling_path(path: &Path, extension: &str) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".");
+    name.push(extension);
+    path.with_file_name(name)
+}
+
+/// Write the contents to the file atomically, the contents are written to a temporary file in the
+/// same directory and synced, then the temporary file is renamed over the file.
+/// So the file is either the old one or the new one, never a truncated one
+pub fn write_atomically(path: &Path, contents: &[u8]) -> SolwalrsResult<()> {
+    let mut temp_name = std::ffi::OsString::from(".");
+    temp_name.push(path.file_name().unwrap_or_default());
+    temp_name.push(format!(".{}.tmp", std::process::id()));
+    let temp_path = path.with_file_name(temp_name);
+
+    let write_temp = || -> std::io::Result<()> {
+        let mut options = fs::OpenOptions::new();
+        options.write(true).create(true).truncate(true);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::OpenOptionsExt;
+            options.mode(0o600);
+        }
+        let mut file = options.open(&temp_path)?;
+        file.write_all(contents)?;
+        file.sync_all()?;
+        fs::rename(&temp_path, path)?;
+        // Sync the directory, so the rename itself survives a crash
+        #[cfg(unix)]
+        if let Some(parent) = path
+            .parent()
+            .filter(|parent| !parent.as_os_str().is_empty())
+        {
+            fs::File::open(parent)?.sync_all()?;
+        }
+        Ok(())
+    };
+
+    write_temp().map_err(|err| {
+        let _ = fs::remove_file(&temp_path);
+        SolwalrsError::IO(format!("Failed to write `{}`: {err}", path.display()))
+    })
+}
+
 /// Returns the app cache file
 pub fn app_cache_file_path() -> SolwalrsResult<std::path::PathBuf> {
     app_cache_dir().map(|cache| cache.join("solwalrs.cache"))
@@ -89,10 +149,13 @@ pub fn app_cache_file_path() -> SolwalrsResult<std::path::PathBuf> {
 pub fn clean_wallet(args: &AppArgs) -> SolwalrsResult<()> {
     crate::info!(args, "Trying to clean the wallet");
     let app_file = app_file_path(args)?;
+    let _lock = WalletLock::acquire(args)?;
     crate::info!(args, "Removing the wallet file");
-    std::fs::remove_file(app_file)
+    std::fs::remove_file(&app_file)
         .map_err(|err| SolwalrsError::Wallet(format!("Failed to remove wallet file: {}", err)))?;
     crate::info!(args, "Wallet file removed successfully");
+    remove_wallet_backup(&app_file, args)?;
+    Revisions::forget(args)?;
     let cache_file = app_cache_file_path()?;
     crate::info!(args, "Removing the cache file");
     std::fs::remove_file(cache_file)
@@ -101,28 +164,30 @@ pub fn clean_wallet(args: &AppArgs) -> SolwalrsResult<()> {
     Ok(())
 }
 
-/// Returns the rpc url
-pub fn rpc_url(args: &AppArgs) -> SolwalrsResult<String> {
-    let url = format!(
-        "{}{}",
-        args.rpc.to_string().trim_end_matches('/'),
-        args.rpc
-            .port()
-            .map(|port| format!(":{}", port))
-            .unwrap_or_default()
-    );
-    Ok(url)
+/// Remove the backup of the removed wallet file, if it exists.
+/// The lock file is kept, removing it while another process waits for it will break the lock
+pub fn remove_wallet_backup(app_file: &Path, args: &AppArgs) -> SolwalrsResult<()> {
+    let backup = sibling_path(app_file, "bak");
+    if backup.exists() {
+        crate::info!(args, "Removing the wallet backup `{}`", backup.display());
+        fs::remove_file(&backup).map_err(|err| {
+            SolwalrsError::Wallet(format!("Failed to remove the wallet backup: {err}"))
+        })?;
+    }
+    Ok(())
 }
 
-/// Returns the RPC client, if the `--rpc` flag is not set, it will use the default RPC client.
-/// The default RPC client is `https://api.mainnet-beta.solana.com`
-pub fn rpc_client(args: &AppArgs) -> SolwalrsResult<RpcClient> {
-    Ok(RpcClient::new(rpc_url(args)?))
+/// Returns the rpc url, the first endpoint of the `--rpc` flags
+pub fn rpc_url(args: &AppArgs) -> SolwalrsResult<String> {
+    rpc_endpoints(args)
+        .into_iter()
+        .next()
+        .ok_or_else(|| SolwalrsError::Other("No RPC endpoint is set".to_owned()))
 }
 
 /// Returns the SPL balance of the given public key
 pub fn spl_balance(args: &AppArgs, public_key: &PublicKey, token: &Tokens) -> SolwalrsResult<u64> {
-    let client = rpc_client(args)?;
+    let rpc = Rpc::new(args);
     let short_pubk = short_public_key(public_key);
     let pubk = public_key.as_bytes().to_base58();
     let token_name = token.name();
@@ -132,8 +197,10 @@ pub fn spl_balance(args: &AppArgs, public_key: &PublicKey, token: &Tokens) -> So
     );
 
     // SAFETY: This is safe because we are sure that the public key is valid
-    match client
-        .get_token_accounts_by_owner(&pubk.parse().unwrap(), token.mint_address())
+    match rpc
+        .call(|client| {
+            client.get_token_accounts_by_owner(&pubk.parse().unwrap(), token.mint_address())
+        })
         .map_err(|err| SolwalrsError::RpcError(err.to_string()))?
         .first()
         .ok_or_else(|| {
@@ -178,11 +245,11 @@ pub fn request_airdrop(
         amount,
         short_public_key(public_key)
     );
-    let client = rpc_client(args)?;
+    let rpc = Rpc::new(args);
     let pubk = public_key.as_bytes().to_base58();
     // SAFETY: This is safe because we are sure that the public key is valid
-    let signature = client
-        .request_airdrop(&pubk.parse().unwrap(), amount)
+    let signature = rpc
+        .call(|client| client.request_airdrop(&pubk.parse().unwrap(), amount))
         .map_err(|err| {
             SolwalrsError::RpcError(format!(
                 "Error while requesting an airdrop of {} lamports to the keypair `{}`: {err}",
@@ -199,29 +266,6 @@ pub fn request_airdrop(
     Ok(signature.to_string())
 }
 
-/// Confirm the given signature, if the signature is not confirmed, it will wait until it is confirmed
-pub fn confirm_signature(args: &AppArgs, signature: &str) -> SolwalrsResult<()> {
-    crate::info!(args, "Confirming the signature `{signature}`");
-    let client = rpc_client(args)?;
-    loop {
-        // SAFETY: This is safe because we are sure that the signature is valid
-        let status = client
-            .confirm_transaction(&signature.parse().unwrap())
-            .map_err(|err| {
-                SolwalrsError::RpcError(format!(
-                    "Error while getting the status of the airdrop singature `{signature}`: {err}"
-                ))
-            })?;
-        if status {
-            break;
-        }
-        crate::info!(args, "Waiting for the airdrop to be confirmed...");
-        std::thread::sleep(std::time::Duration::from_secs(3));
-    }
-    crate::info!(args, "Signature `{signature}` confirmed successfully");
-    Ok(())
-}
-
 /// Returns the `solana.fm` rpc parameters
 pub fn rpc_params(args: &AppArgs) -> SolwalrsResult<String> {
     let rpc = rpc_url(args)?;
@@ -238,12 +282,11 @@ pub fn transaction_url(signature: &str, args: &AppArgs) -> SolwalrsResult<String
     Ok(format!("https://solana.fm//tx/{signature}?{params}"))
 }
 
-/// Returns the `solana.fm` url of the given public key
-pub fn transactions_url(public_key: &PublicKey, args: &AppArgs) -> SolwalrsResult<String> {
+/// Returns the `solana.fm` url of the given address
+pub fn transactions_url(address: &Pubkey, args: &AppArgs) -> SolwalrsResult<String> {
     let params = rpc_params(args)?;
     Ok(format!(
-        "https://solana.fm/address/{}/transfers?{params}&mode=lite",
-        public_key.as_bytes().to_base58()
+        "https://solana.fm/address/{address}/transfers?{params}&mode=lite"
     ))
 }
 
@@ -254,21 +297,23 @@ pub fn sol_balance(args: &AppArgs, public_key: &PublicKey) -> SolwalrsResult<u64
         "Getting the balance of the keypair `{}`",
         short_public_key(public_key)
     );
-    let client = rpc_client(args)?;
+    let rpc = Rpc::new(args);
     let pubk = public_key.as_bytes().to_base58();
     // SAFETY: This is safe because we are sure that the public key is valid
-    client.get_balance(&pubk.parse().unwrap()).map_err(|err| {
-        SolwalrsError::RpcError(format!(
-            "Error while getting the balance of the keypair `{}`: {err}",
-            short_public_key(public_key)
-        ))
-    })
+    rpc.call(|client| client.get_balance(&pubk.parse().unwrap()))
+        .map_err(|err| {
+            SolwalrsError::RpcError(format!(
+                "Error while getting the balance of the keypair `{}`: {err}",
+                short_public_key(public_key)
+            ))
+        })
 }
 
 /// Create a fernet by the given key, using it to encrypt and decrypt.
 /// The key must be 32 bytes long.
 pub fn get_fernet(key: &[u8]) -> SolwalrsResult<Fernet> {
-    let encoded_key = base64::encode(key);
+    // Fernet decodes the key as URL-safe base64
+    let encoded_key = base64::encode(key).replace('+', "-").replace('/', "_");
     Fernet::new(&encoded_key).ok_or_else(|| {
         SolwalrsError::InvalidPassword("The password is not 32 bytes long".to_owned())
     })
@@ -281,17 +326,30 @@ pub fn encrypt(password: &[u8], plaintext: &[u8]) -> SolwalrsResult<String> {
     Ok(fernet.encrypt(plaintext))
 }
 
-/// Decrypt the given ciphertext with the given password.
+/// Decrypt the given ciphertext with the given password, the plaintext is zeroized on drop.
 /// The password must be 32 bytes long. will return `Error::InvalidPassword` if the password is not 32 bytes long.
 /// Will return `Error::InvalidPassword` if the password is not correct.
-pub fn decrypt(password: &[u8], ciphertext: &str) -> SolwalrsResult<String> {
+pub fn decrypt(password: &[u8], ciphertext: &str) -> SolwalrsResult<Zeroizing<Vec<u8>>> {
     let fernet = get_fernet(password)?;
     fernet
         .decrypt(ciphertext)
-        .map(|x| String::from_utf8_lossy(&x).to_string())
+        .map(Zeroizing::new)
         .map_err(|_| SolwalrsError::InvalidPassword("The password is not correct".to_owned()))
 }
 
+/// Decrypt the given ciphertext as UTF-8 text, the plaintext is moved (not copied) into the returned string.
+/// Will return `Error::Wallet` if the plaintext is not valid UTF-8
+pub fn decrypt_string(password: &[u8], ciphertext: &str) -> SolwalrsResult<Zeroizing<String>> {
+    let mut plaintext = decrypt(password, ciphertext)?;
+    String::from_utf8(std::mem::take(&mut *plaintext))
+        .map(Zeroizing::new)
+        .map_err(|err| {
+            // Wipe the invalid plaintext before dropping it
+            drop(Zeroizing::new(err.into_bytes()));
+            SolwalrsError::Wallet("The decrypted data is not valid UTF-8".to_owned())
+        })
+}
+
 /// Shorten the given public key, by replacing the middle with `...`. take the first 4 and last 4 characters.
 /// returned string will be base58 of the public key.
 pub fn short_public_key(public_key: &PublicKey) -> String {
@@ -300,8 +358,8 @@ pub fn short_public_key(public_key: &PublicKey) -> String {
     public_key
 }
 
-/// Create a rows for the tables
-fn create_rows(header: Vec<&str>, rows: Vec<Vec<&str>>) -> Vec<Vec<String>> {
+/// Create a rows for the tables, the rows are zeroized on drop because they may contain secrets
+fn create_rows(header: Vec<&str>, rows: Vec<Vec<&str>>) -> Zeroizing<Vec<Vec<String>>> {
     // Check if the number of columns in the header and rows are the same
     if !rows.iter().all(|row| row.len() == header.len()) {
         panic!("The number of columns in the header and rows must be the same");
@@ -313,7 +371,8 @@ fn create_rows(header: Vec<&str>, rows: Vec<Vec<&str>>) -> Vec<Vec<String>> {
                 .map(|(idx, column)| format!("{}: {column}", header[idx],))
                 .collect::<Vec<_>>()
         })
-        .collect()
+        .collect::<Vec<_>>()
+        .into()
 }
 
 /// Create a table with the given header and rows, table will be vertical
@@ -329,7 +388,7 @@ pub fn print_table(header: Vec<&str>, rows: Vec<Vec<&str>>) {
     // The divider of the table
     let divider = format!("+{:-<1$}+", "", max_len + 2);
     // Print the table
-    for row in rows {
+    for row in rows.iter() {
         println!("{}", divider);
         for column in row {
             // pritn the column
@@ -348,7 +407,7 @@ pub fn print_table(header: Vec<&str>, rows: Vec<Vec<&str>>) {
     // The divider of the table
     let divider = format!("+={:-<1$}=+", "", 10);
     // Print the table
-    for row in rows {
+    for row in rows.iter() {
         println!("{}", divider);
         for column in row {
             println!("- {}", column)
@@ -356,3 +415,45 @@ pub fn print_table(header: Vec<&str>, rows: Vec<Vec<&str>>) {
     }
     println!("{}", divider);
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PASSWORD: &[u8] = b"0123456789abcdef0123456789abcdef";
+
+    #[test]
+    fn decrypt_returns_zeroizing_plaintext() {
+        let ciphertext = encrypt(PASSWORD, b"secret").unwrap();
+        let plaintext: Zeroizing<Vec<u8>> = decrypt(PASSWORD, &ciphertext).unwrap();
+        assert_eq!(plaintext.as_slice(), b"secret");
+        let plaintext: Zeroizing<String> = decrypt_string(PASSWORD, &ciphertext).unwrap();
+        assert_eq!(plaintext.as_str(), "secret");
+    }
+
+    #[test]
+    fn decrypt_string_rejects_invalid_utf8() {
+        // The plaintext was converted lossily before, now it's an error
+        let ciphertext = encrypt(PASSWORD, &[0xff, 0xfe, 0xfd]).unwrap();
+        assert!(matches!(
+            decrypt_string(PASSWORD, &ciphertext),
+            Err(SolwalrsError::Wallet(_))
+        ));
+    }
+
+    #[test]
+    fn decrypt_with_wrong_password() {
+        let ciphertext = encrypt(PASSWORD, b"secret").unwrap();
+        assert!(matches!(
+            decrypt(b"fedcba9876543210fedcba9876543210", &ciphertext),
+            Err(SolwalrsError::InvalidPassword(_))
+        ));
+    }
+
+    #[test]
+    fn table_rows_are_zeroizing() {
+        let rows: Zeroizing<Vec<Vec<String>>> =
+            create_rows(vec!["Private Key"], vec![vec!["secret"]]);
+        assert_eq!(rows[0][0], "Private Key: secret");
+    }
+}